@@ -0,0 +1,255 @@
+use std::{cell::RefCell, rc::Rc};
+
+use js_sys::Function;
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::client::{connection::NetworkQuality, error::ClientError, scope::navigator, subscription::Subscription};
+
+/// A loading tier, e.g. `"full"`, `"reduced"` or `"text-only"`, and the
+/// network conditions it needs. Every threshold is optional, and thresholds
+/// the browser can't measure are treated as met.
+///
+/// ## Attributes
+/// `name`: `string`
+///
+/// `minDownlink`: `number`, in megabits per second
+///
+/// `maxRtt`: `number`, in milliseconds
+///
+/// `minEffectiveType`: "slow-2g" | "2g" | "3g" | "4g"
+///
+/// `allowSaveData`: `boolean`, whether users with Save-Data enabled can get
+/// this tier. Defaults to `true`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tier {
+    pub name: String,
+    pub min_downlink: Option<f64>,
+    pub max_rtt: Option<f64>,
+    pub min_effective_type: Option<String>,
+    #[serde(default = "allow_save_data_default")]
+    pub allow_save_data: bool,
+}
+
+fn allow_save_data_default() -> bool {
+    true
+}
+
+impl Tier {
+    /// Whether the network conditions in `quality` are good enough for this tier.
+    pub fn accepts(&self, quality: &NetworkQuality) -> bool {
+        let downlink = match (self.min_downlink, quality.downlink) {
+            (Some(min), Some(downlink)) => downlink >= min,
+            _ => true,
+        };
+        let rtt = match (self.max_rtt, quality.rtt) {
+            (Some(max), Some(rtt)) => rtt <= max,
+            _ => true,
+        };
+        let effective_type = match (
+            self.min_effective_type.as_deref().and_then(effective_type_rank),
+            quality.effective_type.as_deref().and_then(effective_type_rank),
+        ) {
+            (Some(min), Some(effective_type)) => effective_type >= min,
+            _ => true,
+        };
+        let save_data = self.allow_save_data || !quality.save_data;
+
+        downlink && rtt && effective_type && save_data
+    }
+}
+
+fn effective_type_rank(effective_type: &str) -> Option<u8> {
+    match effective_type {
+        "slow-2g" => Some(0),
+        "2g" => Some(1),
+        "3g" => Some(2),
+        "4g" => Some(3),
+        _ => None,
+    }
+}
+
+/// Picks the first tier `quality` is good enough for. Tiers are expected
+/// from best to worst; the last one is the fallback when none match.
+pub fn select_tier<'a>(tiers: &'a [Tier], quality: &NetworkQuality) -> Option<&'a Tier> {
+    tiers
+        .iter()
+        .find(|tier| tier.accepts(quality))
+        .or_else(|| tiers.last())
+}
+
+/// An adaptive-loading policy. Declare your tiers once, from best to worst,
+/// and let the policy tell you which one the user's network can afford.
+///
+/// ## Example
+///
+/// ```tsx
+/// const policy = new AdaptivePolicy([
+///   { name: "full", minEffectiveType: "4g", minDownlink: 5, allowSaveData: false },
+///   { name: "reduced", minEffectiveType: "3g" },
+///   { name: "text-only" },
+/// ]);
+///
+/// const [tier, setTier] = useState(policy.currentTier());
+/// useEffect(() => {
+///   const subscription = policy.onTierChange((tier) => setTier(tier));
+///   return () => subscription.unsubscribe();
+/// }, []);
+///
+/// <img src={`${src}?q=${tier === "full" ? 90 : 40}`} />
+/// <video autoPlay={tier === "full"} />
+/// ```
+///
+/// [Source](https://github.com/remix-pwa/remix-pwa-ecosystem/blob/main/crates/client/src/client/adaptive.rs)
+#[wasm_bindgen]
+pub struct AdaptivePolicy {
+    tiers: Rc<Vec<Tier>>,
+}
+
+#[wasm_bindgen]
+impl AdaptivePolicy {
+    #[wasm_bindgen(constructor)]
+    pub fn new(tiers: JsValue) -> Result<AdaptivePolicy, JsValue> {
+        let tiers: Vec<Tier> = serde_wasm_bindgen::from_value(tiers)?;
+        Ok(AdaptivePolicy::with_tiers(tiers)?)
+    }
+
+    /// Returns the name of the tier for the current network conditions.
+    /// Without the Network Information API, that's the first tier.
+    #[wasm_bindgen(js_name = "currentTier")]
+    pub fn current_tier(&self) -> String {
+        let quality = NetworkQuality::current().unwrap_or_default();
+        select_tier(&self.tiers, &quality)
+            .map(|tier| tier.name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Calls `callback(tier, quality)` whenever the network conditions change
+    /// enough to move the user to another tier. Browsers without the Network
+    /// Information API never change tier, so the callback is never called.
+    #[wasm_bindgen(js_name = "onTierChange")]
+    pub fn on_tier_change(&self, callback: Function) -> Result<Subscription, JsValue> {
+        Ok(self.on_tier_change_with(move |tier, quality| {
+            let quality = serde_wasm_bindgen::to_value(quality).unwrap_or(JsValue::UNDEFINED);
+            if let Err(e) = callback.call2(&JsValue::NULL, &JsValue::from_str(tier), &quality) {
+                log::error!("Error in tier change callback: {:?}", e);
+            }
+        })?)
+    }
+}
+
+impl AdaptivePolicy {
+    pub fn with_tiers(tiers: Vec<Tier>) -> Result<AdaptivePolicy, ClientError> {
+        if tiers.is_empty() {
+            return Err(ClientError::InvalidArgument(
+                "AdaptivePolicy needs at least one tier".to_string(),
+            ));
+        }
+
+        Ok(AdaptivePolicy { tiers: Rc::new(tiers) })
+    }
+
+    /// Calls `callback(tier, quality)` whenever the user moves to another
+    /// tier. Returns a no-op subscription without the Network Information API.
+    pub fn on_tier_change_with<F>(&self, mut callback: F) -> Result<Subscription, ClientError>
+    where
+        F: FnMut(&str, &NetworkQuality) + 'static,
+    {
+        let connection = match navigator()?.connection() {
+            Ok(connection) => connection,
+            Err(_) => return Ok(Subscription::noop()),
+        };
+
+        let tiers = self.tiers.clone();
+        let current = Rc::new(RefCell::new(self.current_tier()));
+        let target = connection.clone();
+
+        Ok(Subscription::listen(&target, "change", move |_| {
+            let quality = NetworkQuality::from_connection(&connection);
+            let tier = match select_tier(&tiers, &quality) {
+                Some(tier) if *current.borrow() != tier.name => tier.name.clone(),
+                _ => return,
+            };
+
+            current.replace(tier.clone());
+            callback(&tier, &quality);
+        })?)
+    }
+}
+
+#[cfg(test)]
+mod adaptive_tests {
+    use super::*;
+
+    fn tier(name: &str, min_downlink: Option<f64>, min_effective_type: Option<&str>, allow_save_data: bool) -> Tier {
+        Tier {
+            name: name.to_string(),
+            min_downlink,
+            max_rtt: None,
+            min_effective_type: min_effective_type.map(str::to_string),
+            allow_save_data,
+        }
+    }
+
+    fn tiers() -> Vec<Tier> {
+        vec![
+            tier("full", Some(5.0), Some("4g"), false),
+            tier("reduced", None, Some("3g"), true),
+            tier("text-only", None, None, true),
+        ]
+    }
+
+    fn quality(effective_type: &str, downlink: f64, save_data: bool) -> NetworkQuality {
+        NetworkQuality {
+            effective_type: Some(effective_type.to_string()),
+            downlink: Some(downlink),
+            save_data,
+            ..NetworkQuality::default()
+        }
+    }
+
+    fn select(quality: &NetworkQuality) -> String {
+        select_tier(&tiers(), quality).unwrap().name.clone()
+    }
+
+    #[test]
+    fn select_tier_test() {
+        assert_eq!(select(&quality("4g", 10.0, false)), "full");
+        assert_eq!(select(&quality("4g", 1.5, false)), "reduced");
+        assert_eq!(select(&quality("3g", 10.0, false)), "reduced");
+        assert_eq!(select(&quality("2g", 0.2, false)), "text-only");
+    }
+
+    #[test]
+    fn save_data_test() {
+        assert_eq!(select(&quality("4g", 10.0, true)), "reduced");
+    }
+
+    #[test]
+    fn unknown_quality_test() {
+        assert_eq!(select(&NetworkQuality::default()), "full");
+    }
+
+    #[test]
+    fn fallback_test() {
+        let tiers = vec![tier("full", Some(5.0), None, true), tier("lite", Some(1.0), None, true)];
+
+        assert_eq!(select_tier(&tiers, &quality("2g", 0.1, false)).unwrap().name, "lite");
+        assert!(select_tier(&[], &NetworkQuality::default()).is_none());
+    }
+
+    #[test]
+    fn max_rtt_test() {
+        let tier = Tier {
+            max_rtt: Some(100.0),
+            ..tier("fast", None, None, true)
+        };
+        let slow = NetworkQuality {
+            rtt: Some(250.0),
+            ..NetworkQuality::default()
+        };
+
+        assert!(!tier.accepts(&slow));
+    }
+}
@@ -0,0 +1,32 @@
+use js_sys::Function;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, JsCast};
+
+use crate::client::capabilities::get;
+
+/// A postal address, as picked on a payment sheet (`PaymentAddress`) or in
+/// the contact picker (`ContactAddress`). Both have the same fields.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/ContactAddress)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Address {
+    pub country: String,
+    pub address_line: Vec<String>,
+    pub region: String,
+    pub city: String,
+    pub dependent_locality: String,
+    pub postal_code: String,
+    pub sorting_code: String,
+    pub organization: String,
+    pub recipient: String,
+    pub phone: String,
+}
+
+impl Address {
+    /// Reads a `PaymentAddress` or `ContactAddress` through its `toJSON()`.
+    pub(crate) fn from_js(address: &JsValue) -> Option<Address> {
+        let to_json = get(address, "toJSON")?.dyn_into::<Function>().ok()?;
+        serde_wasm_bindgen::from_value(to_json.call0(address).ok()?).ok()
+    }
+}
@@ -0,0 +1,168 @@
+use js_sys::Reflect;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::client::scope::GlobalScope;
+
+/// Which of the APIs wrapped by this crate the current browser supports.
+///
+/// ## Example
+///
+/// ```tsx
+/// const { share, clipboardWrite } = getCapabilities();
+///
+/// {share ? <ShareButton /> : clipboardWrite ? <CopyLinkButton /> : null}
+/// ```
+///
+/// [Source](https://github.com/remix-pwa/remix-pwa-ecosystem/blob/main/crates/client/src/client/capabilities.rs)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    pub clipboard_read: bool,
+    pub clipboard_write: bool,
+    pub clipboard_items: bool,
+    pub fullscreen: bool,
+    pub geolocation: bool,
+    pub wake_lock: bool,
+    pub battery: bool,
+    pub share: bool,
+    pub badging: bool,
+    pub push: bool,
+    pub notifications: bool,
+    pub background_sync: bool,
+    pub storage_persist: bool,
+    pub network_information: bool,
+    pub permissions: bool,
+    pub screen_orientation: bool,
+    pub vibration: bool,
+    pub media_session: bool,
+    pub file_system_access: bool,
+    pub launch_handler: bool,
+    pub broadcast_channel: bool,
+    pub web_locks: bool,
+    pub idle_detection: bool,
+    pub web_authn: bool,
+    pub payment_request: bool,
+    pub device_orientation: bool,
+    pub device_motion: bool,
+    pub window_controls_overlay: bool,
+    pub protocol_handlers: bool,
+    pub contact_picker: bool,
+}
+
+impl Capabilities {
+    /// Probes the current global scope. Never throws: outside of the browser
+    /// every capability is simply `false`.
+    pub fn detect() -> Capabilities {
+        let scope = match GlobalScope::current() {
+            Ok(scope) => scope,
+            Err(_) => return Capabilities::default(),
+        };
+
+        let global = js_sys::global();
+        let navigator = scope.navigator();
+        let navigator = navigator.as_js();
+        let clipboard = get(navigator, "clipboard");
+        let storage = get(navigator, "storage");
+        let document = match &scope {
+            GlobalScope::Window(window) => window.document().map(JsValue::from),
+            GlobalScope::Worker(_) => None,
+        };
+
+        Capabilities {
+            clipboard_read: clipboard.as_ref().is_some_and(|c| has(c, "readText")),
+            clipboard_write: clipboard.as_ref().is_some_and(|c| has(c, "writeText")),
+            clipboard_items: clipboard.as_ref().is_some_and(|c| has(c, "write")) && has(&global, "ClipboardItem"),
+            fullscreen: document.as_ref().is_some_and(|d| has(d, "exitFullscreen")),
+            geolocation: has(navigator, "geolocation"),
+            wake_lock: has(navigator, "wakeLock"),
+            battery: has(navigator, "getBattery"),
+            share: has(navigator, "share"),
+            badging: has(navigator, "setAppBadge"),
+            push: has(&global, "PushManager"),
+            notifications: has(&global, "Notification"),
+            background_sync: has(&global, "SyncManager"),
+            storage_persist: storage.as_ref().is_some_and(|s| has(s, "persist")),
+            network_information: has(navigator, "connection"),
+            permissions: has(navigator, "permissions"),
+            screen_orientation: has(&global, "ScreenOrientation"),
+            vibration: has(navigator, "vibrate"),
+            media_session: has(navigator, "mediaSession"),
+            file_system_access: has(&global, "showOpenFilePicker"),
+            launch_handler: has(&global, "launchQueue"),
+            broadcast_channel: has(&global, "BroadcastChannel"),
+            web_locks: has(navigator, "locks"),
+            idle_detection: has(&global, "IdleDetector"),
+            web_authn: has(&global, "PublicKeyCredential"),
+            payment_request: has(&global, "PaymentRequest"),
+            device_orientation: has(&global, "DeviceOrientationEvent"),
+            device_motion: has(&global, "DeviceMotionEvent"),
+            window_controls_overlay: has(navigator, "windowControlsOverlay"),
+            protocol_handlers: has(navigator, "registerProtocolHandler"),
+            contact_picker: has(navigator, "contacts") && has(&global, "ContactsManager"),
+        }
+    }
+}
+
+/// Returns an object of booleans telling which APIs the browser supports.
+/// Safe to call anywhere, including the server, where everything is `false`.
+///
+/// ## Example
+///
+/// ```tsx
+/// const capabilities = getCapabilities();
+///
+/// if (capabilities.networkInformation) {
+///   const type = await getType();
+/// }
+/// ```
+#[wasm_bindgen(js_name = "getCapabilities")]
+pub fn get_capabilities_js() -> Result<JsValue, JsValue> {
+    Ok(serde_wasm_bindgen::to_value(&Capabilities::detect())?)
+}
+
+/// Checks whether `key` exists on `target` (or its prototype chain), the
+/// same way the JS `in` operator does, without throwing.
+pub(crate) fn has(target: &JsValue, key: &str) -> bool {
+    target.is_object() && Reflect::has(target, &JsValue::from_str(key)).unwrap_or(false)
+}
+
+/// Reads `target[key]`, treating `undefined` and `null` as missing.
+pub(crate) fn get(target: &JsValue, key: &str) -> Option<JsValue> {
+    if !target.is_object() {
+        return None;
+    }
+
+    Reflect::get(target, &JsValue::from_str(key))
+        .ok()
+        .filter(|value| !value.is_undefined() && !value.is_null())
+}
+
+#[cfg(test)]
+mod capabilities_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn detect_test() {
+        let capabilities = Capabilities::detect();
+        assert!(capabilities.fullscreen);
+        assert!(capabilities.permissions);
+    }
+
+    #[wasm_bindgen_test]
+    fn get_capabilities_test() {
+        let capabilities = get_capabilities_js().unwrap();
+        assert!(has(&capabilities, "clipboardWrite"));
+        assert!(has(&capabilities, "networkInformation"));
+    }
+
+    #[wasm_bindgen_test]
+    fn has_test() {
+        assert!(!has(&JsValue::UNDEFINED, "length"));
+        assert!(has(&js_sys::Array::new(), "length"));
+        assert!(get(&js_sys::Object::new(), "missing").is_none());
+    }
+}
@@ -1,9 +1,30 @@
 use log::{error, info};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
-use web_sys::window;
+use web_sys::Clipboard;
 
-use crate::client::response::ClientResponse;
+use crate::client::{error::ClientError, response::ClientResponse, scope::window};
+
+fn clipboard() -> Result<Clipboard, ClientError> {
+    window()?.navigator().clipboard().ok_or(ClientError::Unsupported("Clipboard API"))
+}
+
+/// Writes `text` to the clipboard.
+pub async fn write_text(text: &str) -> Result<(), ClientError> {
+    JsFuture::from(clipboard()?.write_text(text)).await?;
+    Ok(())
+}
+
+/// Reads the text currently in the clipboard.
+pub async fn read_text() -> Result<String, ClientError> {
+    let text = JsFuture::from(clipboard()?.read_text()).await?;
+    Ok(text.as_string().unwrap_or_default())
+}
+
+/// Writes a base64 encoded PNG to the clipboard, as a data URL.
+pub async fn write_image(image: &str) -> Result<(), ClientError> {
+    write_text(&format!("data:image/png;base64,{}", image)).await
+}
 
 /// Copy text to the clipboard.
 /// 
@@ -20,23 +41,14 @@ use crate::client::response::ClientResponse;
 /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Clipboard/writeText)
 /// 
 #[wasm_bindgen(js_name = "copyToClipboard")]
-pub async fn copy_to_clipboard(text: String) -> Result<ClientResponse, ClientResponse> {
-    let window = window().expect("Error occured! Are you calling this function in the server?");
-    let navigator = window.navigator();
-
-    if let Some(clipboard) = navigator.clipboard() {
-        match JsFuture::from(clipboard.write_text(&text)).await {
-            Ok(_) => Ok(ClientResponse::new("success", "Copied to clipboard!")),
-            Err(_) => Err(ClientResponse::new(
-                "error",
-                "Error occured whilst copying to clipboard!",
-            )),
-        }
-    } else {
-        return Err(ClientResponse::new(
+pub async fn copy_to_clipboard_js(text: String) -> Result<ClientResponse, ClientResponse> {
+    match write_text(&text).await {
+        Ok(_) => Ok(ClientResponse::new("success", "Copied to clipboard!")),
+        Err(ClientError::Failed(_)) => Err(ClientResponse::new(
             "error",
-            "Clipboard API doesn't seem available on your browser!",
-        ));
+            "Error occured whilst copying to clipboard!",
+        )),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -55,26 +67,20 @@ pub async fn copy_to_clipboard(text: String) -> Result<ClientResponse, ClientRes
 /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Clipboard/readText)
 /// 
 #[wasm_bindgen(js_name = "pasteFromClipboard")]
-pub async fn paste_from_clipbaord() -> String {
-    let window = window().expect("Error occured! Are you calling this function in the server?");
-    let navigator = window.navigator();
-
-    if let Some(clipboard) = navigator.clipboard() {
-        match JsFuture::from(clipboard.read_text()).await {
-            Ok(text) => {
-                let text = text.as_string().unwrap();
-                info!("Pasted text: {}", text);
-                return text;
-            }
-            Err(_) => {
-                error!("Error occured whilst pasting to clipboard!");
-                return "Error occured whilst pasting to clipboard!".to_string();
-            }
+pub async fn paste_from_clipboard_js() -> String {
+    match read_text().await {
+        Ok(text) => {
+            info!("Pasted text: {}", text);
+            text
+        }
+        Err(ClientError::Failed(_)) => {
+            error!("Error occured whilst pasting to clipboard!");
+            "Error occured whilst pasting to clipboard!".to_string()
+        }
+        Err(e) => {
+            error!("{}", e);
+            e.to_string()
         }
-    } else {
-        let error_message = "Clipboard API doesn't seem available on your browser!";
-        error!("{}", error_message);
-        return error_message.to_string();
     }
 }
 
@@ -95,29 +101,19 @@ pub async fn paste_from_clipbaord() -> String {
 /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Clipboard/writeText)
 /// 
 #[wasm_bindgen(js_name = "copyImageToClipboard")]
-pub async fn copy_image_to_clipboard(image: String) -> Result<ClientResponse, ClientResponse> {
-    let window = window().expect("Error occured! Are you calling this function in the server?");
-    let navigator = window.navigator();
-
-    if let Some(clipboard) = navigator.clipboard() {
-        let image = format!("data:image/png;base64,{}", image);
-
-        match JsFuture::from(clipboard.write_text(&image)).await {
-            Ok(_) => Ok(ClientResponse::new("success", "Copied to clipboard!")),
-            Err(_) => Err(ClientResponse::new(
-                "error",
-                "Error occured whilst copying image to clipboard!",
-            )),
-        }
-    } else {
-        return Err(ClientResponse::new(
+pub async fn copy_image_to_clipboard_js(image: String) -> Result<ClientResponse, ClientResponse> {
+    match write_image(&image).await {
+        Ok(_) => Ok(ClientResponse::new("success", "Copied to clipboard!")),
+        Err(ClientError::Failed(_)) => Err(ClientResponse::new(
             "error",
-            "Clipboard API doesn't seem available on your browser!",
-        ));
+            "Error occured whilst copying image to clipboard!",
+        )),
+        Err(e) => Err(e.into()),
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod clipboard_tests {
     use super::*;
     use wasm_bindgen_test::*;
@@ -127,7 +123,7 @@ mod clipboard_tests {
     #[wasm_bindgen_test]
     async fn copy_to_clipboard_test() {
         let text = "Hello World!";
-        let result = copy_to_clipboard(text.to_string()).await;
+        let result = copy_to_clipboard_js(text.to_string()).await;
         assert_eq!(result.is_ok(), true);
     }
 
@@ -136,16 +132,22 @@ mod clipboard_tests {
         let test_text = "Hello, Rust!";
         let text = String::from(test_text);
 
-        copy_to_clipboard(text).await.unwrap();
+        copy_to_clipboard_js(text).await.unwrap();
 
-        let result = paste_from_clipbaord().await;
+        let result = paste_from_clipboard_js().await;
         assert_eq!(result, test_text.to_string());
     }
 
+    #[wasm_bindgen_test]
+    async fn write_read_text_test() {
+        write_text("Hello, WASM!").await.unwrap();
+        assert_eq!(read_text().await.unwrap(), "Hello, WASM!");
+    }
+
     #[wasm_bindgen_test]
     async fn copy_image_to_clipboard_test() {
         let image = "iVBORw0KGgoAAAANSUhEUgAAABAAAAAQCAYAAAAf8/9hAAAAAXNSR0IArs4c6QAAAARnQU1BAACxjwv8YQUAAACWSURBVDhPY2AYBQAAADABARhI5DkAAAAASUVORK5CYII=";
-        let result = copy_image_to_clipboard(image.to_string()).await;
+        let result = copy_image_to_clipboard_js(image.to_string()).await;
         assert_eq!(result.is_ok(), true);
     }
 }
@@ -1,10 +1,30 @@
 use wasm_bindgen::prelude::*;
 use js_sys::Function;
+use serde::{Deserialize, Serialize};
 use web_sys::{NetworkInformation, ConnectionType};
 
+use crate::client::{capabilities::get, error::ClientError, scope::navigator};
+
+/// Whether the browser thinks it's online.
+pub fn is_online() -> Result<bool, ClientError> {
+    Ok(navigator()?.on_line())
+}
+
+/// The `NetworkInformation` object, on browsers that have one.
+pub fn network_information() -> Result<NetworkInformation, ClientError> {
+    navigator()?.connection()
+}
+
+/// The kind of connection in use, such as `wifi` or `cellular`.
+pub fn connection_type() -> Result<ConnectionType, ClientError> {
+    Ok(network_information()?.type_())
+}
+
 /// Check wether a user is offline or online.
 /// Returns a boolean
 /// 
+/// *Also available in workers*
+/// 
 /// ## Example
 /// 
 /// ```tsx
@@ -13,22 +33,15 @@ use web_sys::{NetworkInformation, ConnectionType};
 /// 
 /// [MDN Documentaion](https://developer.mozilla.org/en-US/docs/Web/API/Navigator/onLine)
 #[wasm_bindgen(js_name = "isOnline")]
-pub async fn is_online() -> Result<bool, JsValue> {
-    let window = web_sys::window().expect("no global `window` exists");
-    let navigator = window.navigator();
-
-    let online = navigator.on_line();
-
-    if online {
-      return Ok(true);
-    } else {
-      return Ok(false);
-    }
+pub async fn is_online_js() -> Result<bool, JsValue> {
+    Ok(is_online()?)
 }
 
 /// Checks wether a user is online or offline and calls a 
 /// callback based on the connection state
 /// 
+/// *Also available in workers*
+/// 
 /// ## Example
 /// 
 /// ```tsx
@@ -37,31 +50,25 @@ pub async fn is_online() -> Result<bool, JsValue> {
 /// 
 /// [MDN Documentaion](https://developer.mozilla.org/en-US/docs/Web/API/Navigator/onLine)
 #[wasm_bindgen(js_name = "checkConnectivity")]
-pub async fn check_connectivity(online: &Function, offline: &Function) {
-    let window = web_sys::window().expect("no global `window` exists");
-    let navigator = window.navigator();
-
-    let is_online = navigator.on_line();
-
-    if is_online {
-      online.call0(&JsValue::NULL).unwrap();
+pub async fn check_connectivity_js(online: &Function, offline: &Function) -> Result<(), JsValue> {
+    if is_online()? {
+      online.call0(&JsValue::NULL)?;
     } else {
-      offline.call0(&JsValue::NULL).unwrap();
+      offline.call0(&JsValue::NULL)?;
     }
+
+    Ok(())
 }
 
 /// Returns a `NetworkInformation` object about the user
 /// 
+/// *Also available in workers*
+/// 
 /// [MDN Documentaion](https://developer.mozilla.org/en-US/docs/Web/API/Navigator/connection) | 
 /// [NetworkInformation](https://developer.mozilla.org/en-US/docs/Web/API/NetworkInformation)
 #[wasm_bindgen(js_name = "getNetworkInformation")]
-pub async fn get_network_information() -> Result<NetworkInformation, JsValue> {
-    let window = web_sys::window().expect("no global `window` exists");
-    let navigator = window.navigator();
-
-    let connection = navigator.connection().unwrap();
-
-    Ok(connection)
+pub async fn get_network_information_js() -> Result<NetworkInformation, JsValue> {
+    Ok(network_information()?)
 }
 
 /// Returns the type of connection the user is using to 
@@ -70,6 +77,8 @@ pub async fn get_network_information() -> Result<NetworkInformation, JsValue> {
 /// Useful in scenarios where you want to reduce data usage if 
 /// user is connected with cellular, for example.
 /// 
+/// *Also available in workers*
+/// 
 /// ## Example
 /// 
 /// if(getType() == "wifi") {
@@ -78,18 +87,74 @@ pub async fn get_network_information() -> Result<NetworkInformation, JsValue> {
 /// 
 /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/NetworkInformation/type)
 #[wasm_bindgen(js_name = "getType")]
-pub async fn get_type() -> Result<ConnectionType, JsValue> {
-    let window = web_sys::window().expect("no global `window` exists");
-    let navigator = window.navigator();
+pub async fn get_type_js() -> Result<ConnectionType, JsValue> {
+    Ok(connection_type()?)
+}
+
+/// A snapshot of the user's network quality. Every field is optional since
+/// browsers only expose part of the Network Information API, if any.
+/// 
+/// `downlink` and `downlinkMax` are in megabits per second, `rtt` in
+/// milliseconds.
+/// 
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/NetworkInformation)
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkQuality {
+    pub effective_type: Option<String>,
+    pub downlink: Option<f64>,
+    pub downlink_max: Option<f64>,
+    pub rtt: Option<f64>,
+    pub save_data: bool,
+    #[serde(rename = "type")]
+    pub connection_type: Option<String>,
+}
 
-    let connection = navigator.connection().unwrap();
+impl NetworkQuality {
+    /// Reads a snapshot off a `NetworkInformation` object.
+    pub fn from_connection(connection: &NetworkInformation) -> NetworkQuality {
+        let string = |key| get(connection, key).and_then(|value| value.as_string());
+        let number = |key| get(connection, key).and_then(|value| value.as_f64());
+
+        NetworkQuality {
+            effective_type: string("effectiveType"),
+            downlink: number("downlink"),
+            downlink_max: number("downlinkMax").filter(|max| max.is_finite()),
+            rtt: number("rtt"),
+            save_data: get(connection, "saveData").and_then(|value| value.as_bool()).unwrap_or(false),
+            connection_type: string("type"),
+        }
+    }
 
-    let connection_type = connection.type_();
+    /// Takes a snapshot of the current connection.
+    pub fn current() -> Result<NetworkQuality, ClientError> {
+        Ok(NetworkQuality::from_connection(&network_information()?))
+    }
+}
 
-    Ok(connection_type)
+/// Returns a snapshot of the user's network quality: `effectiveType`,
+/// `downlink`, `downlinkMax`, `rtt`, `saveData` and `type`.
+/// 
+/// *Also available in workers*
+/// 
+/// ## Example
+/// 
+/// ```tsx
+/// const { effectiveType, saveData } = await getNetworkQuality();
+/// 
+/// if (saveData || effectiveType === "2g") {
+///   setImageQuality(30);
+/// }
+/// ```
+/// 
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/NetworkInformation)
+#[wasm_bindgen(js_name = "getNetworkQuality")]
+pub async fn get_network_quality_js() -> Result<JsValue, JsValue> {
+    Ok(serde_wasm_bindgen::to_value(&NetworkQuality::current()?)?)
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod connection_tests {
   use super::*;
   use wasm_bindgen_test::*;
@@ -98,8 +163,9 @@ mod connection_tests {
 
   #[wasm_bindgen_test]
   async fn is_online_test() {
-    let result = is_online().await;
+    let result = is_online_js().await;
     assert_eq!(result.is_ok(), true);
+    assert_eq!(result.unwrap(), is_online().unwrap());
   }
 
   #[wasm_bindgen_test]
@@ -108,18 +174,25 @@ mod connection_tests {
 
     let offline = Function::new_no_args("console.log(\"Offline!\");");
 
-    check_connectivity(&online, &offline).await;
+    let result = check_connectivity_js(&online, &offline).await;
+    assert!(result.is_ok());
   }
 
   #[wasm_bindgen_test]
   async fn get_network_information_test() {
-    let result = get_network_information().await;
+    let result = get_network_information_js().await;
     assert_eq!(result.is_ok(), true);
   }
 
+  #[wasm_bindgen_test]
+  async fn get_network_quality_test() {
+    let result = get_network_quality_js().await;
+    assert!(result.is_ok());
+  }
+
   #[wasm_bindgen_test]
   async fn get_type_test() {
-    let result = get_type().await;
+    let result = get_type_js().await;
     assert_eq!(result.is_ok(), true);
     // You can additionally add another assertion for connection_type
     // with regards to what connection is used. I omitted it because 
@@ -0,0 +1,293 @@
+use std::fmt;
+
+use js_sys::{Array, Function, Object, Promise, Reflect};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, DomException};
+
+use crate::client::{
+    address::Address,
+    capabilities::get,
+    error::{impl_js_error, ClientError, JsErrorName},
+    scope::window,
+};
+
+/// Why the contact picker couldn't be used.
+///
+/// Thrown as a JS `Error` whose `name` is [`ContactError::name`]. A user
+/// closing the picker isn't an error: `selectContacts` resolves with `[]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContactError {
+    Client(ClientError),
+    /// Not a contact property. Holds the rejected name.
+    InvalidProperty(String),
+    /// A property this browser's picker doesn't provide. Holds its name.
+    UnavailableProperty(String),
+    /// Not called from a user gesture, or from an iframe.
+    Security,
+    /// The picker is already open.
+    InvalidState,
+    /// Any other failure. Holds the browser's message.
+    Failed(String),
+}
+
+impl JsErrorName for ContactError {
+    fn name(&self) -> &'static str {
+        match self {
+            ContactError::Client(error) => error.name(),
+            ContactError::InvalidProperty(_) => "TypeError",
+            ContactError::UnavailableProperty(_) => "NotSupportedError",
+            ContactError::Security => "SecurityError",
+            ContactError::InvalidState => "InvalidStateError",
+            ContactError::Failed(_) => "ContactPickerError",
+        }
+    }
+}
+
+impl ContactError {
+    /// Classifies a `DOMException` thrown by `navigator.contacts.select()`.
+    fn from_dom(name: &str, message: String) -> ContactError {
+        match name {
+            "SecurityError" => ContactError::Security,
+            "InvalidStateError" => ContactError::InvalidState,
+            _ => ContactError::Failed(message),
+        }
+    }
+}
+
+impl fmt::Display for ContactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContactError::Client(error) => write!(f, "{}", error),
+            ContactError::InvalidProperty(property) => write!(
+                f,
+                "`{}` isn't a contact property, expected name, email, tel, address or icon",
+                property
+            ),
+            ContactError::UnavailableProperty(property) => {
+                write!(f, "This browser's contact picker doesn't provide `{}`", property)
+            }
+            ContactError::Security => write!(f, "The contact picker has to be opened from a user gesture"),
+            ContactError::InvalidState => write!(f, "The contact picker is already open"),
+            ContactError::Failed(message) => write!(f, "Failed to pick contacts: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ContactError {}
+
+impl From<ClientError> for ContactError {
+    fn from(error: ClientError) -> Self {
+        ContactError::Client(error)
+    }
+}
+
+impl_js_error!(ContactError);
+
+fn contact_error(error: JsValue) -> ContactError {
+    match error.dyn_into::<DomException>() {
+        Ok(exception) => ContactError::from_dom(&exception.name(), exception.message()),
+        Err(error) => ContactError::Failed(format!("{:?}", error)),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContactProperty {
+    Name,
+    Email,
+    Tel,
+    Address,
+    Icon,
+}
+
+impl ContactProperty {
+    pub fn from_name(name: &str) -> Result<ContactProperty, ContactError> {
+        match name {
+            "name" => Ok(ContactProperty::Name),
+            "email" => Ok(ContactProperty::Email),
+            "tel" => Ok(ContactProperty::Tel),
+            "address" => Ok(ContactProperty::Address),
+            "icon" => Ok(ContactProperty::Icon),
+            _ => Err(ContactError::InvalidProperty(name.to_string())),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContactProperty::Name => "name",
+            ContactProperty::Email => "email",
+            ContactProperty::Tel => "tel",
+            ContactProperty::Address => "address",
+            ContactProperty::Icon => "icon",
+        }
+    }
+}
+
+/// A picked contact. Each property holds every value the user shared, and
+/// is empty when it wasn't requested.
+///
+/// `icon` holds `Blob`s, so it's left out of serialization; `selectContacts`
+/// adds it back to the JS objects.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Contact {
+    pub name: Vec<String>,
+    pub email: Vec<String>,
+    pub tel: Vec<String>,
+    pub address: Vec<Address>,
+    #[serde(skip)]
+    pub icon: Vec<Blob>,
+}
+
+impl Contact {
+    fn from_js(contact: &JsValue) -> Contact {
+        let list = |key| get(contact, key).map(|list| Array::from(&list)).unwrap_or_default();
+        let strings = |key| list(key).iter().filter_map(|value| value.as_string()).collect();
+
+        Contact {
+            name: strings("name"),
+            email: strings("email"),
+            tel: strings("tel"),
+            address: list("address").iter().filter_map(|a| Address::from_js(&a)).collect(),
+            icon: list("icon").iter().filter_map(|icon| icon.dyn_into().ok()).collect(),
+        }
+    }
+}
+
+/// `navigator.contacts`, on Android Chrome.
+fn contacts_manager() -> Result<JsValue, ContactError> {
+    get(&window()?.navigator(), "contacts").ok_or_else(|| ClientError::Unsupported("Contact Picker API").into())
+}
+
+async fn call(manager: &JsValue, method: &str, args: &Array) -> Result<JsValue, ContactError> {
+    let function = get(manager, method)
+        .and_then(|function| function.dyn_into::<Function>().ok())
+        .ok_or(ClientError::Unsupported("Contact Picker API"))?;
+
+    let promise = Reflect::apply(&function, manager, args).map_err(contact_error)?;
+    JsFuture::from(promise.unchecked_into::<Promise>()).await.map_err(contact_error)
+}
+
+/// The properties this browser's picker can provide.
+pub async fn get_contact_properties() -> Result<Vec<ContactProperty>, ContactError> {
+    let properties = call(&contacts_manager()?, "getProperties", &Array::new()).await?;
+
+    Ok(Array::from(&properties)
+        .iter()
+        .filter_map(|property| ContactProperty::from_name(&property.as_string()?).ok())
+        .collect())
+}
+
+/// Opens the contact picker, asking for `properties`. Resolves with no
+/// contacts when the user closes the picker.
+pub async fn select_contacts(properties: &[ContactProperty], multiple: bool) -> Result<Vec<Contact>, ContactError> {
+    let manager = contacts_manager()?;
+
+    let supported = get_contact_properties().await?;
+    if let Some(property) = properties.iter().find(|property| !supported.contains(property)) {
+        return Err(ContactError::UnavailableProperty(property.as_str().to_string()));
+    }
+
+    let js_properties = properties.iter().map(|property| JsValue::from(property.as_str())).collect::<Array>();
+    let options = Object::new();
+    Reflect::set(&options, &"multiple".into(), &multiple.into()).map_err(contact_error)?;
+
+    let contacts = call(&manager, "select", &Array::of2(&js_properties, &options)).await?;
+    Ok(Array::from(&contacts).iter().map(|contact| Contact::from_js(&contact)).collect())
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SelectOptions {
+    multiple: bool,
+}
+
+/// Resolves with the properties this browser's contact picker provides,
+/// out of `name`, `email`, `tel`, `address` and `icon`.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/ContactsManager/getProperties)
+#[wasm_bindgen(js_name = "getContactProperties")]
+pub async fn get_contact_properties_js() -> Result<JsValue, JsValue> {
+    Ok(serde_wasm_bindgen::to_value(&get_contact_properties().await?)?)
+}
+
+/// Opens the native contact picker and resolves with the picked contacts,
+/// as `{ name, email, tel, address, icon }` objects holding arrays. Resolves
+/// with `[]` when the user closes the picker.
+///
+/// Properties the browser can't provide are rejected upfront with a
+/// `NotSupportedError`; check `getContactProperties()` to offer only what's
+/// available. Has to be called from a user gesture.
+///
+/// ## Example
+///
+/// ```tsx
+/// <button onClick={async () => {
+///   const contacts = await selectContacts(["name", "email"], { multiple: true });
+///   setInvitees(contacts.flatMap((contact) => contact.email));
+/// }}>
+///   Invite from contacts
+/// </button>
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/ContactsManager/select)
+#[wasm_bindgen(js_name = "selectContacts")]
+pub async fn select_contacts_js(properties: Vec<String>, options: JsValue) -> Result<Array, JsValue> {
+    let properties = properties
+        .iter()
+        .map(|property| ContactProperty::from_name(property))
+        .collect::<Result<Vec<_>, _>>()?;
+    let options: SelectOptions = if options.is_undefined() || options.is_null() {
+        SelectOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)?
+    };
+
+    let contacts = select_contacts(&properties, options.multiple).await?;
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+
+    let js_contacts = Array::new();
+    for contact in contacts {
+        let js_contact = contact.serialize(&serializer)?;
+        Reflect::set(&js_contact, &"icon".into(), &contact.icon.iter().collect::<Array>())?;
+        js_contacts.push(&js_contact);
+    }
+
+    Ok(js_contacts)
+}
+
+#[cfg(test)]
+mod contacts_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn property_test() {
+        for name in ["name", "email", "tel", "address", "icon"] {
+            assert_eq!(ContactProperty::from_name(name).unwrap().as_str(), name);
+        }
+
+        assert_eq!(
+            ContactProperty::from_name("phone"),
+            Err(ContactError::InvalidProperty("phone".to_string()))
+        );
+        assert_eq!(ContactError::InvalidProperty("phone".to_string()).name(), "TypeError");
+    }
+
+    #[wasm_bindgen_test]
+    fn contact_test() {
+        let contact = js_sys::JSON::parse(r#"{ "name": ["Ada"], "email": ["ada@example.com", 42], "tel": [] }"#).unwrap();
+
+        assert_eq!(
+            Contact::from_js(&contact),
+            Contact {
+                name: vec!["Ada".to_string()],
+                email: vec!["ada@example.com".to_string()],
+                ..Contact::default()
+            }
+        );
+    }
+}
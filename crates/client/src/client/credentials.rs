@@ -0,0 +1,729 @@
+use std::fmt;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use js_sys::{Array, ArrayBuffer, Function, Object, Promise, Reflect, Uint8Array, JSON};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortSignal, DomException};
+
+use crate::client::{
+    capabilities::get,
+    error::{impl_js_error, ClientError, JsErrorName},
+    scope::window,
+};
+
+/// Why a passkey couldn't be created or used.
+///
+/// Thrown as a JS `Error` whose `name` is [`CredentialError::name`], which
+/// matches the browser's own `DOMException` names where there is one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CredentialError {
+    Client(ClientError),
+    /// The options from the server are malformed. Holds what's wrong.
+    InvalidOptions(String),
+    /// The user dismissed the prompt, or it timed out. Browsers don't tell
+    /// the two apart.
+    NotAllowed,
+    /// One of `excludeCredentials` is already on the authenticator, i.e.
+    /// the user already registered this device.
+    AlreadyRegistered,
+    /// The RP ID doesn't match the current origin.
+    Security,
+    /// The `signal` was aborted, e.g. to cancel a conditional request.
+    Aborted,
+    /// Any other failure. Holds the browser's message.
+    Failed(String),
+}
+
+impl JsErrorName for CredentialError {
+    fn name(&self) -> &'static str {
+        match self {
+            CredentialError::Client(error) => error.name(),
+            CredentialError::InvalidOptions(_) => "TypeError",
+            CredentialError::NotAllowed => "NotAllowedError",
+            CredentialError::AlreadyRegistered => "InvalidStateError",
+            CredentialError::Security => "SecurityError",
+            CredentialError::Aborted => "AbortError",
+            CredentialError::Failed(_) => "CredentialError",
+        }
+    }
+}
+
+impl CredentialError {
+    /// Classifies a `DOMException` thrown by `navigator.credentials`.
+    fn from_dom(name: &str, message: String) -> CredentialError {
+        match name {
+            "NotAllowedError" => CredentialError::NotAllowed,
+            "InvalidStateError" => CredentialError::AlreadyRegistered,
+            "SecurityError" => CredentialError::Security,
+            "AbortError" => CredentialError::Aborted,
+            "NotSupportedError" => CredentialError::Client(ClientError::Unsupported("This credential type")),
+            _ => CredentialError::Failed(message),
+        }
+    }
+}
+
+impl fmt::Display for CredentialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialError::Client(error) => write!(f, "{}", error),
+            CredentialError::InvalidOptions(message) => write!(f, "Invalid credential options: {}", message),
+            CredentialError::NotAllowed => write!(f, "The request was cancelled or timed out"),
+            CredentialError::AlreadyRegistered => write!(f, "This authenticator is already registered"),
+            CredentialError::Security => write!(f, "The relying party ID doesn't match this origin"),
+            CredentialError::Aborted => write!(f, "The credential request was aborted"),
+            CredentialError::Failed(message) => write!(f, "Credential request failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for CredentialError {}
+
+impl From<ClientError> for CredentialError {
+    fn from(error: ClientError) -> Self {
+        CredentialError::Client(error)
+    }
+}
+
+impl_js_error!(CredentialError);
+
+fn credential_error(error: JsValue) -> CredentialError {
+    match error.dyn_into::<DomException>() {
+        Ok(exception) => CredentialError::from_dom(&exception.name(), exception.message()),
+        Err(error) => CredentialError::Failed(format!("{:?}", error)),
+    }
+}
+
+/// Encodes `bytes` as unpadded base64url, the encoding of every binary
+/// field in the WebAuthn JSON format.
+pub fn encode(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Decodes base64url. Padding and the standard base64 alphabet are
+/// accepted too, since servers aren't always strict about it.
+pub fn decode(value: &str) -> Option<Vec<u8>> {
+    let value = value
+        .trim_end_matches('=')
+        .chars()
+        .map(|c| match c {
+            '+' => '-',
+            '/' => '_',
+            c => c,
+        })
+        .collect::<String>();
+
+    URL_SAFE_NO_PAD.decode(value).ok()
+}
+
+/// Replaces the base64url string at `object[key]` with its bytes.
+fn decode_field(object: &JsValue, key: &str, path: &str) -> Result<(), CredentialError> {
+    let value = get(object, key)
+        .and_then(|value| value.as_string())
+        .ok_or_else(|| CredentialError::InvalidOptions(format!("`{}` is missing", path)))?;
+    let bytes = decode(&value).ok_or_else(|| CredentialError::InvalidOptions(format!("`{}` isn't base64url", path)))?;
+
+    Reflect::set(object, &key.into(), &Uint8Array::from(bytes.as_slice()))
+        .map(|_| ())
+        .map_err(|_| CredentialError::InvalidOptions(format!("`{}` can't be set", path)))
+}
+
+/// Decodes the `id` of every descriptor in `excludeCredentials` or
+/// `allowCredentials`, if there are any.
+fn decode_descriptors(options: &JsValue, key: &str) -> Result<(), CredentialError> {
+    let descriptors = match get(options, key) {
+        Some(descriptors) => descriptors
+            .dyn_into::<Array>()
+            .map_err(|_| CredentialError::InvalidOptions(format!("`{}` isn't an array", key)))?,
+        None => return Ok(()),
+    };
+
+    for (index, descriptor) in descriptors.iter().enumerate() {
+        decode_field(&descriptor, "id", &format!("{}[{}].id", key, index))?;
+    }
+
+    Ok(())
+}
+
+/// Copies the server's JSON options, so the caller's object stays intact.
+/// Both the bare options and `{ publicKey: options }` are accepted.
+fn copy_options(options: &JsValue) -> Result<JsValue, CredentialError> {
+    let options = get(options, "publicKey").unwrap_or_else(|| options.clone());
+
+    let copy = JSON::stringify(&options)
+        .and_then(|json| JSON::parse(&String::from(json)))
+        .map_err(|_| CredentialError::InvalidOptions("the options aren't JSON".to_string()))?;
+    match copy.is_object() {
+        true => Ok(copy),
+        false => Err(CredentialError::InvalidOptions("the options aren't an object".to_string())),
+    }
+}
+
+/// Turns `PublicKeyCredentialCreationOptionsJSON` into the options
+/// `navigator.credentials.create()` takes.
+fn creation_options(options: &JsValue) -> Result<JsValue, CredentialError> {
+    let options = copy_options(options)?;
+    decode_field(&options, "challenge", "challenge")?;
+
+    let user = get(&options, "user").ok_or_else(|| CredentialError::InvalidOptions("`user` is missing".to_string()))?;
+    decode_field(&user, "id", "user.id")?;
+    decode_descriptors(&options, "excludeCredentials")?;
+
+    Ok(options)
+}
+
+/// Turns `PublicKeyCredentialRequestOptionsJSON` into the options
+/// `navigator.credentials.get()` takes.
+fn request_options(options: &JsValue) -> Result<JsValue, CredentialError> {
+    let options = copy_options(options)?;
+    decode_field(&options, "challenge", "challenge")?;
+    decode_descriptors(&options, "allowCredentials")?;
+
+    Ok(options)
+}
+
+/// Reads `object[key]` as base64url, if it's an `ArrayBuffer`.
+fn encoded(object: &JsValue, key: &str) -> Option<String> {
+    get(object, key)
+        .filter(|value| value.is_instance_of::<ArrayBuffer>())
+        .map(|buffer| encode(&Uint8Array::new(&buffer).to_vec()))
+}
+
+/// Calls the optional method `object[method]()`.
+fn call(object: &JsValue, method: &str) -> Option<JsValue> {
+    get(object, method)?.dyn_into::<Function>().ok()?.call0(object).ok()
+}
+
+fn required(object: &JsValue, key: &str) -> Result<String, CredentialError> {
+    encoded(object, key).ok_or_else(|| CredentialError::Failed(format!("the credential has no `{}`", key)))
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialProperties {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rk: Option<bool>,
+}
+
+/// The client extension outputs this crate knows how to serialize.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientExtensionResults {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cred_props: Option<CredentialProperties>,
+}
+
+impl ClientExtensionResults {
+    fn from_credential(credential: &JsValue) -> ClientExtensionResults {
+        let results = call(credential, "getClientExtensionResults");
+        let cred_props = results.and_then(|results| get(&results, "credProps"));
+
+        ClientExtensionResults {
+            cred_props: cred_props.map(|props| CredentialProperties {
+                rk: get(&props, "rk").and_then(|rk| rk.as_bool()),
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub attestation_object: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authenticator_data: Option<String>,
+    pub transports: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key_algorithm: Option<i32>,
+}
+
+/// A new passkey in the `RegistrationResponseJSON` format, ready to be
+/// sent to the server.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationResponse {
+    pub id: String,
+    pub raw_id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authenticator_attachment: Option<String>,
+    pub client_extension_results: ClientExtensionResults,
+    pub response: AttestationResponse,
+}
+
+impl RegistrationResponse {
+    fn from_credential(credential: &JsValue) -> Result<RegistrationResponse, CredentialError> {
+        let response = get(credential, "response").unwrap_or(JsValue::UNDEFINED);
+
+        let transports = call(&response, "getTransports")
+            .and_then(|transports| transports.dyn_into::<Array>().ok())
+            .map(|transports| transports.iter().filter_map(|t| t.as_string()).collect())
+            .unwrap_or_default();
+        let public_key = call(&response, "getPublicKey")
+            .filter(|key| key.is_instance_of::<ArrayBuffer>())
+            .map(|key| encode(&Uint8Array::new(&key).to_vec()));
+        let authenticator_data = call(&response, "getAuthenticatorData")
+            .filter(|data| data.is_instance_of::<ArrayBuffer>())
+            .map(|data| encode(&Uint8Array::new(&data).to_vec()));
+
+        Ok(RegistrationResponse {
+            id: get(credential, "id").and_then(|id| id.as_string()).unwrap_or_default(),
+            raw_id: required(credential, "rawId")?,
+            kind: get(credential, "type").and_then(|kind| kind.as_string()).unwrap_or_default(),
+            authenticator_attachment: get(credential, "authenticatorAttachment").and_then(|a| a.as_string()),
+            client_extension_results: ClientExtensionResults::from_credential(credential),
+            response: AttestationResponse {
+                client_data_json: required(&response, "clientDataJSON")?,
+                attestation_object: required(&response, "attestationObject")?,
+                authenticator_data,
+                transports,
+                public_key,
+                public_key_algorithm: call(&response, "getPublicKeyAlgorithm")
+                    .and_then(|algorithm| algorithm.as_f64())
+                    .map(|algorithm| algorithm as i32),
+            },
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_handle: Option<String>,
+}
+
+/// A passkey sign-in in the `AuthenticationResponseJSON` format, ready to
+/// be sent to the server.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticationResponse {
+    pub id: String,
+    pub raw_id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authenticator_attachment: Option<String>,
+    pub client_extension_results: ClientExtensionResults,
+    pub response: AssertionResponse,
+}
+
+impl AuthenticationResponse {
+    fn from_credential(credential: &JsValue) -> Result<AuthenticationResponse, CredentialError> {
+        let response = get(credential, "response").unwrap_or(JsValue::UNDEFINED);
+
+        Ok(AuthenticationResponse {
+            id: get(credential, "id").and_then(|id| id.as_string()).unwrap_or_default(),
+            raw_id: required(credential, "rawId")?,
+            kind: get(credential, "type").and_then(|kind| kind.as_string()).unwrap_or_default(),
+            authenticator_attachment: get(credential, "authenticatorAttachment").and_then(|a| a.as_string()),
+            client_extension_results: ClientExtensionResults::from_credential(credential),
+            response: AssertionResponse {
+                client_data_json: required(&response, "clientDataJSON")?,
+                authenticator_data: required(&response, "authenticatorData")?,
+                signature: required(&response, "signature")?,
+                user_handle: encoded(&response, "userHandle"),
+            },
+        })
+    }
+}
+
+/// The site a passkey is for. `id` defaults to the current domain.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelyingParty {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+}
+
+/// The account a passkey is created for. `id` is base64url.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserEntity {
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+/// A signature algorithm the server accepts, e.g. `-7` for ES256.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialParameters {
+    /// Always `"public-key"`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub alg: i32,
+}
+
+/// A passkey the server already knows about. `id` is base64url.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialDescriptor {
+    /// Always `"public-key"`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transports: Vec<String>,
+}
+
+/// Which authenticators may create the passkey.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AuthenticatorSelection {
+    /// `"platform"` or `"cross-platform"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authenticator_attachment: Option<String>,
+    /// `"discouraged"`, `"preferred"` or `"required"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resident_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_resident_key: Option<bool>,
+    /// `"discouraged"`, `"preferred"` or `"required"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_verification: Option<String>,
+}
+
+/// The client extensions this crate knows how to read back, see
+/// [`ClientExtensionResults`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Extensions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cred_props: Option<bool>,
+}
+
+/// `PublicKeyCredentialCreationOptionsJSON`, as sent by the server to
+/// register a passkey. Binary fields are base64url.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreationOptions {
+    pub rp: RelyingParty,
+    pub user: UserEntity,
+    pub challenge: String,
+    pub pub_key_cred_params: Vec<CredentialParameters>,
+    /// In milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_credentials: Vec<CredentialDescriptor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authenticator_selection: Option<AuthenticatorSelection>,
+    /// `"none"`, `"indirect"`, `"direct"` or `"enterprise"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Extensions>,
+}
+
+/// `PublicKeyCredentialRequestOptionsJSON`, as sent by the server to sign
+/// in with a passkey. Binary fields are base64url.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestOptions {
+    pub challenge: String,
+    /// In milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rp_id: Option<String>,
+    /// Empty to let the user pick any of their passkeys for this site
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow_credentials: Vec<CredentialDescriptor>,
+    /// `"discouraged"`, `"preferred"` or `"required"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_verification: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Extensions>,
+}
+
+/// How `getPasskey` involves the user.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mediation {
+    Silent,
+    #[default]
+    Optional,
+    /// Offer passkeys in the autofill of inputs with
+    /// `autocomplete="username webauthn"`, without a modal prompt
+    Conditional,
+    Required,
+}
+
+impl Mediation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Mediation::Silent => "silent",
+            Mediation::Optional => "optional",
+            Mediation::Conditional => "conditional",
+            Mediation::Required => "required",
+        }
+    }
+}
+
+fn public_key_credential() -> Option<JsValue> {
+    get(&js_sys::global(), "PublicKeyCredential")
+}
+
+/// Calls an optional static `PublicKeyCredential` check, treating a
+/// missing method or a failure as `false`.
+async fn check(method: &str) -> bool {
+    let promise = public_key_credential().and_then(|credential| call(&credential, method));
+    match promise.and_then(|promise| promise.dyn_into::<Promise>().ok()) {
+        Some(promise) => JsFuture::from(promise).await.ok().and_then(|v| v.as_bool()).unwrap_or(false),
+        None => false,
+    }
+}
+
+/// Calls `navigator.credentials[method]({ publicKey, signal, mediation })`.
+async fn request(
+    method: &str,
+    public_key: JsValue,
+    signal: Option<AbortSignal>,
+    mediation: Option<Mediation>,
+) -> Result<JsValue, CredentialError> {
+    if public_key_credential().is_none() {
+        return Err(ClientError::Unsupported("Web Authentication API").into());
+    }
+
+    let credentials = get(&window()?.navigator(), "credentials")
+        .ok_or(ClientError::Unsupported("Credential Management API"))?;
+    let function = get(&credentials, method)
+        .and_then(|function| function.dyn_into::<Function>().ok())
+        .ok_or(ClientError::Unsupported("Credential Management API"))?;
+
+    let options = Object::new();
+    let set = |key: &str, value: &JsValue| Reflect::set(&options, &key.into(), value);
+    set("publicKey", &public_key)
+        .and_then(|_| match signal {
+            Some(signal) => set("signal", &signal),
+            None => Ok(true),
+        })
+        .and_then(|_| match mediation {
+            Some(mediation) => set("mediation", &mediation.as_str().into()),
+            None => Ok(true),
+        })
+        .map_err(credential_error)?;
+
+    let promise = function.call1(&credentials, &options).map_err(credential_error)?;
+    let credential = JsFuture::from(promise.unchecked_into::<Promise>())
+        .await
+        .map_err(credential_error)?;
+
+    match credential.is_null() || credential.is_undefined() {
+        true => Err(CredentialError::NotAllowed),
+        false => Ok(credential),
+    }
+}
+
+/// Takes the options as JSON, so `createPasskey` passes fields this crate
+/// doesn't model on to the browser.
+async fn register(options: &JsValue, signal: Option<AbortSignal>) -> Result<RegistrationResponse, CredentialError> {
+    let public_key = creation_options(options)?;
+    let credential = request("create", public_key, signal, None).await?;
+    RegistrationResponse::from_credential(&credential)
+}
+
+/// Takes the options as JSON, so `getPasskey` passes fields this crate
+/// doesn't model on to the browser.
+async fn sign_in(
+    options: &JsValue,
+    mediation: Mediation,
+    signal: Option<AbortSignal>,
+) -> Result<AuthenticationResponse, CredentialError> {
+    if mediation == Mediation::Conditional && !check("isConditionalMediationAvailable").await {
+        return Err(ClientError::Unsupported("Passkey autofill").into());
+    }
+
+    let public_key = request_options(options)?;
+    let credential = request("get", public_key, signal, Some(mediation)).await?;
+    AuthenticationResponse::from_credential(&credential)
+}
+
+/// Registers a passkey from the server's creation options.
+pub async fn create_passkey(
+    options: &CreationOptions,
+    signal: Option<AbortSignal>,
+) -> Result<RegistrationResponse, CredentialError> {
+    register(&to_js(options).map_err(credential_error)?, signal).await
+}
+
+/// Signs in with a passkey from the server's request options.
+pub async fn get_passkey(
+    options: &RequestOptions,
+    mediation: Mediation,
+    signal: Option<AbortSignal>,
+) -> Result<AuthenticationResponse, CredentialError> {
+    sign_in(&to_js(options).map_err(credential_error)?, mediation, signal).await
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct GetOptions {
+    mediation: Mediation,
+}
+
+fn signal(options: &JsValue) -> Option<AbortSignal> {
+    get(options, "signal").and_then(|signal| signal.dyn_into().ok())
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    Ok(value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
+
+/// Registers a new passkey. Takes the creation options the server sent, in
+/// the WebAuthn JSON format (base64url strings instead of `ArrayBuffer`s),
+/// and resolves with the `RegistrationResponseJSON` to send back.
+///
+/// `{ signal }` can abort the prompt. Rejects with a `NotAllowedError` when
+/// the user cancels, and an `InvalidStateError` when this authenticator is
+/// already registered.
+///
+/// ## Example
+///
+/// ```tsx
+/// const options = await fetch("/passkeys/register").then((r) => r.json());
+/// const credential = await createPasskey(options);
+///
+/// await fetch("/passkeys/register", { method: "POST", body: JSON.stringify(credential) });
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/CredentialsContainer/create)
+#[wasm_bindgen(js_name = "createPasskey")]
+pub async fn create_passkey_js(options: JsValue, request: JsValue) -> Result<JsValue, JsValue> {
+    to_js(&register(&options, signal(&request)).await?)
+}
+
+/// Signs in with a passkey. Takes the request options the server sent, in
+/// the WebAuthn JSON format, and resolves with the
+/// `AuthenticationResponseJSON` to send back.
+///
+/// With `{ mediation: "conditional" }`, passkeys are offered in the
+/// autofill of inputs with `autocomplete="username webauthn"` and the
+/// promise only settles once the user picks one. Abort it with `signal`
+/// when the user signs in another way.
+///
+/// ## Example
+///
+/// ```tsx
+/// useEffect(() => {
+///   const controller = new AbortController();
+///
+///   getPasskey(options, { mediation: "conditional", signal: controller.signal })
+///     .then((credential) => submit({ credential: JSON.stringify(credential) }, { method: "post" }))
+///     .catch((e) => e.name !== "AbortError" && console.error(e));
+///
+///   return () => controller.abort();
+/// }, [options]);
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/CredentialsContainer/get)
+#[wasm_bindgen(js_name = "getPasskey")]
+pub async fn get_passkey_js(options: JsValue, request: JsValue) -> Result<JsValue, JsValue> {
+    let get_options: GetOptions = if request.is_undefined() || request.is_null() {
+        GetOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(request.clone())?
+    };
+
+    to_js(&sign_in(&options, get_options.mediation, signal(&request)).await?)
+}
+
+/// Whether the browser can offer passkeys in autofill, i.e. whether
+/// `getPasskey(options, { mediation: "conditional" })` works.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/PublicKeyCredential/isConditionalMediationAvailable_static)
+#[wasm_bindgen(js_name = "isConditionalMediationAvailable")]
+pub async fn is_conditional_mediation_available() -> bool {
+    check("isConditionalMediationAvailable").await
+}
+
+/// Whether the device has a built-in authenticator, such as Touch ID or
+/// Windows Hello. Useful to decide whether to offer creating a passkey.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/PublicKeyCredential/isUserVerifyingPlatformAuthenticatorAvailable_static)
+#[wasm_bindgen(js_name = "isPlatformAuthenticatorAvailable")]
+pub async fn is_platform_authenticator_available() -> bool {
+    check("isUserVerifyingPlatformAuthenticatorAvailable").await
+}
+
+#[cfg(test)]
+mod credentials_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn base64url_test() {
+        assert_eq!(encode(&[0xfb, 0xff, 0x01]), "-_8B");
+        assert_eq!(encode(b"hi"), "aGk");
+
+        assert_eq!(decode("-_8B"), Some(vec![0xfb, 0xff, 0x01]));
+        assert_eq!(decode("+/8B"), Some(vec![0xfb, 0xff, 0x01]));
+        assert_eq!(decode("aGk="), Some(b"hi".to_vec()));
+        assert_eq!(decode("not base64!"), None);
+    }
+
+    #[test]
+    fn from_dom_test() {
+        let error = |name| CredentialError::from_dom(name, "message".to_string());
+
+        assert_eq!(error("NotAllowedError"), CredentialError::NotAllowed);
+        assert_eq!(error("InvalidStateError"), CredentialError::AlreadyRegistered);
+        assert_eq!(error("AbortError"), CredentialError::Aborted);
+        assert_eq!(error("UnknownError"), CredentialError::Failed("message".to_string()));
+        assert_eq!(CredentialError::AlreadyRegistered.name(), "InvalidStateError");
+    }
+
+    #[wasm_bindgen_test]
+    fn creation_options_test() {
+        let json = r#"{
+            "publicKey": {
+                "challenge": "aGk",
+                "rp": { "name": "Remix PWA" },
+                "user": { "id": "-_8B", "name": "ada", "displayName": "Ada" },
+                "pubKeyCredParams": [{ "type": "public-key", "alg": -7 }],
+                "excludeCredentials": [{ "type": "public-key", "id": "aGk" }]
+            }
+        }"#;
+        let options = creation_options(&JSON::parse(json).unwrap()).unwrap();
+
+        let challenge = get(&options, "challenge").unwrap().unchecked_into::<Uint8Array>();
+        assert_eq!(challenge.to_vec(), b"hi".to_vec());
+        assert!(get(&options, "rp").is_some());
+
+        let missing = creation_options(&JSON::parse(r#"{ "challenge": "aGk" }"#).unwrap());
+        assert_eq!(missing, Err(CredentialError::InvalidOptions("`user` is missing".to_string())));
+    }
+
+    #[wasm_bindgen_test]
+    fn typed_options_test() {
+        let options = RequestOptions {
+            challenge: "aGk".to_string(),
+            allow_credentials: vec![CredentialDescriptor {
+                kind: "public-key".to_string(),
+                id: "-_8B".to_string(),
+                transports: Vec::new(),
+            }],
+            ..RequestOptions::default()
+        };
+        let options = request_options(&to_js(&options).unwrap()).unwrap();
+
+        let challenge = get(&options, "challenge").unwrap().unchecked_into::<Uint8Array>();
+        assert_eq!(challenge.to_vec(), b"hi".to_vec());
+        // Unset fields are left out rather than sent as `null`
+        assert!(get(&options, "userVerification").is_none());
+    }
+}
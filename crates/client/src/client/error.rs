@@ -0,0 +1,158 @@
+use std::fmt;
+
+use wasm_bindgen::{prelude::*, JsCast};
+
+use crate::client::response::ClientResponse;
+
+/// Errors shared by every client API.
+///
+/// Functions returning `Result<_, JsValue>` throw these as a JS `Error` whose
+/// `name` is the variant's error name, so they can be told apart with
+/// `e.name === "NotInBrowserError"`. Functions returning a `ClientResponse`
+/// resolve with an `"error"` response carrying the same message.
+///
+/// [Source](https://github.com/remix-pwa/remix-pwa-ecosystem/blob/main/crates/client/src/client/error.rs)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClientError {
+    /// The API needs a browser `window` (or, for APIs that also work in
+    /// workers, a `WorkerGlobalScope`) and none was found. Happens when the
+    /// function is called during server rendering or from a worker.
+    NotInBrowser,
+    /// The browser doesn't implement the API. Holds the API's name.
+    Unsupported(&'static str),
+    /// An argument was rejected before calling the browser. Holds why.
+    InvalidArgument(String),
+    /// The browser API threw or rejected. Holds its message.
+    Failed(String),
+}
+
+/// An error that's thrown to JS as an `Error` with its own `name`, so it can
+/// be told apart with `e.name === "..."`. Implement it and call
+/// `impl_js_error!` to get the `From<_> for JsValue` conversion.
+pub trait JsErrorName: fmt::Display {
+    /// The `name` of the JS `Error` this error is thrown as.
+    fn name(&self) -> &'static str;
+}
+
+/// Creates a JS `Error` with the given `name` and `message`.
+pub fn js_error(name: &str, message: &str) -> JsValue {
+    let error = js_sys::Error::new(message);
+    error.set_name(name);
+    error.into()
+}
+
+/// Implements `From<$error> for JsValue` for a [`JsErrorName`] error,
+/// converting it with [`js_error`].
+macro_rules! impl_js_error {
+    ($error:ty) => {
+        impl From<$error> for wasm_bindgen::JsValue {
+            fn from(error: $error) -> Self {
+                $crate::client::error::js_error(
+                    $crate::client::error::JsErrorName::name(&error),
+                    &error.to_string(),
+                )
+            }
+        }
+    };
+}
+
+pub(crate) use impl_js_error;
+
+impl ClientError {
+    /// The `name` of the JS `Error` this error is thrown as.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClientError::NotInBrowser => "NotInBrowserError",
+            ClientError::Unsupported(_) => "NotSupportedError",
+            ClientError::InvalidArgument(_) => "TypeError",
+            ClientError::Failed(_) => "ClientError",
+        }
+    }
+}
+
+impl JsErrorName for ClientError {
+    fn name(&self) -> &'static str {
+        ClientError::name(self)
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::NotInBrowser => write!(
+                f,
+                "This API is only available in the browser. Are you calling this function in the server or in a worker?"
+            ),
+            ClientError::Unsupported(api) => {
+                write!(f, "{} doesn't seem available on your browser!", api)
+            }
+            ClientError::InvalidArgument(reason) => write!(f, "{}", reason),
+            ClientError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+/// Wraps an error thrown by a browser API, keeping its message.
+impl From<JsValue> for ClientError {
+    fn from(error: JsValue) -> Self {
+        if let Some(message) = error.as_string() {
+            return ClientError::Failed(message);
+        }
+
+        match error.dyn_into::<js_sys::Error>() {
+            Ok(error) => ClientError::Failed(format!("{}: {}", String::from(error.name()), String::from(error.message()))),
+            Err(error) => ClientError::Failed(format!("{:?}", error)),
+        }
+    }
+}
+
+/// A value that couldn't be converted to or from JS.
+impl From<serde_wasm_bindgen::Error> for ClientError {
+    fn from(error: serde_wasm_bindgen::Error) -> Self {
+        ClientError::Failed(error.to_string())
+    }
+}
+
+impl_js_error!(ClientError);
+
+impl From<ClientError> for ClientResponse {
+    fn from(error: ClientError) -> Self {
+        ClientResponse::new("error", &error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod client_error_tests {
+    use super::*;
+
+    #[test]
+    fn name_test() {
+        assert_eq!(ClientError::NotInBrowser.name(), "NotInBrowserError");
+        assert_eq!(ClientError::Unsupported("Vibration API").name(), "NotSupportedError");
+    }
+
+    #[test]
+    fn unsupported_message_test() {
+        assert_eq!(
+            ClientError::Unsupported("Network Information API").to_string(),
+            "Network Information API doesn't seem available on your browser!"
+        );
+    }
+
+    #[test]
+    fn argument_test() {
+        let error = ClientError::InvalidArgument("AdaptivePolicy needs at least one tier".to_string());
+
+        assert_eq!(error.name(), "TypeError");
+        assert_eq!(error.to_string(), "AdaptivePolicy needs at least one tier");
+    }
+
+    #[test]
+    fn client_response_test() {
+        let response = ClientResponse::from(ClientError::NotInBrowser);
+        assert_eq!(response.get_status(), "error");
+        assert_eq!(response.get_message(), ClientError::NotInBrowser.to_string());
+    }
+}
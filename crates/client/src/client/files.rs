@@ -0,0 +1,618 @@
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use js_sys::{Array, Function, Promise, Reflect, Uint8Array};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+use web_sys::{
+    Blob, BlobPropertyBag, DomException, File, FilePropertyBag, HtmlAnchorElement, HtmlInputElement, Url, Window,
+};
+
+use crate::client::{
+    capabilities::get,
+    error::ClientError,
+    scope::{document, window},
+    subscription::Subscription,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/FileSystemFileHandle)
+    #[wasm_bindgen(extends = js_sys::Object)]
+    #[derive(Clone, Debug)]
+    pub(crate) type FileSystemFileHandle;
+
+    #[wasm_bindgen(method, js_name = "getFile")]
+    fn get_file(this: &FileSystemFileHandle) -> Promise;
+
+    #[wasm_bindgen(method, js_name = "createWritable")]
+    fn create_writable(this: &FileSystemFileHandle) -> Promise;
+
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/FileSystemWritableFileStream)
+    #[wasm_bindgen(extends = js_sys::Object)]
+    type FileSystemWritableFileStream;
+
+    #[wasm_bindgen(method)]
+    fn write(this: &FileSystemWritableFileStream, data: &JsValue) -> Promise;
+
+    #[wasm_bindgen(method)]
+    fn close(this: &FileSystemWritableFileStream) -> Promise;
+
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/FileSystemDirectoryHandle)
+    #[wasm_bindgen(extends = js_sys::Object)]
+    type FileSystemDirectoryHandle;
+
+    #[wasm_bindgen(method)]
+    fn values(this: &FileSystemDirectoryHandle) -> js_sys::AsyncIterator;
+
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/FileSystemHandle)
+    #[wasm_bindgen(extends = js_sys::Object)]
+    pub(crate) type FileSystemHandle;
+
+    #[wasm_bindgen(method, getter)]
+    pub(crate) fn kind(this: &FileSystemHandle) -> String;
+
+    #[wasm_bindgen(method, getter)]
+    pub(crate) fn name(this: &FileSystemHandle) -> String;
+}
+
+/// A group of accepted files, e.g.
+/// `{ description: "Images", accept: { "image/*": [".png", ".jpg"] } }`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MIME types mapped to file extensions
+    pub accept: BTreeMap<String, Vec<String>>,
+}
+
+/// Options for `openFiles`. Every field is optional.
+///
+/// ## Attributes
+/// `multiple`: allow picking more than one file, defaults to `false`
+///
+/// `types`: the files that can be picked, defaults to any file
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OpenOptions {
+    pub multiple: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<FileType>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SaveOptions<'a> {
+    suggested_name: &'a str,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    types: &'a [FileType],
+}
+
+/// The value of an `<input type="file">`'s `accept` attribute matching
+/// `types`, e.g. `image/*,.png,.jpg`.
+pub fn accept_attribute(types: &[FileType]) -> String {
+    let mut accept: Vec<&str> = Vec::new();
+
+    for file_type in types {
+        for (mime_type, extensions) in &file_type.accept {
+            for value in std::iter::once(mime_type).chain(extensions) {
+                if !accept.contains(&value.as_str()) {
+                    accept.push(value);
+                }
+            }
+        }
+    }
+
+    accept.join(",")
+}
+
+/// A file picked or saved by the user, whichever API was used to get it.
+///
+/// When the File System Access API is available, the handle remembers where
+/// the file lives, so `save()` writes back to the same file. Otherwise it
+/// falls back to downloading a copy.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct FileHandle {
+    file: Rc<RefCell<File>>,
+    handle: Option<FileSystemFileHandle>,
+    path: String,
+}
+
+impl FileHandle {
+    /// Wraps a file without a native handle, e.g. from a drag and drop.
+    pub fn from_file(file: File) -> FileHandle {
+        let path = get(&file, "webkitRelativePath")
+            .and_then(|path| path.as_string())
+            .filter(|path| !path.is_empty())
+            .unwrap_or_else(|| file.name());
+
+        FileHandle {
+            file: Rc::new(RefCell::new(file)),
+            handle: None,
+            path,
+        }
+    }
+
+    pub(crate) async fn from_handle(handle: FileSystemFileHandle, path: String) -> Result<FileHandle, JsValue> {
+        let file = JsFuture::from(handle.get_file()).await?.unchecked_into::<File>();
+
+        Ok(FileHandle {
+            file: Rc::new(RefCell::new(file)),
+            handle: Some(handle),
+            path,
+        })
+    }
+
+    /// The underlying `File`, as of the last read or save.
+    pub fn file(&self) -> File {
+        self.file.borrow().clone()
+    }
+
+    /// Reads the contents as bytes.
+    pub async fn read_bytes(&self) -> Result<Vec<u8>, ClientError> {
+        let buffer = self.file.borrow().array_buffer();
+        let buffer = JsFuture::from(buffer).await?;
+        Ok(Uint8Array::new(&buffer).to_vec())
+    }
+
+    /// Reads the contents as a string.
+    pub async fn read_text(&self) -> Result<String, ClientError> {
+        let text = self.file.borrow().text();
+        let text = JsFuture::from(text).await?;
+        Ok(text.as_string().unwrap_or_default())
+    }
+
+    /// Replaces the file's contents with `blob`. Returns `true` when the
+    /// file was written in place, and `false` when a copy was downloaded
+    /// instead.
+    pub async fn save_blob(&self, blob: &Blob) -> Result<bool, ClientError> {
+        Ok(self.write_blob(blob).await?)
+    }
+
+    async fn write_blob(&self, blob: &Blob) -> Result<bool, JsValue> {
+        match &self.handle {
+            Some(handle) => {
+                write(handle, blob).await?;
+                *self.file.borrow_mut() = JsFuture::from(handle.get_file()).await?.unchecked_into();
+                Ok(true)
+            }
+            None => {
+                download(blob, &self.name())?;
+                Ok(false)
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl FileHandle {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.file.borrow().name()
+    }
+
+    /// Path relative to the picked directory, or the file name for single
+    /// files.
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> String {
+        self.path.clone()
+    }
+
+    /// Size in bytes
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> f64 {
+        self.file.borrow().size()
+    }
+
+    /// MIME type, empty when unknown
+    #[wasm_bindgen(getter, js_name = "type")]
+    pub fn mime_type(&self) -> String {
+        self.file.borrow().type_()
+    }
+
+    #[wasm_bindgen(getter, js_name = "lastModified")]
+    pub fn last_modified(&self) -> f64 {
+        self.file.borrow().last_modified()
+    }
+
+    /// Whether `save()` writes back to the file itself rather than
+    /// downloading a copy.
+    #[wasm_bindgen(getter, js_name = "canWrite")]
+    pub fn can_write(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    /// Reads the contents as a `Uint8Array`.
+    #[wasm_bindgen(js_name = "bytes")]
+    pub fn bytes_js(&self) -> Promise {
+        let buffer = self.file.borrow().array_buffer();
+
+        future_to_promise(async move {
+            let buffer = JsFuture::from(buffer).await?;
+            Ok(Uint8Array::new(&buffer).into())
+        })
+    }
+
+    /// Reads the contents as a string.
+    #[wasm_bindgen(js_name = "text")]
+    pub fn text_js(&self) -> Promise {
+        self.file.borrow().text()
+    }
+
+    /// Replaces the file's contents with `data` (a string, `Blob`,
+    /// `ArrayBuffer` or typed array). Resolves to `true` when the file was
+    /// written in place, and `false` when a copy was downloaded instead.
+    #[wasm_bindgen(js_name = "save")]
+    pub fn save_js(&self, data: JsValue) -> Promise {
+        let this = self.clone();
+
+        future_to_promise(async move {
+            let blob = to_blob(&data, &this.mime_type())?;
+
+            Ok(this.write_blob(&blob).await?.into())
+        })
+    }
+}
+
+fn picker(window: &Window, name: &str) -> Option<Function> {
+    get(window, name).and_then(|picker| picker.dyn_into::<Function>().ok())
+}
+
+/// Calls a native picker, resolving to `None` when the user cancels it.
+async fn call_picker(window: &Window, picker: &Function, options: &JsValue) -> Result<Option<JsValue>, JsValue> {
+    let promise = picker.call1(window, options)?.unchecked_into::<Promise>();
+
+    match JsFuture::from(promise).await {
+        Ok(result) => Ok(Some(result)),
+        Err(e) if e.dyn_ref::<DomException>().is_some_and(|e| e.name() == "AbortError") => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    // Pickers expect `accept` to be a plain object, not a `Map`
+    Ok(value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
+
+fn to_blob(data: &JsValue, mime_type: &str) -> Result<Blob, JsValue> {
+    if let Some(blob) = data.dyn_ref::<Blob>() {
+        return Ok(blob.clone());
+    }
+
+    let mut options = BlobPropertyBag::new();
+    if !mime_type.is_empty() {
+        options.type_(mime_type);
+    }
+
+    Blob::new_with_str_sequence_and_options(&Array::of1(data), &options)
+}
+
+async fn write(handle: &FileSystemFileHandle, blob: &Blob) -> Result<(), JsValue> {
+    let writable = JsFuture::from(handle.create_writable())
+        .await?
+        .unchecked_into::<FileSystemWritableFileStream>();
+
+    JsFuture::from(writable.write(blob)).await?;
+    JsFuture::from(writable.close()).await?;
+
+    Ok(())
+}
+
+fn download(blob: &Blob, name: &str) -> Result<(), JsValue> {
+    let url = Url::create_object_url_with_blob(blob)?;
+    let anchor = document()?.create_element("a")?.unchecked_into::<HtmlAnchorElement>();
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+
+    // Revoking right away can cancel the download in some browsers
+    let revoke = Closure::once_into_js(move || {
+        let _ = Url::revoke_object_url(&url);
+    });
+    window()?.set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), 1_000)?;
+
+    Ok(())
+}
+
+/// Picks files with a hidden `<input type="file">`, resolving to an empty
+/// list when the user cancels. Browsers without the `cancel` event never
+/// resolve on cancel.
+async fn pick_with_input(multiple: bool, accept: &str, directory: bool) -> Result<Vec<File>, JsValue> {
+    let document = document()?;
+    let input = document.create_element("input")?.unchecked_into::<HtmlInputElement>();
+    input.set_type("file");
+    input.set_multiple(multiple);
+    input.set_accept(accept);
+    input.set_webkitdirectory(directory);
+    input.set_attribute("style", "display: none")?;
+
+    // Safari only fires `change` for inputs that are in the document
+    if let Some(body) = document.body() {
+        body.append_child(&input)?;
+    }
+
+    let mut subscriptions = Vec::new();
+    let settled = Promise::new(&mut |resolve, _| {
+        for event in ["change", "cancel"] {
+            let resolve = resolve.clone();
+            subscriptions.push(Subscription::listen(&input, event, move |_| {
+                let _ = resolve.call0(&JsValue::NULL);
+            }));
+        }
+    });
+    let subscriptions = subscriptions.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+    input.click();
+    JsFuture::from(settled).await?;
+    drop(subscriptions);
+    input.remove();
+
+    let files = input.files();
+    Ok((0..files.as_ref().map_or(0, |files| files.length()))
+        .filter_map(|index| files.as_ref()?.item(index))
+        .collect())
+}
+
+/// Lets the user pick one or more files, returning no files when the picker
+/// was cancelled. Must be called from a user gesture.
+pub async fn open_files(options: &OpenOptions) -> Result<Vec<FileHandle>, ClientError> {
+    Ok(pick_files(options).await?)
+}
+
+async fn pick_files(options: &OpenOptions) -> Result<Vec<FileHandle>, JsValue> {
+    let window = window()?;
+
+    match picker(&window, "showOpenFilePicker") {
+        Some(picker) => {
+            let handles = match call_picker(&window, &picker, &to_js(options)?).await? {
+                Some(handles) => handles.unchecked_into::<Array>(),
+                None => return Ok(Vec::new()),
+            };
+
+            let mut files = Vec::new();
+            for handle in handles.iter() {
+                let handle = handle.unchecked_into::<FileSystemFileHandle>();
+                let name = handle.unchecked_ref::<FileSystemHandle>().name();
+                files.push(FileHandle::from_handle(handle, name).await?);
+            }
+            Ok(files)
+        }
+        None => Ok(
+            pick_with_input(options.multiple, &accept_attribute(&options.types), false)
+                .await?
+                .into_iter()
+                .map(FileHandle::from_file)
+                .collect(),
+        ),
+    }
+}
+
+/// Lets the user pick a directory, returning every file in it, including
+/// subdirectories. Returns no files when the picker was cancelled.
+pub async fn open_directory() -> Result<Vec<FileHandle>, ClientError> {
+    Ok(pick_directory().await?)
+}
+
+async fn pick_directory() -> Result<Vec<FileHandle>, JsValue> {
+    let window = window()?;
+
+    match picker(&window, "showDirectoryPicker") {
+        Some(picker) => match call_picker(&window, &picker, &JsValue::UNDEFINED).await? {
+            Some(directory) => Ok(walk(directory.unchecked_into()).await?),
+            None => Ok(Vec::new()),
+        },
+        None => Ok(pick_with_input(true, "", true)
+            .await?
+            .into_iter()
+            .map(FileHandle::from_file)
+            .collect()),
+    }
+}
+
+/// Saves `blob` to a file the user picks, or downloads it as
+/// `suggested_name` where `showSaveFilePicker` is unsupported. Returns
+/// `None` when the picker was cancelled.
+pub async fn save_file(
+    blob: &Blob,
+    suggested_name: &str,
+    types: &[FileType],
+) -> Result<Option<FileHandle>, ClientError> {
+    Ok(pick_and_save(blob, suggested_name, types).await?)
+}
+
+async fn pick_and_save(blob: &Blob, suggested_name: &str, types: &[FileType]) -> Result<Option<FileHandle>, JsValue> {
+    let window = window()?;
+
+    if let Some(picker) = picker(&window, "showSaveFilePicker") {
+        let options = SaveOptions { suggested_name, types };
+        let handle = match call_picker(&window, &picker, &to_js(&options)?).await? {
+            Some(handle) => handle.unchecked_into::<FileSystemFileHandle>(),
+            None => return Ok(None),
+        };
+
+        write(&handle, blob).await?;
+        let name = handle.unchecked_ref::<FileSystemHandle>().name();
+        return Ok(Some(FileHandle::from_handle(handle, name).await?));
+    }
+
+    download(blob, suggested_name)?;
+
+    let mut options = FilePropertyBag::new();
+    options.type_(&blob.type_());
+    let file = File::new_with_blob_sequence_and_options(&Array::of1(blob), suggested_name, &options)?;
+
+    Ok(Some(FileHandle::from_file(file)))
+}
+
+/// Lets the user pick one or more files. Resolves to an array of
+/// `FileHandle`s, which is empty when the picker was cancelled.
+///
+/// Uses `showOpenFilePicker` where available, so the files can be saved
+/// back in place, and a hidden `<input type="file">` elsewhere. Must be
+/// called from a user gesture, e.g. a click handler.
+///
+/// ## Example
+///
+/// ```tsx
+/// const [file] = await openFiles({
+///   types: [{ description: "Markdown", accept: { "text/markdown": [".md"] } }],
+/// });
+///
+/// if (file) setContent(await file.text());
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Window/showOpenFilePicker)
+#[wasm_bindgen(js_name = "openFiles")]
+pub async fn open_files_js(options: JsValue) -> Result<Array, JsValue> {
+    let options: OpenOptions = if options.is_undefined() || options.is_null() {
+        OpenOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)?
+    };
+
+    Ok(pick_files(&options).await?.into_iter().map(JsValue::from).collect())
+}
+
+/// Lets the user pick a directory. Resolves to a `FileHandle` for every
+/// file in it, including subdirectories, with `path` relative to the
+/// picked directory. Empty when the picker was cancelled.
+///
+/// ## Example
+///
+/// ```tsx
+/// const files = await openDirectory();
+/// const notes = files.filter((file) => file.path.endsWith(".md"));
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Window/showDirectoryPicker)
+#[wasm_bindgen(js_name = "openDirectory")]
+pub async fn open_directory_js() -> Result<Array, JsValue> {
+    Ok(pick_directory().await?.into_iter().map(JsValue::from).collect())
+}
+
+async fn walk(root: FileSystemDirectoryHandle) -> Result<Vec<FileHandle>, JsValue> {
+    let mut files = Vec::new();
+    let mut directories = vec![(String::new(), root)];
+
+    while let Some((prefix, directory)) = directories.pop() {
+        let entries = directory.values();
+
+        loop {
+            let next = JsFuture::from(entries.next()?).await?;
+            if Reflect::get(&next, &"done".into())?.is_truthy() {
+                break;
+            }
+
+            let entry = Reflect::get(&next, &"value".into())?.unchecked_into::<FileSystemHandle>();
+            let path = format!("{}{}", prefix, entry.name());
+
+            match entry.kind().as_str() {
+                "directory" => directories.push((format!("{}/", path), entry.unchecked_into())),
+                _ => files.push(FileHandle::from_handle(entry.unchecked_into(), path).await?),
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Saves `data` (a string, `Blob`, `ArrayBuffer` or typed array) to a file
+/// the user picks. Resolves to the saved file's `FileHandle`, or
+/// `undefined` when the picker was cancelled.
+///
+/// Where `showSaveFilePicker` is unsupported, the file is downloaded as
+/// `suggestedName` instead, and the handle can't write back in place.
+///
+/// ## Example
+///
+/// ```tsx
+/// // "Save" writes back in place when it can, "Save as" otherwise
+/// if (handle?.canWrite) await handle.save(content);
+/// else handle = await saveFile(content, "notes.md", [{ accept: { "text/markdown": [".md"] } }]);
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Window/showSaveFilePicker)
+#[wasm_bindgen(js_name = "saveFile")]
+pub async fn save_file_js(
+    data: JsValue,
+    suggested_name: String,
+    types: JsValue,
+) -> Result<Option<FileHandle>, JsValue> {
+    let types: Vec<FileType> = if types.is_undefined() || types.is_null() {
+        Vec::new()
+    } else {
+        serde_wasm_bindgen::from_value(types)?
+    };
+    let mime_type = types
+        .iter()
+        .flat_map(|file_type| file_type.accept.keys())
+        .find(|mime_type| !mime_type.contains('*'))
+        .cloned()
+        .unwrap_or_default();
+    let blob = to_blob(&data, &mime_type)?;
+
+    pick_and_save(&blob, &suggested_name, &types).await
+}
+
+#[cfg(test)]
+mod files_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn accept_attribute_test() {
+        let types = vec![
+            FileType {
+                description: Some("Images".to_string()),
+                accept: BTreeMap::from([
+                    ("image/png".to_string(), vec![".png".to_string()]),
+                    ("image/jpeg".to_string(), vec![".jpg".to_string(), ".jpeg".to_string()]),
+                ]),
+            },
+            FileType {
+                description: None,
+                accept: BTreeMap::from([("image/png".to_string(), vec![".png".to_string()])]),
+            },
+        ];
+
+        assert_eq!(accept_attribute(&types), "image/jpeg,.jpg,.jpeg,image/png,.png");
+        assert_eq!(accept_attribute(&[]), "");
+    }
+
+    #[wasm_bindgen_test]
+    async fn file_handle_test() {
+        let mut options = FilePropertyBag::new();
+        options.type_("text/plain");
+        let file =
+            File::new_with_str_sequence_and_options(&Array::of1(&"hello".into()), "hello.txt", &options).unwrap();
+
+        let handle = FileHandle::from_file(file);
+        assert_eq!(handle.name(), "hello.txt");
+        assert_eq!(handle.path(), "hello.txt");
+        assert_eq!(handle.size(), 5.0);
+        assert_eq!(handle.mime_type(), "text/plain");
+        assert!(!handle.can_write());
+
+        let text = JsFuture::from(handle.text_js()).await.unwrap();
+        assert_eq!(text.as_string().unwrap(), "hello");
+
+        let bytes = JsFuture::from(handle.bytes_js()).await.unwrap();
+        assert_eq!(bytes.unchecked_into::<Uint8Array>().to_vec(), b"hello");
+
+        assert_eq!(handle.read_text().await.unwrap(), "hello");
+        assert_eq!(handle.read_bytes().await.unwrap(), b"hello");
+    }
+
+    #[wasm_bindgen_test]
+    async fn to_blob_test() {
+        let blob = to_blob(&"# Notes".into(), "text/markdown").unwrap();
+        assert_eq!(blob.size(), 7.0);
+        assert_eq!(blob.type_(), "text/markdown");
+
+        assert_eq!(to_blob(&blob.clone().into(), "").unwrap().type_(), "text/markdown");
+    }
+}
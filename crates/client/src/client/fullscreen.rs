@@ -1,48 +1,57 @@
 use js_sys::Boolean;
 use wasm_bindgen::prelude::*;
+use web_sys::Element;
 
-use crate::client::response::ClientResponse;
+use crate::client::{error::ClientError, response::ClientResponse, scope::document};
+
+fn document_element() -> Result<Option<Element>, ClientError> {
+    Ok(document()?.document_element())
+}
+
+/// Asks to show the whole document fullscreen.
+pub fn request_fullscreen() -> Result<(), ClientError> {
+    let document_element =
+        document_element()?.ok_or_else(|| ClientError::Failed("no document element".to_string()))?;
+
+    Ok(document_element.request_fullscreen()?)
+}
+
+/// Leaves fullscreen.
+pub fn exit_fullscreen() -> Result<(), ClientError> {
+    document()?.exit_fullscreen();
+    Ok(())
+}
+
+/// Whether the document is currently shown fullscreen.
+pub fn is_fullscreen() -> Result<bool, ClientError> {
+    Ok(document()?.fullscreen())
+}
 
 /// Request access to fullscreen and if allowed, provides fullscreen
 /// functionality to a DOM element.
 /// 
 /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Element/requestFullscreen)
 #[wasm_bindgen(js_name = "requestFullscreen")]
-pub async fn request_fullscreen() -> Result<ClientResponse, ClientResponse> {
-    let window = web_sys::window()
-        .expect("`window` doesn't exists. Make sure you are calling this function in the browser");
-    let document = window
-        .document()
-        .expect("type `Document` doesn't exist in the current scope!");
-    let document_element = document
-        .document_element()
-        .expect("error occured! expected document element");
+pub async fn request_fullscreen_js() -> Result<ClientResponse, ClientResponse> {
+    let document_element = document_element()?.ok_or_else(|| {
+        ClientResponse::new("error", "Failed to enable full-screen: no document element")
+    })?;
 
     match document_element.request_fullscreen() {
-        Ok(_) => {
-            return Ok(ClientResponse::new("success", "Enabled full-screen"));
-        }
-        Err(e) => {
-            return Err(ClientResponse::new(
-                "error",
-                &format!("Failed to enable full-screen: {:?}", e),
-            ));
-        }
+        Ok(_) => Ok(ClientResponse::new("success", "Enabled full-screen")),
+        Err(e) => Err(ClientResponse::new(
+            "error",
+            &format!("Failed to enable full-screen: {:?}", e),
+        )),
     }
 }
 
 /// Exits fullscreen on the document. *Duh*
 /// 
-/// [MDN Documentation]()
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Document/exitFullscreen)
 #[wasm_bindgen(js_name = "exitFullscreen")]
-pub async fn exit_fullscreen() {
-    let window = web_sys::window()
-        .expect("`window` doesn't exists. Make sure you are calling this function in the browser");
-    let document = window
-        .document()
-        .expect("type `Document` doesn't exist in the current scope!");
-
-    document.exit_fullscreen();
+pub async fn exit_fullscreen_js() -> Result<(), JsValue> {
+    Ok(exit_fullscreen()?)
 }
 
 /// Check wether the document is currently in fullscreen mode or not.
@@ -50,21 +59,12 @@ pub async fn exit_fullscreen() {
 /// 
 /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Document/fullscreen)
 #[wasm_bindgen(js_name = "isFullscreen")]
-pub async fn is_fullscreen() -> Result<Boolean, JsValue> {
-    let window = web_sys::window()
-        .expect("`window` doesn't exists. Make sure you are calling this function in the browser");
-    let document = window
-        .document()
-        .expect("type `Document` doesn't exist in the current scope!");
-
-    if document.is_undefined() {
-        return Err(JsValue::from_str("document is undefined"));
-    } else {
-        return Ok(js_sys::Boolean::from(document.fullscreen()));
-    }
+pub async fn is_fullscreen_js() -> Result<Boolean, JsValue> {
+    Ok(js_sys::Boolean::from(is_fullscreen()?))
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod fullscreen_tests {
     use super::*;
     use wasm_bindgen_test::*;
@@ -73,21 +73,20 @@ mod fullscreen_tests {
 
     #[wasm_bindgen_test]
     async fn test_request_fullscreen() {
-        let result = request_fullscreen().await;
+        let result = request_fullscreen_js().await;
         assert_eq!(result.is_ok(), true);
         assert_eq!(result.unwrap().get_status(), "success");
     }
 
-    fn test_exit_fullscreen() {
-        // What other test could fit in here?
-        assert_eq!(5, 5);
-        todo!("Fit assertions in here");
-        // exit_fullscreen().await;
+    #[wasm_bindgen_test]
+    async fn test_exit_fullscreen() {
+        let result = exit_fullscreen_js().await;
+        assert!(result.is_ok());
     }
 
     #[wasm_bindgen_test]
     async fn test_is_fullscreen() {
-        let result = is_fullscreen().await;
+        let result = is_fullscreen_js().await;
         assert_eq!(result.is_ok(), true);
     }
 }
@@ -1,6 +1,103 @@
-use js_sys::Function;
-use wasm_bindgen::prelude::*;
-use web_sys::Geolocation;
+use js_sys::{Function, Object, Promise, Reflect};
+use serde::Serialize;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Geolocation, PositionOptions};
+
+use crate::client::{
+    capabilities::{get, has},
+    error::ClientError,
+    scope::window,
+};
+
+/// Options for [`current_position`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PositionRequest {
+    /// Prefer GPS over Wi-Fi and cell towers, at the cost of battery and time
+    pub enable_high_accuracy: bool,
+    /// Give up after this long
+    pub timeout_ms: Option<u32>,
+    /// Accept a cached position up to this old
+    pub maximum_age_ms: Option<u32>,
+}
+
+/// Where the device is. Distances are in meters, `heading` in degrees
+/// clockwise from true north and `speed` in meters per second.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub accuracy: f64,
+    pub altitude: Option<f64>,
+    pub altitude_accuracy: Option<f64>,
+    pub heading: Option<f64>,
+    pub speed: Option<f64>,
+    /// When the position was determined, in milliseconds since the epoch
+    pub timestamp: f64,
+}
+
+impl Position {
+    fn from_js(position: &JsValue) -> Option<Position> {
+        let coords = get(position, "coords")?;
+        let number = |key| get(&coords, key).and_then(|value| value.as_f64());
+
+        Some(Position {
+            latitude: number("latitude")?,
+            longitude: number("longitude")?,
+            accuracy: number("accuracy")?,
+            altitude: number("altitude"),
+            altitude_accuracy: number("altitudeAccuracy"),
+            heading: number("heading").filter(|heading| !heading.is_nan()),
+            speed: number("speed"),
+            timestamp: get(position, "timestamp").and_then(|value| value.as_f64()).unwrap_or_default(),
+        })
+    }
+}
+
+/// The `Geolocation` object, on browsers that have one.
+pub fn geolocation() -> Result<Geolocation, ClientError> {
+    let navigator = window()?.navigator();
+
+    if !has(&navigator, "geolocation") {
+        return Err(ClientError::Unsupported("Geolocation API"));
+    }
+
+    Ok(navigator.geolocation()?)
+}
+
+/// Locates the device once. Fails when the user denies the permission, the
+/// position can't be determined or `timeout_ms` runs out.
+pub async fn current_position(request: &PositionRequest) -> Result<Position, ClientError> {
+    let geolocation = geolocation()?;
+
+    let options = Object::new();
+    Reflect::set(&options, &"enableHighAccuracy".into(), &request.enable_high_accuracy.into())?;
+    if let Some(timeout) = request.timeout_ms {
+        Reflect::set(&options, &"timeout".into(), &timeout.into())?;
+    }
+    if let Some(maximum_age) = request.maximum_age_ms {
+        Reflect::set(&options, &"maximumAge".into(), &maximum_age.into())?;
+    }
+
+    let mut result = Ok(());
+    let promise = Promise::new(&mut |resolve, reject| {
+        result = geolocation.get_current_position_with_error_callback_and_options(
+            &resolve,
+            Some(&reject),
+            options.unchecked_ref::<PositionOptions>(),
+        );
+    });
+    result?;
+
+    let position = JsFuture::from(promise).await.map_err(|error| {
+        // A `GeolocationPositionError` isn't an `Error`, only its message helps
+        let message = get(&error, "message").and_then(|message| message.as_string());
+        ClientError::Failed(message.unwrap_or_else(|| "Failed to get the current position".to_string()))
+    })?;
+
+    Position::from_js(&position).ok_or_else(|| ClientError::Failed("The position has no coordinates".to_string()))
+}
 
 /// Returns a `Geolocation` object that allows you to 
 /// determine the position of the user's device programatically.
@@ -9,40 +106,27 @@ use web_sys::Geolocation;
 /// 
 /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Navigator/geolocation)
 #[wasm_bindgen(js_name = "getGeolocationObject")]
-pub async fn get_geolocation_object() -> Result<Geolocation, JsValue> {
-    let window = web_sys::window().expect("no global `window` exists");
-    let navigator = window.navigator();
-
-    let geolocation = navigator.geolocation().unwrap();
-
-    Ok(geolocation)
+pub async fn get_geolocation_object_js() -> Result<Geolocation, JsValue> {
+    Ok(geolocation()?)
 }
 
-// todo!("Add geolocation getPosition but with error callback and also with options");
-
 /// Returns the current position of the user's device. Takes in a 
 /// success callback that would be incoked when the user's device 
 /// has been successfully located
 /// 
 /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Geolocation/getCurrentPosition)
 #[wasm_bindgen(js_name = "getCurrentPosition")]
-pub async fn get_current_position(success_callback: &Function) -> Result<(), JsValue> {
-    let window = web_sys::window().expect("no global `window` exists");
-    let navigator = window.navigator();
-
-    let geolocation = navigator.geolocation().unwrap();
-    match geolocation.get_current_position(success_callback) {
-        Ok(_) => {
-          return Ok(());
-        },
-        Err(e) => {
-            log::error!("Error getting current position: {:?}", e);
-            return Err(e);
-        }
-    };
+pub async fn get_current_position_js(success_callback: &Function) -> Result<(), JsValue> {
+    let geolocation = get_geolocation_object_js().await?;
+
+    geolocation.get_current_position(success_callback).map_err(|e| {
+        log::error!("Error getting current position: {:?}", e);
+        e
+    })
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod geolocation_tests {
     use std::ops::Not;
 
@@ -51,16 +135,28 @@ mod geolocation_tests {
 
     #[wasm_bindgen_test]
     async fn get_geolocation_object_test() {
-        let geolocation = get_geolocation_object().await.unwrap();
+        let geolocation = get_geolocation_object_js().await.unwrap();
 
         assert_eq!(geolocation.is_null().not(), true);
     }
 
+    #[wasm_bindgen_test]
+    fn position_test() {
+        let position = js_sys::JSON::parse(
+            r#"{ "coords": { "latitude": 6.5, "longitude": 3.4, "accuracy": 20, "speed": null }, "timestamp": 1 }"#,
+        )
+        .unwrap();
+
+        let position = Position::from_js(&position).unwrap();
+        assert_eq!((position.latitude, position.longitude), (6.5, 3.4));
+        assert_eq!(position.speed, None);
+    }
+
     #[wasm_bindgen_test]
     async fn get_current_position_test() {
         let success_callback = Function::new_no_args("console.log('Woohoo! We are finally live somewhere in the world...')");
 
-        let result = get_current_position(&success_callback).await;
+        let result = get_current_position_js(&success_callback).await;
 
         assert_eq!(result.is_ok(), true);
     }
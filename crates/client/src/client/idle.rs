@@ -0,0 +1,392 @@
+use std::{cell::RefCell, rc::Rc};
+
+use js_sys::{Date, Object, Promise, Reflect};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortController, EventTarget, PermissionState, VisibilityState};
+
+use crate::client::{
+    capabilities::has,
+    error::ClientError,
+    permissions::permission_state,
+    scope::{document, window},
+    subscription::Subscription,
+    visibility::visibility_state,
+};
+
+/// The shortest threshold `IdleDetector` accepts, in milliseconds
+pub const MIN_THRESHOLD_MS: u32 = 60_000;
+
+/// How often the fallback checks for inactivity, in milliseconds
+const CHECK_MS: i32 = 5_000;
+
+/// Events counting as user activity for the fallback
+const ACTIVITY_EVENTS: [&str; 5] = ["pointerdown", "pointermove", "keydown", "wheel", "touchstart"];
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = EventTarget)]
+    #[derive(Clone, Debug)]
+    type IdleDetector;
+
+    #[wasm_bindgen(constructor, catch)]
+    fn new() -> Result<IdleDetector, JsValue>;
+
+    #[wasm_bindgen(method, getter, js_name = "userState")]
+    fn user_state(this: &IdleDetector) -> Option<String>;
+
+    #[wasm_bindgen(method, getter, js_name = "screenState")]
+    fn screen_state(this: &IdleDetector) -> Option<String>;
+
+    #[wasm_bindgen(method, catch)]
+    fn start(this: &IdleDetector, options: &JsValue) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(static_method_of = IdleDetector, js_name = "requestPermission", catch)]
+    fn request_permission() -> Result<Promise, JsValue>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserState {
+    Active,
+    Idle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenState {
+    Locked,
+    Unlocked,
+}
+
+/// What `onIdleChange` reports.
+///
+/// `screenState` is only known with `IdleDetector`; the fallback always
+/// reports `"unlocked"`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleState {
+    pub user_state: UserState,
+    pub screen_state: ScreenState,
+}
+
+impl IdleState {
+    fn from_detector(detector: &IdleDetector) -> Option<IdleState> {
+        Some(IdleState {
+            user_state: match detector.user_state()?.as_str() {
+                "idle" => UserState::Idle,
+                _ => UserState::Active,
+            },
+            screen_state: match detector.screen_state()?.as_str() {
+                "locked" => ScreenState::Locked,
+                _ => ScreenState::Unlocked,
+            },
+        })
+    }
+}
+
+/// Options for `onIdleChange`. Every field is optional.
+///
+/// ## Attributes
+/// `thresholdMs`: inactivity before the user counts as idle. Defaults to,
+/// and can't go below, one minute
+///
+/// `fallback`: watch input events when `IdleDetector` is unavailable or
+/// not permitted, instead of throwing. Defaults to `true`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IdleOptions {
+    pub threshold_ms: u32,
+    pub fallback: bool,
+}
+
+impl Default for IdleOptions {
+    fn default() -> Self {
+        IdleOptions {
+            threshold_ms: MIN_THRESHOLD_MS,
+            fallback: true,
+        }
+    }
+}
+
+impl IdleOptions {
+    fn threshold(&self) -> u32 {
+        self.threshold_ms.max(MIN_THRESHOLD_MS)
+    }
+}
+
+/// The fallback's verdict for a user last seen at `last_activity`.
+fn user_state(last_activity: f64, now: f64, threshold: u32) -> UserState {
+    match now - last_activity >= threshold as f64 {
+        true => UserState::Idle,
+        false => UserState::Active,
+    }
+}
+
+fn detector_supported() -> bool {
+    has(&js_sys::global(), "IdleDetector")
+}
+
+/// Whether the `idle-detection` permission is known to be granted.
+async fn permission_granted() -> bool {
+    permission_state("idle-detection").await.ok() == Some(PermissionState::Granted)
+}
+
+/// Keeps the browser's own rejection, e.g. when not called from a user
+/// gesture, so `requestIdlePermission` can reject with it as is.
+async fn request_permission_state() -> Result<String, JsValue> {
+    if !detector_supported() {
+        return Err(ClientError::Unsupported("Idle Detection API").into());
+    }
+
+    if permission_granted().await {
+        return Ok("granted".to_string());
+    }
+
+    let state = JsFuture::from(IdleDetector::request_permission()?).await?;
+    Ok(state.as_string().unwrap_or_else(|| "denied".to_string()))
+}
+
+/// Asks for the `idle-detection` permission, unless it's already granted.
+/// Has to be called from a user gesture.
+pub async fn request_idle_permission() -> Result<PermissionState, ClientError> {
+    let state = request_permission_state().await?;
+
+    Ok(PermissionState::from_js_value(&state.into()).unwrap_or(PermissionState::Denied))
+}
+
+/// Asks for the `idle-detection` permission, unless it's already granted.
+/// Resolves with `"granted"` or `"denied"`.
+///
+/// Has to be called from a user gesture, such as a click handler.
+///
+/// ## Example
+///
+/// ```tsx
+/// <button onClick={() => requestIdlePermission()}>Set me away when idle</button>
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/IdleDetector/requestPermission_static)
+#[wasm_bindgen(js_name = "requestIdlePermission")]
+pub async fn request_idle_permission_js() -> Result<String, JsValue> {
+    request_permission_state().await
+}
+
+async fn watch_detector<F>(threshold: u32, callback: Rc<RefCell<F>>) -> Result<Subscription, JsValue>
+where
+    F: FnMut(IdleState) + 'static,
+{
+    let detector = IdleDetector::new()?;
+    let controller = AbortController::new()?;
+
+    let options = Object::new();
+    Reflect::set(&options, &"threshold".into(), &threshold.into())?;
+    Reflect::set(&options, &"signal".into(), &controller.signal())?;
+
+    let on_change = Subscription::listen(&detector, "change", {
+        let detector = detector.clone();
+        let callback = callback.clone();
+        move |_| {
+            if let Some(state) = IdleState::from_detector(&detector) {
+                (callback.borrow_mut())(state);
+            }
+        }
+    })?;
+
+    JsFuture::from(detector.start(&options)?).await?;
+    if let Some(state) = IdleState::from_detector(&detector) {
+        (callback.borrow_mut())(state);
+    }
+
+    Ok(Subscription::new(move || {
+        controller.abort();
+        drop(on_change);
+    }))
+}
+
+struct Fallback<F> {
+    last_activity: f64,
+    state: UserState,
+    callback: F,
+}
+
+impl<F: FnMut(IdleState)> Fallback<F> {
+    fn update(&mut self, state: UserState) {
+        if self.state != state {
+            self.state = state;
+            (self.callback)(IdleState {
+                user_state: state,
+                screen_state: ScreenState::Unlocked,
+            });
+        }
+    }
+}
+
+/// Watches input events and visibility. Hidden pages get no input, so
+/// leaving the tab counts towards the threshold, and coming back is
+/// activity.
+fn watch_fallback<F>(threshold: u32, callback: F) -> Result<Subscription, JsValue>
+where
+    F: FnMut(IdleState) + 'static,
+{
+    let window = window()?;
+    let document = document()?;
+
+    let fallback = Rc::new(RefCell::new(Fallback {
+        last_activity: Date::now(),
+        state: UserState::Active,
+        callback,
+    }));
+    (fallback.borrow_mut().callback)(IdleState {
+        user_state: UserState::Active,
+        screen_state: ScreenState::Unlocked,
+    });
+
+    let on_activity = {
+        let fallback = fallback.clone();
+        move |_| {
+            let mut fallback = fallback.borrow_mut();
+            fallback.last_activity = Date::now();
+            fallback.update(UserState::Active);
+        }
+    };
+
+    let mut subscriptions = ACTIVITY_EVENTS
+        .iter()
+        .map(|event| Subscription::listen(&window, event, on_activity.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    subscriptions.push(Subscription::listen(&document, "visibilitychange", move |event| {
+        if visibility_state().ok() == Some(VisibilityState::Visible) {
+            on_activity(event);
+        }
+    })?);
+
+    let tick = Closure::<dyn FnMut()>::new(move || {
+        let mut fallback = fallback.borrow_mut();
+        let state = user_state(fallback.last_activity, Date::now(), threshold);
+        fallback.update(state);
+    });
+    let handle = window.set_interval_with_callback_and_timeout_and_arguments_0(tick.as_ref().unchecked_ref(), CHECK_MS)?;
+
+    subscriptions.push(Subscription::new(move || {
+        window.clear_interval_with_handle(handle);
+        drop(tick);
+    }));
+
+    Ok(Subscription::all(subscriptions))
+}
+
+/// Calls `callback` with the current state, then on every change. Uses
+/// `IdleDetector` once the `idle-detection` permission is granted, and the
+/// fallback otherwise.
+pub async fn on_idle_change<F>(options: IdleOptions, callback: F) -> Result<Subscription, ClientError>
+where
+    F: FnMut(IdleState) + 'static,
+{
+    Ok(watch(options, callback).await?)
+}
+
+/// Keeps `IdleDetector`'s own rejection, so `onIdleChange` can reject with
+/// it as is.
+async fn watch<F>(options: IdleOptions, callback: F) -> Result<Subscription, JsValue>
+where
+    F: FnMut(IdleState) + 'static,
+{
+    let threshold = options.threshold();
+
+    if detector_supported() && permission_granted().await {
+        let callback = Rc::new(RefCell::new(callback));
+        match watch_detector(threshold, callback.clone()).await {
+            Ok(subscription) => return Ok(subscription),
+            Err(e) if !options.fallback => return Err(e),
+            Err(e) => log::warn!("IdleDetector failed, watching input events instead: {:?}", e),
+        }
+
+        return watch_fallback(threshold, move |state| (callback.borrow_mut())(state));
+    }
+
+    if !options.fallback {
+        return Err(ClientError::Unsupported("Idle Detection API").into());
+    }
+
+    watch_fallback(threshold, callback)
+}
+
+/// Calls `callback` with `{ userState, screenState }` right away and
+/// whenever the user goes idle or comes back. Resolves with a
+/// `Subscription`.
+///
+/// Uses the Idle Detection API, which also notices activity outside the
+/// browser and screen locks, once `requestIdlePermission()` was granted.
+/// Otherwise, the user is idle after `thresholdMs` without input on the
+/// page.
+///
+/// ## Example
+///
+/// ```tsx
+/// useEffect(() => {
+///   const subscription = onIdleChange(({ userState }) => {
+///     setPresence(userState === "idle" ? "away" : "online");
+///   }, { thresholdMs: 5 * 60 * 1000 });
+///
+///   return () => void subscription.then((s) => s.unsubscribe());
+/// }, []);
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/IdleDetector)
+#[wasm_bindgen(js_name = "onIdleChange")]
+pub async fn on_idle_change_js(callback: js_sys::Function, options: JsValue) -> Result<Subscription, JsValue> {
+    let options: IdleOptions = if options.is_undefined() || options.is_null() {
+        IdleOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)?
+    };
+
+    watch(options, move |state| {
+        let state = match serde_wasm_bindgen::to_value(&state) {
+            Ok(state) => state,
+            Err(e) => return log::error!("Failed to serialize idle state: {:?}", e),
+        };
+        if let Err(e) = callback.call1(&JsValue::NULL, &state) {
+            log::error!("Error in idle callback: {:?}", e);
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod idle_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn user_state_test() {
+        assert_eq!(user_state(0.0, 59_999.0, MIN_THRESHOLD_MS), UserState::Active);
+        assert_eq!(user_state(0.0, 60_000.0, MIN_THRESHOLD_MS), UserState::Idle);
+        assert_eq!(user_state(1_000.0, 100_000.0, 120_000), UserState::Active);
+    }
+
+    #[test]
+    fn threshold_test() {
+        let options = IdleOptions {
+            threshold_ms: 1_000,
+            ..IdleOptions::default()
+        };
+        assert_eq!(options.threshold(), MIN_THRESHOLD_MS);
+        assert_eq!(IdleOptions::default().threshold(), MIN_THRESHOLD_MS);
+    }
+
+    #[wasm_bindgen_test]
+    async fn fallback_test() {
+        let states = Rc::new(RefCell::new(Vec::new()));
+        let seen = states.clone();
+        let subscription = watch_fallback(MIN_THRESHOLD_MS, move |state| seen.borrow_mut().push(state)).unwrap();
+
+        assert!(subscription.is_active());
+        assert_eq!(states.borrow()[0].user_state, UserState::Active);
+    }
+}
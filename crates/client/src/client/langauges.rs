@@ -1,9 +1,18 @@
 use js_sys::Array;
 use wasm_bindgen::prelude::*;
 
+use crate::client::{error::ClientError, scope::navigator};
+
+/// The user's languages, most preferred first.
+pub fn languages() -> Result<Vec<String>, ClientError> {
+    Ok(navigator()?.languages().iter().filter_map(|l| l.as_string()).collect())
+}
+
 /// Returns the languages of the browser in an array
 /// that's ordered by user preference.
 /// 
+/// *Also available in workers*
+/// 
 /// ## Example
 /// 
 /// ```tsx
@@ -13,15 +22,11 @@ use wasm_bindgen::prelude::*;
 /// 
 /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Navigator/languages)
 #[wasm_bindgen(js_name = "getLanguages")]
-pub async fn get_languages() -> Result<Array, JsValue> {
-    let languages = web_sys::window()
-        .expect("Can't find the window object. Ensure you are calling this API in the browser")
-        .navigator()
-        .languages();
+pub async fn get_languages_js() -> Result<Array, JsValue> {
+    let languages = languages()?;
 
-    let languages_vec = languages.iter().map(|l| l.as_string().unwrap()).collect::<Vec<String>>();
-    log::info!("Languages: {:?}", languages_vec);
-    Ok(languages)
+    log::info!("Languages: {:?}", languages);
+    Ok(languages.iter().map(|language| JsValue::from_str(language)).collect())
 }
 
 #[cfg(test)]
@@ -33,7 +38,8 @@ mod languages_tests {
 
     #[wasm_bindgen_test]
     async fn get_languages_test() {
-        let languages = get_languages().await;
+        let languages = get_languages_js().await;
         assert!(languages.is_ok());
+        assert_eq!(languages.unwrap().length() as usize, super::languages().unwrap().len());
     }
 }
\ No newline at end of file
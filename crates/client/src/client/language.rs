@@ -1,10 +1,19 @@
 use log::{error, info};
 use wasm_bindgen::prelude::*;
-use web_sys::window;
 
+use crate::client::{error::ClientError, scope::navigator};
+
+/// The user's preferred language, such as `"en-US"`.
+pub fn language() -> Result<String, ClientError> {
+    navigator()?
+        .language()
+        .ok_or_else(|| ClientError::Failed("Error occured whilst getting language!".to_string()))
+}
 
 /// Gets the language of the user's browser.
 /// 
+/// *Also available in workers*
+/// 
 /// ## Example
 /// 
 /// ```tsx
@@ -14,16 +23,20 @@ use web_sys::window;
 /// 
 /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Navigator/language)
 #[wasm_bindgen(js_name = "getLanguage")]
-pub async fn get_language() -> Result<String, JsValue> {
-    let window = window().expect("Error occured! Are you calling this function in the server?");
-    let navigator = window.navigator();
-
-    if let Some(language) = navigator.language() {
-        info!("Language: {}", language);
-        return Ok(language);
-    } else {
-        error!("Error occured whilst getting language!");
-        return Err(JsValue::from("Error occured whilst getting language!"));
+pub async fn get_language_js() -> Result<String, JsValue> {
+    match language() {
+        Ok(language) => {
+            info!("Language: {}", language);
+            Ok(language)
+        }
+        Err(e) => {
+            error!("{}", e);
+            match e {
+                // Rejects with the bare message, as it always has
+                ClientError::Failed(message) => Err(message.into()),
+                e => Err(e.into()),
+            }
+        }
     }
 }
 
@@ -36,7 +49,7 @@ mod language_tests {
 
     #[wasm_bindgen_test]
     async fn get_language_test() {
-        let language = get_language().await;
+        let language = get_language_js().await;
         assert!(language.is_ok());
     }
 }
\ No newline at end of file
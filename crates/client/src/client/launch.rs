@@ -0,0 +1,241 @@
+use std::{cell::RefCell, rc::Rc};
+
+use js_sys::{Array, Function};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::spawn_local;
+
+use crate::client::{
+    capabilities::get,
+    files::{FileHandle, FileSystemHandle},
+    subscription::Subscription,
+};
+
+/// Files (and/or a URL) the installed app was launched with, e.g. after the
+/// user picked "Open with" on a file matching the manifest's
+/// `file_handlers`.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/LaunchParams)
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Launch {
+    #[wasm_bindgen(skip)]
+    pub target_url: Option<String>,
+    /// Directories are skipped, only files are included
+    #[wasm_bindgen(skip)]
+    pub files: Vec<FileHandle>,
+}
+
+impl Launch {
+    async fn from_params(params: &JsValue) -> Result<Launch, JsValue> {
+        let target_url = get(params, "targetURL").and_then(|url| url.as_string());
+        let handles = get(params, "files").map(|files| Array::from(&files)).unwrap_or_default();
+
+        let mut files = Vec::new();
+        for handle in handles.iter() {
+            let handle = handle.unchecked_into::<FileSystemHandle>();
+            if handle.kind() == "file" {
+                let name = handle.name();
+                files.push(FileHandle::from_handle(handle.unchecked_into(), name).await?);
+            }
+        }
+
+        Ok(Launch { target_url, files })
+    }
+}
+
+#[wasm_bindgen]
+impl Launch {
+    /// The URL the app was launched at, if any.
+    #[wasm_bindgen(getter, js_name = "targetURL")]
+    pub fn target_url_js(&self) -> Option<String> {
+        self.target_url.clone()
+    }
+
+    /// An array of `FileHandle`s. Writable in place, so "Save" updates the
+    /// opened file.
+    #[wasm_bindgen(getter, js_name = "files")]
+    pub fn files_js(&self) -> Array {
+        self.files.iter().cloned().map(JsValue::from).collect()
+    }
+}
+
+type Consumer = Rc<RefCell<dyn FnMut(Launch)>>;
+
+#[derive(Default)]
+struct LaunchQueue {
+    initialized: bool,
+    supported: bool,
+    /// Launches received while nobody was subscribed
+    pending: Vec<JsValue>,
+    consumers: Vec<(u32, Consumer)>,
+    next_id: u32,
+}
+
+thread_local! {
+    static QUEUE: RefCell<LaunchQueue> = RefCell::new(LaunchQueue::default());
+}
+
+/// Registers this crate as the `launchQueue` consumer, once. Called when
+/// the module loads, so launches that happen before the app subscribes are
+/// buffered instead of lost. Returns whether the browser supports launch
+/// handling.
+pub fn init() -> bool {
+    if let Some(supported) = QUEUE.with(|queue| {
+        let queue = queue.borrow();
+        queue.initialized.then_some(queue.supported)
+    }) {
+        return supported;
+    }
+
+    let set_consumer = web_sys::window()
+        .and_then(|window| get(&window, "launchQueue"))
+        .and_then(|launch_queue| Some((get(&launch_queue, "setConsumer")?.dyn_into::<Function>().ok()?, launch_queue)));
+
+    let supported = match set_consumer {
+        Some((set_consumer, launch_queue)) => {
+            // The consumer lives as long as the page, like the queue itself
+            let consumer = Closure::<dyn FnMut(JsValue)>::new(dispatch);
+            let registered = set_consumer.call1(&launch_queue, consumer.as_ref()).is_ok();
+            consumer.forget();
+            registered
+        }
+        None => false,
+    };
+
+    QUEUE.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        queue.initialized = true;
+        queue.supported = supported;
+    });
+
+    supported
+}
+
+fn dispatch(params: JsValue) {
+    let consumers = QUEUE.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        if queue.consumers.is_empty() {
+            queue.pending.push(params.clone());
+        }
+        queue.consumers.iter().map(|(_, consumer)| consumer.clone()).collect::<Vec<_>>()
+    });
+
+    if !consumers.is_empty() {
+        deliver(params, consumers);
+    }
+}
+
+fn deliver(params: JsValue, consumers: Vec<Consumer>) {
+    spawn_local(async move {
+        match Launch::from_params(&params).await {
+            Ok(launch) => {
+                for consumer in consumers {
+                    (consumer.borrow_mut())(launch.clone());
+                }
+            }
+            Err(e) => log::error!("Error reading launch files: {:?}", e),
+        }
+    });
+}
+
+fn subscribe(consumer: impl FnMut(Launch) + 'static) -> Subscription {
+    let consumer: Consumer = Rc::new(RefCell::new(consumer));
+
+    let (id, pending) = QUEUE.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        let id = queue.next_id;
+        queue.next_id += 1;
+        queue.consumers.push((id, consumer.clone()));
+        (id, std::mem::take(&mut queue.pending))
+    });
+
+    for params in pending {
+        deliver(params, vec![consumer.clone()]);
+    }
+
+    Subscription::new(move || {
+        QUEUE.with(|queue| queue.borrow_mut().consumers.retain(|(consumer_id, _)| *consumer_id != id));
+    })
+}
+
+/// Calls `consumer` for every launch of the installed app, starting with
+/// any that happened before subscribing. Does nothing in browsers without
+/// launch handling.
+pub fn on_launch(consumer: impl FnMut(Launch) + 'static) -> Subscription {
+    if !init() {
+        return Subscription::noop();
+    }
+
+    subscribe(consumer)
+}
+
+/// Receives files opened with the installed PWA, for apps declaring
+/// `file_handlers` in their manifest. Launches that happened before
+/// subscribing, e.g. the one that started the app, are delivered right
+/// away.
+///
+/// Returns a handle whose `unsubscribe()` stops delivery. Never throws: in
+/// browsers without launch handling the callback is simply never called.
+///
+/// ## Example
+///
+/// ```tsx
+/// useEffect(() => {
+///   const subscription = onLaunch(async (launch) => {
+///     const [file] = launch.files;
+///     if (file) openDocument(file, await file.text());
+///   });
+///   return () => subscription.unsubscribe();
+/// }, []);
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/LaunchQueue/setConsumer)
+#[wasm_bindgen(js_name = "onLaunch")]
+pub fn on_launch_js(callback: Function) -> Subscription {
+    on_launch(move |launch| {
+        if let Err(e) = callback.call1(&JsValue::NULL, &launch.into()) {
+            log::error!("Error in launch callback: {:?}", e);
+        }
+    })
+}
+
+#[cfg(test)]
+mod launch_tests {
+    use super::*;
+    use js_sys::Reflect;
+    use std::cell::Cell;
+    use wasm_bindgen_futures::JsFuture;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    async fn tick() {
+        for _ in 0..2 {
+            JsFuture::from(js_sys::Promise::resolve(&JsValue::NULL)).await.unwrap();
+        }
+    }
+
+    #[wasm_bindgen_test]
+    async fn buffered_launch_test() {
+        let params = js_sys::Object::new();
+        Reflect::set(&params, &"targetURL".into(), &"https://example.com/editor".into()).unwrap();
+        Reflect::set(&params, &"files".into(), &Array::new()).unwrap();
+
+        // Nobody is subscribed yet, so the launch has to wait
+        dispatch(params.into());
+
+        let received = Rc::new(Cell::new(0));
+        let counter = received.clone();
+        let mut subscription = subscribe(move |launch| {
+            assert_eq!(launch.target_url.as_deref(), Some("https://example.com/editor"));
+            assert!(launch.files.is_empty());
+            counter.set(counter.get() + 1);
+        });
+
+        tick().await;
+        assert_eq!(received.get(), 1);
+
+        subscription.unsubscribe();
+        assert!(QUEUE.with(|queue| queue.borrow().consumers.is_empty()));
+    }
+}
@@ -0,0 +1,129 @@
+use js_sys::{Array, Function};
+use locale::{filter, negotiate, negotiate_accept_language, LanguageTag};
+use wasm_bindgen::{prelude::*, JsCast};
+
+use crate::client::{
+    error::ClientError,
+    scope::{navigator, GlobalScope},
+    subscription::Subscription,
+};
+
+/// The user's preferred locales, most preferred first.
+pub fn preferred_locales() -> Result<Vec<String>, ClientError> {
+    let navigator = navigator()?;
+    let languages = navigator
+        .languages()
+        .iter()
+        .filter_map(|language| language.as_string())
+        .collect::<Vec<_>>();
+
+    if languages.is_empty() {
+        return Ok(navigator.language().into_iter().collect());
+    }
+
+    Ok(languages)
+}
+
+fn strings(array: &Array) -> Vec<String> {
+    array.iter().filter_map(|value| value.as_string()).collect()
+}
+
+/// Negotiates the best of the `supported` locales against the user's
+/// preferred ones, see [`negotiate`].
+pub fn negotiate_preferred(supported: &[String], default_locale: &str) -> Result<String, ClientError> {
+    Ok(negotiate(&preferred_locales()?, supported, default_locale))
+}
+
+/// Calls `callback` with the user's preferred locales whenever they change.
+pub fn on_locales_change(mut callback: impl FnMut(Vec<String>) + 'static) -> Result<Subscription, ClientError> {
+    let target: web_sys::EventTarget = match GlobalScope::current()? {
+        GlobalScope::Window(window) => window.unchecked_into(),
+        GlobalScope::Worker(worker) => worker.unchecked_into(),
+    };
+
+    Ok(Subscription::listen(&target, "languagechange", move |_| match preferred_locales() {
+        Ok(languages) => callback(languages),
+        Err(e) => log::error!("Error reading languages: {:?}", e),
+    })?)
+}
+
+/// Negotiates the best locale your app supports against the user's
+/// preferred languages (`navigator.languages`). Falls back to region and
+/// script variants before giving up and returning `defaultLocale`.
+///
+/// *Also available in workers*
+///
+/// ## Example
+///
+/// ```tsx
+/// const locale = await negotiateLocale(["en-US", "fr", "zh-Hant"], "en-US");
+/// // navigator.languages = ["zh-TW", "en"] -> "zh-Hant"
+/// ```
+///
+/// [RFC 4647](https://www.rfc-editor.org/rfc/rfc4647)
+#[wasm_bindgen(js_name = "negotiateLocale")]
+pub async fn negotiate_locale_js(supported: Array, default_locale: String) -> Result<String, JsValue> {
+    Ok(negotiate_preferred(&strings(&supported), &default_locale)?)
+}
+
+/// Returns every supported locale that matches one of the user's preferred
+/// languages, most preferred first.
+///
+/// *Also available in workers*
+///
+/// [RFC 4647](https://www.rfc-editor.org/rfc/rfc4647#section-3.3.1)
+#[wasm_bindgen(js_name = "filterLocales")]
+pub async fn filter_locales_js(supported: Array) -> Result<Array, JsValue> {
+    let filtered = filter(&preferred_locales()?, &strings(&supported));
+    Ok(filtered.into_iter().map(JsValue::from).collect())
+}
+
+/// Negotiates a locale from an `Accept-Language` header, with the same
+/// rules as `negotiateLocale`. Useful when the header is forwarded to the
+/// client, e.g. from a Remix loader, so both sides agree on the language.
+///
+/// ## Example
+///
+/// ```tsx
+/// negotiateAcceptLanguage("fr-CH, fr;q=0.9, en;q=0.8", ["en", "fr"], "en")
+/// // "fr"
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Accept-Language)
+#[wasm_bindgen(js_name = "negotiateAcceptLanguage")]
+pub fn negotiate_accept_language_header_js(header: String, supported: Array, default_locale: String) -> String {
+    negotiate_accept_language(&header, &strings(&supported), &default_locale)
+}
+
+/// Parses a BCP 47 language tag into `{ language, script, region, variants }`.
+/// Throws if the tag isn't well-formed.
+///
+/// ## Example
+///
+/// ```tsx
+/// parseLanguageTag("zh-hant-tw")
+/// // { language: "zh", script: "Hant", region: "TW", variants: [] }
+/// ```
+#[wasm_bindgen(js_name = "parseLanguageTag")]
+pub fn parse_language_tag_js(tag: String) -> Result<JsValue, JsValue> {
+    let parsed = LanguageTag::parse(&tag)
+        .ok_or_else(|| JsValue::from(js_sys::RangeError::new(&format!("Invalid language tag: {}", tag))))?;
+
+    Ok(serde_wasm_bindgen::to_value(&parsed)?)
+}
+
+/// Calls `callback(languages)` whenever the user changes their preferred
+/// languages.
+///
+/// *Also available in workers*
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Window/languagechange_event)
+#[wasm_bindgen(js_name = "onLanguageChange")]
+pub fn on_language_change_js(callback: Function) -> Result<Subscription, JsValue> {
+    Ok(on_locales_change(move |languages| {
+        let languages = languages.into_iter().map(JsValue::from).collect::<Array>();
+        if let Err(e) = callback.call1(&JsValue::NULL, &languages) {
+            log::error!("Error in language change callback: {:?}", e);
+        }
+    })?)
+}
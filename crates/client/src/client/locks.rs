@@ -0,0 +1,426 @@
+use std::{cell::RefCell, fmt, future::Future, rc::Rc};
+
+use js_sys::{Function, Object, Promise, Reflect};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+use web_sys::{AbortController, AbortSignal, DomException};
+
+use crate::client::{
+    capabilities::get,
+    error::{impl_js_error, ClientError, JsErrorName},
+    scope::{navigator, GlobalScope},
+    subscription::Subscription,
+};
+
+/// Why a lock couldn't be acquired or was lost.
+///
+/// Thrown as a JS `Error` whose `name` is [`LockError::name`]. Errors thrown
+/// by the callback itself are passed through untouched.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LockError {
+    Client(ClientError),
+    /// The options can't be combined, e.g. `steal` with `ifAvailable`.
+    InvalidOptions(&'static str),
+    /// The `signal` was aborted while waiting for the lock.
+    Aborted,
+    /// The lock wasn't granted within `timeoutMs`.
+    Timeout,
+    /// Another context took the lock with `steal: true`.
+    Stolen,
+    /// Any other failure. Holds the browser's message.
+    Failed(String),
+}
+
+impl JsErrorName for LockError {
+    fn name(&self) -> &'static str {
+        match self {
+            LockError::Client(error) => error.name(),
+            LockError::InvalidOptions(_) => "NotSupportedError",
+            LockError::Aborted => "AbortError",
+            LockError::Timeout => "TimeoutError",
+            LockError::Stolen => "LockStolenError",
+            LockError::Failed(_) => "LockError",
+        }
+    }
+}
+
+impl fmt::Display for LockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockError::Client(error) => write!(f, "{}", error),
+            LockError::InvalidOptions(message) => write!(f, "Invalid lock options: {}", message),
+            LockError::Aborted => write!(f, "The lock request was aborted"),
+            LockError::Timeout => write!(f, "Timed out waiting for the lock"),
+            LockError::Stolen => write!(f, "The lock was stolen by another context"),
+            LockError::Failed(message) => write!(f, "Failed to acquire the lock: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for LockError {}
+
+impl From<ClientError> for LockError {
+    fn from(error: ClientError) -> Self {
+        LockError::Client(error)
+    }
+}
+
+impl_js_error!(LockError);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LockMode {
+    /// Only one holder at a time
+    #[default]
+    Exclusive,
+    /// Any number of shared holders, but never alongside an exclusive one
+    Shared,
+}
+
+impl LockMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LockMode::Exclusive => "exclusive",
+            LockMode::Shared => "shared",
+        }
+    }
+}
+
+/// Options for `withLock`. Every field is optional.
+///
+/// ## Attributes
+/// `mode`: `"exclusive"` (default) or `"shared"`
+///
+/// `ifAvailable`: don't wait; the callback gets `null` if the lock is held
+///
+/// `steal`: take the lock even if it's held, aborting the current holder
+///
+/// `signal`: an `AbortSignal` to stop waiting for the lock
+///
+/// `timeoutMs`: stop waiting after this long, with a `TimeoutError`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LockOptions {
+    pub mode: LockMode,
+    pub if_available: bool,
+    pub steal: bool,
+    #[serde(skip)]
+    pub signal: Option<AbortSignal>,
+    pub timeout_ms: Option<u32>,
+}
+
+impl LockOptions {
+    /// Rejects the combinations the spec forbids, before asking the browser.
+    pub fn validate(&self) -> Result<(), LockError> {
+        if self.steal && self.if_available {
+            return Err(LockError::InvalidOptions("`steal` and `ifAvailable` can't be combined"));
+        }
+        if self.steal && self.mode == LockMode::Shared {
+            return Err(LockError::InvalidOptions("`steal` needs `exclusive` mode"));
+        }
+
+        let waits = !self.steal && !self.if_available;
+        if !waits && (self.signal.is_some() || self.timeout_ms.is_some()) {
+            return Err(LockError::InvalidOptions(
+                "`signal` and `timeoutMs` only apply to requests that wait",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// `timeout_ms` as a `setTimeout` delay, capped at the largest one
+    /// browsers accept instead of wrapping around.
+    fn timeout_delay(&self) -> Option<i32> {
+        self.timeout_ms.map(|ms| ms.min(i32::MAX as u32) as i32)
+    }
+}
+
+/// A held or requested lock, as reported by `queryLocks`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockInfo {
+    pub name: String,
+    pub mode: LockMode,
+    /// The tab or worker holding or requesting the lock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+impl LockInfo {
+    fn from_lock(lock: &JsValue) -> Option<LockInfo> {
+        Some(LockInfo {
+            name: get(lock, "name")?.as_string()?,
+            mode: match get(lock, "mode")?.as_string()?.as_str() {
+                "shared" => LockMode::Shared,
+                _ => LockMode::Exclusive,
+            },
+            client_id: None,
+        })
+    }
+}
+
+/// Every lock of the origin, held or waiting.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockSnapshot {
+    pub held: Vec<LockInfo>,
+    pub pending: Vec<LockInfo>,
+}
+
+fn lock_manager() -> Result<JsValue, ClientError> {
+    get(navigator()?.as_js(), "locks").ok_or(ClientError::Unsupported("Web Locks API"))
+}
+
+/// Runs `f` while holding the lock `name`, and returns its output once the
+/// lock is released. `f` gets the granted lock, or `None` when
+/// `if_available` is set and the lock is taken.
+pub async fn with_lock<F, Fut, T>(name: &str, options: LockOptions, f: F) -> Result<T, LockError>
+where
+    F: FnOnce(Option<LockInfo>) -> Fut + 'static,
+    Fut: Future<Output = T> + 'static,
+    T: 'static,
+{
+    options.validate()?;
+    let locks = lock_manager()?;
+    let request = get(&locks, "request")
+        .and_then(|request| request.dyn_into::<Function>().ok())
+        .ok_or(ClientError::Unsupported("Web Locks API"))?;
+
+    if options.signal.as_ref().is_some_and(|signal| signal.aborted()) {
+        return Err(LockError::Aborted);
+    }
+
+    let output = Rc::new(RefCell::new(None));
+    let callback = Closure::once_into_js({
+        let output = output.clone();
+        move |lock: JsValue| -> Promise {
+            future_to_promise(async move {
+                let lock = LockInfo::from_lock(&lock);
+                *output.borrow_mut() = Some(f(lock).await);
+                Ok(JsValue::UNDEFINED)
+            })
+        }
+    });
+
+    // The user's signal and the timeout both abort this one, so the two
+    // can be told apart afterwards
+    let controller = AbortController::new().map_err(|e| LockError::Failed(format!("{:?}", e)))?;
+    let timed_out = Rc::new(RefCell::new(false));
+
+    let _on_abort = match &options.signal {
+        Some(signal) => {
+            let controller = controller.clone();
+            Some(
+                Subscription::listen(signal, "abort", move |_| controller.abort())
+                    .map_err(|e| LockError::Failed(format!("{:?}", e)))?,
+            )
+        }
+        None => None,
+    };
+
+    let scope = GlobalScope::current()?;
+    let expire = Closure::once({
+        let controller = controller.clone();
+        let timed_out = timed_out.clone();
+        move || {
+            *timed_out.borrow_mut() = true;
+            controller.abort();
+        }
+    });
+    let timeout = match options.timeout_delay() {
+        Some(ms) => scope.set_timeout(expire.as_ref().unchecked_ref(), ms).ok(),
+        None => None,
+    };
+
+    let js_options = Object::new();
+    let set = |key: &str, value: &JsValue| Reflect::set(&js_options, &key.into(), value);
+    set("mode", &options.mode.as_str().into())
+        .and_then(|_| set("ifAvailable", &options.if_available.into()))
+        .and_then(|_| set("steal", &options.steal.into()))
+        .and_then(|_| match options.signal.is_some() || timeout.is_some() {
+            true => set("signal", &controller.signal()),
+            false => Ok(true),
+        })
+        .map_err(|e| LockError::Failed(format!("{:?}", e)))?;
+
+    let result = match request.call3(&locks, &name.into(), &js_options, &callback) {
+        Ok(promise) => JsFuture::from(promise.unchecked_into::<Promise>()).await,
+        Err(e) => Err(e),
+    };
+
+    if let Some(timeout) = timeout {
+        scope.clear_timeout(timeout);
+    }
+
+    if let Err(e) = result {
+        let aborted = e.dyn_ref::<DomException>().is_some_and(|e| e.name() == "AbortError");
+
+        return Err(match aborted {
+            true if *timed_out.borrow() => LockError::Timeout,
+            true if options.signal.as_ref().is_some_and(|signal| signal.aborted()) => LockError::Aborted,
+            true => LockError::Stolen,
+            false => LockError::Failed(
+                e.dyn_ref::<js_sys::Error>()
+                    .map(|e| String::from(e.message()))
+                    .unwrap_or_else(|| format!("{:?}", e)),
+            ),
+        });
+    }
+
+    let output = output.borrow_mut().take();
+    output.ok_or_else(|| LockError::Failed("the lock was released before the callback ran".to_string()))
+}
+
+/// Lists every lock of the origin, held or waiting.
+pub async fn query_locks() -> Result<LockSnapshot, LockError> {
+    let locks = lock_manager()?;
+    let query = get(&locks, "query")
+        .and_then(|query| query.dyn_into::<Function>().ok())
+        .ok_or(ClientError::Unsupported("Web Locks API"))?;
+
+    let snapshot = query
+        .call0(&locks)
+        .map(|promise| JsFuture::from(promise.unchecked_into::<Promise>()));
+    let snapshot = match snapshot {
+        Ok(snapshot) => snapshot.await,
+        Err(e) => Err(e),
+    }
+    .map_err(|e| LockError::Failed(format!("{:?}", e)))?;
+
+    serde_wasm_bindgen::from_value(snapshot).map_err(|e| LockError::Failed(e.to_string()))
+}
+
+/// Runs `callback(lock)` while holding the lock `name`, and resolves with
+/// whatever it returns (or resolves to) once done. The lock is shared by
+/// every tab and worker of the origin.
+///
+/// Rejects with an `AbortError` when `signal` aborts, a `TimeoutError`
+/// after `timeoutMs`, and a `LockStolenError` when another context steals
+/// the lock. Errors thrown by `callback` are rethrown as is.
+///
+/// *Also available in workers*
+///
+/// ## Example
+///
+/// ```tsx
+/// // Only one tab runs the migration, the others wait and then skip it
+/// await withLock("idb-migration", { timeoutMs: 30000 }, async () => {
+///   if (await needsMigration()) await migrate();
+/// });
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/LockManager/request)
+#[wasm_bindgen(js_name = "withLock")]
+pub async fn with_lock_js(name: String, options: JsValue, callback: Function) -> Result<JsValue, JsValue> {
+    let mut lock_options: LockOptions = if options.is_undefined() || options.is_null() {
+        LockOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options.clone())?
+    };
+    lock_options.signal = get(&options, "signal").and_then(|signal| signal.dyn_into().ok());
+
+    let result = with_lock(&name, lock_options, move |lock| async move {
+        let lock = match lock {
+            Some(lock) => serde_wasm_bindgen::to_value(&lock)?,
+            None => JsValue::NULL,
+        };
+        JsFuture::from(Promise::resolve(&callback.call1(&JsValue::NULL, &lock)?)).await
+    })
+    .await?;
+
+    result
+}
+
+/// Lists the origin's locks as `{ held, pending }`, each an array of
+/// `{ name, mode, clientId }`. Handy for debugging stuck locks.
+///
+/// *Also available in workers*
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/LockManager/query)
+#[wasm_bindgen(js_name = "queryLocks")]
+pub async fn query_locks_js() -> Result<JsValue, JsValue> {
+    Ok(serde_wasm_bindgen::to_value(&query_locks().await?)?)
+}
+
+#[cfg(test)]
+mod locks_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn validate_test() {
+        let options = |mode, if_available, steal, timeout_ms| LockOptions {
+            mode,
+            if_available,
+            steal,
+            signal: None,
+            timeout_ms,
+        };
+
+        assert!(options(LockMode::Exclusive, false, false, Some(100)).validate().is_ok());
+        assert!(options(LockMode::Shared, true, false, None).validate().is_ok());
+        assert!(options(LockMode::Exclusive, false, true, None).validate().is_ok());
+        assert!(options(LockMode::Exclusive, true, true, None).validate().is_err());
+        assert!(options(LockMode::Shared, false, true, None).validate().is_err());
+        assert!(options(LockMode::Exclusive, true, false, Some(100)).validate().is_err());
+        assert_eq!(LockError::Timeout.name(), "TimeoutError");
+    }
+
+    #[test]
+    fn timeout_delay_test() {
+        let delay = |timeout_ms| {
+            LockOptions {
+                timeout_ms,
+                ..LockOptions::default()
+            }
+            .timeout_delay()
+        };
+
+        assert_eq!(delay(None), None);
+        assert_eq!(delay(Some(100)), Some(100));
+        assert_eq!(delay(Some(i32::MAX as u32)), Some(i32::MAX));
+        assert_eq!(delay(Some(i32::MAX as u32 + 1)), Some(i32::MAX));
+        assert_eq!(delay(Some(u32::MAX)), Some(i32::MAX));
+    }
+
+    #[wasm_bindgen_test]
+    async fn with_lock_test() {
+        let result = with_lock("test", LockOptions::default(), |lock| async move { lock.unwrap().name })
+            .await
+            .unwrap();
+        assert_eq!(result, "test");
+    }
+
+    #[wasm_bindgen_test]
+    async fn if_available_test() {
+        let result = with_lock("held", LockOptions::default(), |_| async {
+            let options = LockOptions {
+                if_available: true,
+                ..LockOptions::default()
+            };
+            with_lock("held", options, |lock| async move { lock.is_none() }).await
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result, Ok(true));
+    }
+
+    #[wasm_bindgen_test]
+    async fn timeout_test() {
+        let result = with_lock("slow", LockOptions::default(), |_| async {
+            let options = LockOptions {
+                timeout_ms: Some(10),
+                ..LockOptions::default()
+            };
+            with_lock("slow", options, |_| async {}).await
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result, Err(LockError::Timeout));
+    }
+}
@@ -0,0 +1,459 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use js_sys::{Array, Function, Reflect};
+use serde::Deserialize;
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{MediaImage, MediaMetadata, MediaMetadataInit, MediaPositionState, MediaSession, MediaSessionPlaybackState};
+
+use crate::client::{
+    capabilities::{get, has},
+    error::ClientError,
+    scope::window,
+    subscription::Subscription,
+};
+
+/// Media controls the OS can show on the lock screen, notification shade or
+/// hardware media keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MediaAction {
+    Play,
+    Pause,
+    Stop,
+    SeekTo,
+    SeekBackward,
+    SeekForward,
+    PreviousTrack,
+    NextTrack,
+}
+
+impl MediaAction {
+    pub fn from_name(name: &str) -> Option<MediaAction> {
+        match name {
+            "play" => Some(MediaAction::Play),
+            "pause" => Some(MediaAction::Pause),
+            "stop" => Some(MediaAction::Stop),
+            "seekto" => Some(MediaAction::SeekTo),
+            "seekbackward" => Some(MediaAction::SeekBackward),
+            "seekforward" => Some(MediaAction::SeekForward),
+            "previoustrack" => Some(MediaAction::PreviousTrack),
+            "nexttrack" => Some(MediaAction::NextTrack),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaAction::Play => "play",
+            MediaAction::Pause => "pause",
+            MediaAction::Stop => "stop",
+            MediaAction::SeekTo => "seekto",
+            MediaAction::SeekBackward => "seekbackward",
+            MediaAction::SeekForward => "seekforward",
+            MediaAction::PreviousTrack => "previoustrack",
+            MediaAction::NextTrack => "nexttrack",
+        }
+    }
+}
+
+/// An image shown alongside the media controls.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Artwork {
+    pub src: String,
+    /// e.g. `"512x512"`
+    pub sizes: Option<String>,
+    /// e.g. `"image/png"`
+    #[serde(rename = "type")]
+    pub mime_type: Option<String>,
+}
+
+/// What's currently playing. Every field is optional.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Metadata {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub artwork: Vec<Artwork>,
+}
+
+/// Playback progress, in seconds.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionState {
+    pub duration: f64,
+    #[serde(default)]
+    pub position: f64,
+    #[serde(default = "default_playback_rate")]
+    pub playback_rate: f64,
+}
+
+fn default_playback_rate() -> f64 {
+    1.0
+}
+
+impl PositionState {
+    /// Applies the same checks as the spec, which throws a `TypeError` for
+    /// invalid states.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.duration.is_nan() || self.duration < 0.0 {
+            return Err("duration must be a non-negative number".to_string());
+        }
+        if !(0.0..=self.duration).contains(&self.position) {
+            return Err("position must be between 0 and duration".to_string());
+        }
+        if self.playback_rate == 0.0 || !self.playback_rate.is_finite() {
+            return Err("playbackRate must be a finite, non-zero number".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// What a media control asked for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActionDetails {
+    pub action: MediaAction,
+    /// Seconds to skip, for `SeekBackward` and `SeekForward`
+    pub seek_offset: Option<f64>,
+    /// Where to seek to, in seconds, for `SeekTo`
+    pub seek_time: Option<f64>,
+    /// Whether more `SeekTo` actions follow, e.g. while scrubbing
+    pub fast_seek: bool,
+}
+
+impl ActionDetails {
+    fn from_js(action: MediaAction, details: &JsValue) -> ActionDetails {
+        let number = |key| get(details, key).and_then(|value| value.as_f64());
+
+        ActionDetails {
+            action,
+            seek_offset: number("seekOffset"),
+            seek_time: number("seekTime"),
+            fast_seek: get(details, "fastSeek").is_some_and(|fast_seek| fast_seek.is_truthy()),
+        }
+    }
+}
+
+thread_local! {
+    // Bumped every time a handler is set, so a stale handle doesn't remove
+    // the handler that replaced it
+    static HANDLER_GENERATIONS: RefCell<HashMap<MediaAction, u32>> = RefCell::new(HashMap::new());
+}
+
+fn media_session() -> Result<MediaSession, ClientError> {
+    let navigator = window()?.navigator();
+
+    if !has(&navigator, "mediaSession") {
+        return Err(ClientError::Unsupported("Media Session API"));
+    }
+
+    Ok(navigator.media_session())
+}
+
+fn set_handler(session: &MediaSession, action: MediaAction, handler: &JsValue) -> Result<(), JsValue> {
+    // Going through `Reflect`, since browsers throw for actions they don't
+    // support and `MediaSession::set_action_handler` doesn't catch
+    let set_action_handler = Reflect::get(session, &"setActionHandler".into())?.unchecked_into::<Function>();
+    set_action_handler.call2(session, &action.as_str().into(), handler)?;
+
+    Ok(())
+}
+
+/// Registers `handler` for `action`, replacing any previous handler.
+/// Unsubscribing removes it, unless another handler has replaced it since.
+pub fn on_action(
+    action: MediaAction,
+    mut handler: impl FnMut(ActionDetails) + 'static,
+) -> Result<Subscription, ClientError> {
+    on_raw_action(action, move |details| handler(ActionDetails::from_js(action, &details)))
+}
+
+fn on_raw_action(action: MediaAction, handler: impl FnMut(JsValue) + 'static) -> Result<Subscription, ClientError> {
+    let session = media_session()?;
+    let handler = Closure::<dyn FnMut(JsValue)>::new(handler);
+    set_handler(&session, action, handler.as_ref()).map_err(|_| ClientError::Unsupported("This media session action"))?;
+
+    let generation = HANDLER_GENERATIONS.with(|generations| {
+        let mut generations = generations.borrow_mut();
+        let generation = generations.entry(action).or_insert(0);
+        *generation += 1;
+        *generation
+    });
+
+    Ok(Subscription::new(move || {
+        let current = HANDLER_GENERATIONS.with(|generations| generations.borrow().get(&action).copied());
+
+        if current == Some(generation) {
+            let _ = set_handler(&session, action, &JsValue::NULL);
+        }
+        drop(handler);
+    }))
+}
+
+/// Sets the title, artist, album and artwork shown in the OS media controls.
+pub fn set_media_metadata(metadata: &Metadata) -> Result<(), ClientError> {
+    let artwork = metadata
+        .artwork
+        .iter()
+        .map(|artwork| {
+            let mut image = MediaImage::new(&artwork.src);
+            if let Some(sizes) = &artwork.sizes {
+                image.sizes(sizes);
+            }
+            if let Some(mime_type) = &artwork.mime_type {
+                image.type_(mime_type);
+            }
+            JsValue::from(image)
+        })
+        .collect::<Array>();
+
+    let mut init = MediaMetadataInit::new();
+    init.title(&metadata.title)
+        .artist(&metadata.artist)
+        .album(&metadata.album)
+        .artwork(&artwork);
+
+    // Throws a `TypeError` for artwork with an invalid `src`
+    let media_metadata = MediaMetadata::new_with_init(&init)
+        .map_err(|e| ClientError::InvalidArgument(String::from(e.unchecked_into::<js_sys::Error>().message())))?;
+    media_session()?.set_metadata(Some(&media_metadata));
+
+    Ok(())
+}
+
+/// Removes the metadata, e.g. when playback ends.
+pub fn clear_media_metadata() -> Result<(), ClientError> {
+    media_session()?.set_metadata(None);
+
+    Ok(())
+}
+
+/// Tells the OS whether media is playing, so it shows the right play/pause
+/// button.
+pub fn set_playback_state(state: MediaSessionPlaybackState) -> Result<(), ClientError> {
+    media_session()?.set_playback_state(state);
+
+    Ok(())
+}
+
+/// Updates the progress bar in the OS media controls, or clears it when
+/// `state` is `None`. Invalid states are rejected with
+/// [`ClientError::InvalidArgument`].
+pub fn set_position_state(state: Option<&PositionState>) -> Result<(), ClientError> {
+    let session = media_session()?;
+
+    let state = match state {
+        Some(state) => state,
+        None => {
+            session.set_position_state();
+            return Ok(());
+        }
+    };
+    state.validate().map_err(ClientError::InvalidArgument)?;
+
+    let mut position_state = MediaPositionState::new();
+    position_state
+        .duration(state.duration)
+        .position(state.position)
+        .playback_rate(state.playback_rate);
+    session.set_position_state_with_state(&position_state);
+
+    Ok(())
+}
+
+/// Sets the title, artist, album and artwork shown in the OS media controls.
+///
+/// ## Example
+///
+/// ```tsx
+/// await setMediaMetadata({
+///   title: episode.title,
+///   artist: podcast.author,
+///   album: podcast.name,
+///   artwork: [{ src: podcast.cover, sizes: "512x512", type: "image/png" }],
+/// });
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/MediaSession/metadata)
+#[wasm_bindgen(js_name = "setMediaMetadata")]
+pub async fn set_media_metadata_js(metadata: JsValue) -> Result<(), JsValue> {
+    let metadata: Metadata = serde_wasm_bindgen::from_value(metadata)?;
+
+    Ok(set_media_metadata(&metadata)?)
+}
+
+/// Removes the metadata, e.g. when playback ends.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/MediaSession/metadata)
+#[wasm_bindgen(js_name = "clearMediaMetadata")]
+pub async fn clear_media_metadata_js() -> Result<(), JsValue> {
+    Ok(clear_media_metadata()?)
+}
+
+/// Tells the OS whether media is `"playing"`, `"paused"` or `"none"`, so it
+/// shows the right play/pause button.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/MediaSession/playbackState)
+#[wasm_bindgen(js_name = "setPlaybackState")]
+pub async fn set_playback_state_js(state: String) -> Result<(), JsValue> {
+    let state = match state.as_str() {
+        "none" => MediaSessionPlaybackState::None,
+        "paused" => MediaSessionPlaybackState::Paused,
+        "playing" => MediaSessionPlaybackState::Playing,
+        _ => {
+            return Err(js_sys::TypeError::new(&format!("Invalid playback state: {}", state)).into());
+        }
+    };
+
+    Ok(set_playback_state(state)?)
+}
+
+/// Updates the progress bar in the OS media controls. Call it whenever the
+/// position jumps (seeks) or the playback rate changes; the OS extrapolates
+/// in between. Pass `null` to clear it.
+///
+/// ## Example
+///
+/// ```tsx
+/// audio.addEventListener("seeked", () => {
+///   setPositionState({ duration: audio.duration, position: audio.currentTime, playbackRate: audio.playbackRate });
+/// });
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/MediaSession/setPositionState)
+#[wasm_bindgen(js_name = "setPositionState")]
+pub async fn set_position_state_js(state: JsValue) -> Result<(), JsValue> {
+    if state.is_undefined() || state.is_null() {
+        return Ok(set_position_state(None)?);
+    }
+
+    let state: PositionState = serde_wasm_bindgen::from_value(state)?;
+
+    Ok(set_position_state(Some(&state))?)
+}
+
+/// Handles a media control: `"play"`, `"pause"`, `"stop"`, `"seekto"`,
+/// `"seekbackward"`, `"seekforward"`, `"previoustrack"` or `"nexttrack"`.
+/// The callback receives the action details, e.g. `seekTime` for `"seekto"`.
+///
+/// Returns a handle whose `unsubscribe()` removes the handler. Throws a
+/// `NotSupportedError` when the browser doesn't support the action.
+///
+/// ## Example
+///
+/// ```tsx
+/// useEffect(() => {
+///   const handlers = [
+///     setActionHandler("play", () => audio.play()),
+///     setActionHandler("pause", () => audio.pause()),
+///     setActionHandler("seekto", ({ seekTime }) => (audio.currentTime = seekTime)),
+///   ];
+///   return () => handlers.forEach((handler) => handler.unsubscribe());
+/// }, []);
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/MediaSession/setActionHandler)
+#[wasm_bindgen(js_name = "setActionHandler")]
+pub fn set_action_handler_js(action: String, callback: Function) -> Result<Subscription, JsValue> {
+    let action = MediaAction::from_name(&action)
+        .ok_or_else(|| JsValue::from(js_sys::TypeError::new(&format!("Invalid media session action: {}", action))))?;
+
+    Ok(on_raw_action(action, move |details| {
+        if let Err(e) = callback.call1(&JsValue::NULL, &details) {
+            log::error!("Error in media session action handler: {:?}", e);
+        }
+    })?)
+}
+
+#[cfg(test)]
+mod media_session_tests {
+    use super::*;
+    use std::{cell::Cell, rc::Rc};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn action_test() {
+        for name in ["play", "pause", "stop", "seekto", "seekbackward", "seekforward", "previoustrack", "nexttrack"] {
+            assert_eq!(MediaAction::from_name(name).unwrap().as_str(), name);
+        }
+        assert_eq!(MediaAction::from_name("skipad"), None);
+    }
+
+    #[test]
+    fn position_state_test() {
+        let state = |duration, position, playback_rate| PositionState {
+            duration,
+            position,
+            playback_rate,
+        };
+
+        assert!(state(120.0, 30.0, 1.0).validate().is_ok());
+        assert!(state(f64::INFINITY, 30.0, -1.0).validate().is_ok());
+        assert!(state(-1.0, 0.0, 1.0).validate().is_err());
+        assert!(state(f64::NAN, 0.0, 1.0).validate().is_err());
+        assert!(state(120.0, 121.0, 1.0).validate().is_err());
+        assert!(state(120.0, 30.0, 0.0).validate().is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn action_details_test() {
+        let details = js_sys::Object::new();
+        Reflect::set(&details, &"seekTime".into(), &42.5.into()).unwrap();
+        Reflect::set(&details, &"fastSeek".into(), &true.into()).unwrap();
+
+        assert_eq!(
+            ActionDetails::from_js(MediaAction::SeekTo, &details),
+            ActionDetails {
+                action: MediaAction::SeekTo,
+                seek_offset: None,
+                seek_time: Some(42.5),
+                fast_seek: true,
+            }
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn metadata_test() {
+        let metadata = js_sys::Object::new();
+        Reflect::set(&metadata, &"title".into(), &"Episode 1".into()).unwrap();
+        Reflect::set(&metadata, &"artist".into(), &"Remix PWA".into()).unwrap();
+
+        assert!(set_media_metadata_js(metadata.into()).await.is_ok());
+        assert_eq!(media_session().unwrap().metadata().unwrap().title(), "Episode 1");
+        assert!(clear_media_metadata_js().await.is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    fn action_handler_test() {
+        // Records the handlers the browser is given, since it can't be asked
+        let session = media_session().unwrap();
+        let handlers = js_sys::Object::new();
+        let spy = Function::new_with_args("handlers", "return (action, handler) => { handlers[action] = handler; };")
+            .call1(&JsValue::NULL, &handlers)
+            .unwrap();
+        Reflect::set(&session, &"setActionHandler".into(), &spy).unwrap();
+
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let mut first = on_action(MediaAction::Play, |_| {}).unwrap();
+        let mut second = on_action(MediaAction::Play, move |details| {
+            assert_eq!(details.action, MediaAction::Play);
+            counter.set(counter.get() + 1);
+        })
+        .unwrap();
+
+        // The first handle was replaced, so unsubscribing it must be a no-op
+        first.unsubscribe();
+        let play = Reflect::get(&handlers, &"play".into()).unwrap().unchecked_into::<Function>();
+        play.call1(&JsValue::NULL, &js_sys::Object::new()).unwrap();
+        assert_eq!(calls.get(), 1);
+
+        second.unsubscribe();
+        assert!(Reflect::get(&handlers, &"play".into()).unwrap().is_null());
+
+        Reflect::delete_property(&session, &"setActionHandler".into()).unwrap();
+    }
+}
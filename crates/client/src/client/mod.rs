@@ -1,10 +1,33 @@
+pub mod address;
+pub mod error;
 pub mod response;
+pub mod scope;
+pub mod subscription;
 
+pub mod adaptive;
+pub mod capabilities;
 pub mod clipboard;
 pub mod connection;
+pub mod contacts;
+pub mod credentials;
+pub mod files;
 pub mod fullscreen;
 pub mod geolocation;
+pub mod idle;
 pub mod language;
+pub mod launch;
 pub mod langauges;
+pub mod locale;
+pub mod locks;
+pub mod media_session;
+pub mod motion;
+pub mod orientation;
+pub mod payments;
 pub mod permissions;
-pub mod visibility;
\ No newline at end of file
+pub mod protocol;
+pub mod reachability;
+pub mod share_target;
+pub mod tabs;
+pub mod vibrate;
+pub mod visibility;
+pub mod window_controls;
\ No newline at end of file
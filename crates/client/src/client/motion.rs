@@ -0,0 +1,442 @@
+use js_sys::{Function, Promise};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::PermissionState;
+
+use crate::client::{
+    capabilities::{get, has},
+    error::ClientError,
+    scope::window,
+    subscription::Subscription,
+};
+
+/// A low-pass filter. `smoothing` is the weight of the previous reading:
+/// `0` passes readings through, values close to `1` damp jitter the most
+/// but lag behind.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LowPass {
+    smoothing: f64,
+}
+
+impl LowPass {
+    pub fn new(smoothing: f64) -> LowPass {
+        LowPass {
+            smoothing: match smoothing.is_finite() {
+                true => smoothing.clamp(0.0, 0.99),
+                false => 0.0,
+            },
+        }
+    }
+
+    fn value(&self, previous: Option<f64>, next: Option<f64>) -> Option<f64> {
+        match (previous, next) {
+            (Some(previous), Some(next)) => Some(previous * self.smoothing + next * (1.0 - self.smoothing)),
+            (_, next) => next,
+        }
+    }
+
+    /// Smooths an angle that wraps around at `min + 360`, so that e.g. a
+    /// heading going from 359° to 1° passes through 0° rather than 180°.
+    fn angle(&self, previous: Option<f64>, next: Option<f64>, min: f64) -> Option<f64> {
+        match (previous, next) {
+            (Some(previous), Some(next)) => {
+                let delta = (next - previous + 180.0).rem_euclid(360.0) - 180.0;
+                Some((previous + delta * (1.0 - self.smoothing) - min).rem_euclid(360.0) + min)
+            }
+            (_, next) => next,
+        }
+    }
+}
+
+/// How the device is tilted, in degrees.
+///
+/// ## Attributes
+/// `alpha`: rotation around the z axis (compass heading), `[0, 360)`
+///
+/// `beta`: front to back tilt, `[-180, 180)`
+///
+/// `gamma`: left to right tilt, `[-90, 90]`
+///
+/// `absolute`: whether `alpha` is relative to the Earth rather than to
+/// an arbitrary starting point
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Orientation {
+    pub alpha: Option<f64>,
+    pub beta: Option<f64>,
+    pub gamma: Option<f64>,
+    pub absolute: bool,
+}
+
+impl Orientation {
+    fn from_event(event: &JsValue) -> Orientation {
+        Orientation {
+            alpha: number(event, "alpha"),
+            beta: number(event, "beta"),
+            gamma: number(event, "gamma"),
+            absolute: get(event, "absolute").and_then(|absolute| absolute.as_bool()).unwrap_or(false),
+        }
+    }
+
+    fn smoothed(&self, previous: &Orientation, filter: &LowPass) -> Orientation {
+        Orientation {
+            alpha: filter.angle(previous.alpha, self.alpha, 0.0),
+            beta: filter.angle(previous.beta, self.beta, -180.0),
+            gamma: filter.value(previous.gamma, self.gamma),
+            absolute: self.absolute,
+        }
+    }
+}
+
+/// Acceleration along each axis, in m/s².
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct Acceleration {
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub z: Option<f64>,
+}
+
+impl Acceleration {
+    fn from_js(value: Option<JsValue>) -> Acceleration {
+        let value = value.unwrap_or(JsValue::UNDEFINED);
+        Acceleration {
+            x: number(&value, "x"),
+            y: number(&value, "y"),
+            z: number(&value, "z"),
+        }
+    }
+
+    fn smoothed(&self, previous: &Acceleration, filter: &LowPass) -> Acceleration {
+        Acceleration {
+            x: filter.value(previous.x, self.x),
+            y: filter.value(previous.y, self.y),
+            z: filter.value(previous.z, self.z),
+        }
+    }
+}
+
+/// Rotation speed around each axis, in degrees per second.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct RotationRate {
+    pub alpha: Option<f64>,
+    pub beta: Option<f64>,
+    pub gamma: Option<f64>,
+}
+
+impl RotationRate {
+    fn from_js(value: Option<JsValue>) -> RotationRate {
+        let value = value.unwrap_or(JsValue::UNDEFINED);
+        RotationRate {
+            alpha: number(&value, "alpha"),
+            beta: number(&value, "beta"),
+            gamma: number(&value, "gamma"),
+        }
+    }
+
+    fn smoothed(&self, previous: &RotationRate, filter: &LowPass) -> RotationRate {
+        RotationRate {
+            alpha: filter.value(previous.alpha, self.alpha),
+            beta: filter.value(previous.beta, self.beta),
+            gamma: filter.value(previous.gamma, self.gamma),
+        }
+    }
+}
+
+/// A `devicemotion` reading. `interval` is the time between readings, in
+/// milliseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Motion {
+    pub acceleration: Acceleration,
+    pub acceleration_including_gravity: Acceleration,
+    pub rotation_rate: RotationRate,
+    pub interval: f64,
+}
+
+impl Motion {
+    fn from_event(event: &JsValue) -> Motion {
+        Motion {
+            acceleration: Acceleration::from_js(get(event, "acceleration")),
+            acceleration_including_gravity: Acceleration::from_js(get(event, "accelerationIncludingGravity")),
+            rotation_rate: RotationRate::from_js(get(event, "rotationRate")),
+            interval: number(event, "interval").unwrap_or(0.0),
+        }
+    }
+
+    fn smoothed(&self, previous: &Motion, filter: &LowPass) -> Motion {
+        Motion {
+            acceleration: self.acceleration.smoothed(&previous.acceleration, filter),
+            acceleration_including_gravity: self
+                .acceleration_including_gravity
+                .smoothed(&previous.acceleration_including_gravity, filter),
+            rotation_rate: self.rotation_rate.smoothed(&previous.rotation_rate, filter),
+            interval: self.interval,
+        }
+    }
+}
+
+/// Options for `onDeviceOrientation` and `onDeviceMotion`.
+///
+/// ## Attributes
+/// `smoothing`: low-pass filter strength, from `0` (raw readings, the
+/// default) to `0.99`. Around `0.8` works well for tilt controls
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MotionOptions {
+    pub smoothing: f64,
+}
+
+fn number(target: &JsValue, key: &str) -> Option<f64> {
+    get(target, key)?.as_f64()
+}
+
+fn parse_options(options: JsValue) -> Result<MotionOptions, JsValue> {
+    if options.is_undefined() || options.is_null() {
+        Ok(MotionOptions::default())
+    } else {
+        Ok(serde_wasm_bindgen::from_value(options)?)
+    }
+}
+
+/// Calls the iOS-only `requestPermission()` of an event constructor, if it
+/// has one. Everywhere else, the events need no permission.
+async fn request_permission(constructor: &str) -> Result<String, JsValue> {
+    let feature = match constructor {
+        "DeviceOrientationEvent" => "Device orientation events",
+        _ => "Device motion events",
+    };
+    let constructor = get(&js_sys::global(), constructor).ok_or(ClientError::Unsupported(feature))?;
+
+    let request = match get(&constructor, "requestPermission").and_then(|f| f.dyn_into::<Function>().ok()) {
+        Some(request) => request,
+        None => return Ok("granted".to_string()),
+    };
+
+    let state = JsFuture::from(request.call0(&constructor)?.unchecked_into::<Promise>()).await?;
+    Ok(state.as_string().unwrap_or_else(|| "denied".to_string()))
+}
+
+/// Asks for both sensors, stopping at the first one that isn't granted.
+async fn request_permissions() -> Result<String, JsValue> {
+    let orientation = request_permission("DeviceOrientationEvent").await?;
+    if orientation != "granted" {
+        return Ok(orientation);
+    }
+
+    match has(&js_sys::global(), "DeviceMotionEvent") {
+        true => request_permission("DeviceMotionEvent").await,
+        false => Ok(orientation),
+    }
+}
+
+/// Asks for access to the orientation and motion sensors, which only iOS
+/// Safari asks the user for. Has to be called from a user gesture.
+pub async fn request_motion_permission() -> Result<PermissionState, ClientError> {
+    let state = request_permissions().await?;
+
+    Ok(PermissionState::from_js_value(&state.into()).unwrap_or(PermissionState::Denied))
+}
+
+/// Asks for access to the orientation and motion sensors. Only iOS Safari
+/// asks the user; other browsers resolve with `"granted"` right away.
+/// Resolves with `"granted"` or `"denied"`.
+///
+/// Has to be called from a user gesture, such as a click handler.
+///
+/// ## Example
+///
+/// ```tsx
+/// <button onClick={async () => {
+///   if (await requestMotionPermission() === "granted") setTiltEnabled(true);
+/// }}>
+///   Look around
+/// </button>
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/DeviceOrientationEvent/requestPermission_static)
+#[wasm_bindgen(js_name = "requestMotionPermission")]
+pub async fn request_motion_permission_js() -> Result<String, JsValue> {
+    request_permissions().await
+}
+
+/// Calls `callback` with every `deviceorientation` reading, smoothed by
+/// `options.smoothing`.
+pub fn on_orientation(
+    options: MotionOptions,
+    mut callback: impl FnMut(Orientation) + 'static,
+) -> Result<Subscription, ClientError> {
+    if !has(&js_sys::global(), "DeviceOrientationEvent") {
+        return Err(ClientError::Unsupported("Device orientation events"));
+    }
+
+    let filter = LowPass::new(options.smoothing);
+    let mut previous: Option<Orientation> = None;
+    let window = window()?;
+    let subscription = Subscription::listen(&window, "deviceorientation", move |event| {
+        let reading = Orientation::from_event(&event);
+        let reading = match &previous {
+            Some(previous) => reading.smoothed(previous, &filter),
+            None => reading,
+        };
+
+        previous = Some(reading);
+        callback(reading);
+    })?;
+
+    Ok(subscription)
+}
+
+/// Calls `callback` with every `devicemotion` reading, smoothed by
+/// `options.smoothing`.
+pub fn on_motion(
+    options: MotionOptions,
+    mut callback: impl FnMut(Motion) + 'static,
+) -> Result<Subscription, ClientError> {
+    if !has(&js_sys::global(), "DeviceMotionEvent") {
+        return Err(ClientError::Unsupported("Device motion events"));
+    }
+
+    let filter = LowPass::new(options.smoothing);
+    let mut previous: Option<Motion> = None;
+    let window = window()?;
+    let subscription = Subscription::listen(&window, "devicemotion", move |event| {
+        let reading = Motion::from_event(&event);
+        let reading = match &previous {
+            Some(previous) => reading.smoothed(previous, &filter),
+            None => reading,
+        };
+
+        previous = Some(reading);
+        callback(reading);
+    })?;
+
+    Ok(subscription)
+}
+
+fn emit<T: Serialize>(callback: &Function, reading: &T) {
+    let reading = match serde_wasm_bindgen::to_value(reading) {
+        Ok(reading) => reading,
+        Err(e) => return log::error!("Failed to serialize the sensor reading: {:?}", e),
+    };
+
+    if let Err(e) = callback.call1(&JsValue::NULL, &reading) {
+        log::error!("Error in motion callback: {:?}", e);
+    }
+}
+
+/// Calls `callback` with `{ alpha, beta, gamma, absolute }` whenever the
+/// device is tilted. On iOS, call `requestMotionPermission()` first.
+///
+/// ## Example
+///
+/// ```tsx
+/// useEffect(() => {
+///   const subscription = onDeviceOrientation(({ beta, gamma }) => {
+///     model.rotation.set(beta ?? 0, gamma ?? 0, 0);
+///   }, { smoothing: 0.8 });
+///
+///   return () => subscription.unsubscribe();
+/// }, []);
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Window/deviceorientation_event)
+#[wasm_bindgen(js_name = "onDeviceOrientation")]
+pub fn on_orientation_js(callback: Function, options: JsValue) -> Result<Subscription, JsValue> {
+    let options = parse_options(options)?;
+
+    Ok(on_orientation(options, move |reading| emit(&callback, &reading))?)
+}
+
+/// Calls `callback` with `{ acceleration, accelerationIncludingGravity,
+/// rotationRate, interval }` on every `devicemotion` reading. On iOS, call
+/// `requestMotionPermission()` first.
+///
+/// ## Example
+///
+/// ```tsx
+/// const subscription = onDeviceMotion(({ rotationRate }) => {
+///   spin(rotationRate.alpha ?? 0);
+/// }, { smoothing: 0.5 });
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Window/devicemotion_event)
+#[wasm_bindgen(js_name = "onDeviceMotion")]
+pub fn on_motion_js(callback: Function, options: JsValue) -> Result<Subscription, JsValue> {
+    let options = parse_options(options)?;
+
+    Ok(on_motion(options, move |reading| emit(&callback, &reading))?)
+}
+
+#[cfg(test)]
+mod motion_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn low_pass_test() {
+        let filter = LowPass::new(0.5);
+
+        assert_eq!(filter.value(Some(0.0), Some(10.0)), Some(5.0));
+        assert_eq!(filter.value(None, Some(10.0)), Some(10.0));
+        assert_eq!(filter.value(Some(10.0), None), None);
+        assert_eq!(LowPass::new(0.0).value(Some(0.0), Some(10.0)), Some(10.0));
+        assert_eq!(LowPass::new(f64::NAN).value(Some(0.0), Some(10.0)), Some(10.0));
+    }
+
+    #[test]
+    fn angle_test() {
+        let filter = LowPass::new(0.5);
+
+        // Wraps through 0° instead of averaging to 180°
+        assert_eq!(filter.angle(Some(350.0), Some(10.0), 0.0), Some(0.0));
+        assert_eq!(filter.angle(Some(340.0), Some(10.0), 0.0), Some(355.0));
+        assert_eq!(filter.angle(Some(170.0), Some(-170.0), -180.0), Some(-180.0));
+        assert_eq!(filter.angle(Some(10.0), Some(20.0), 0.0), Some(15.0));
+    }
+
+    #[test]
+    fn smoothed_test() {
+        let filter = LowPass::new(0.5);
+        let previous = Orientation {
+            alpha: Some(0.0),
+            beta: Some(0.0),
+            gamma: Some(10.0),
+            absolute: false,
+        };
+        let next = Orientation {
+            alpha: Some(20.0),
+            beta: None,
+            gamma: Some(20.0),
+            absolute: true,
+        };
+
+        assert_eq!(
+            next.smoothed(&previous, &filter),
+            Orientation {
+                alpha: Some(10.0),
+                beta: None,
+                gamma: Some(15.0),
+                absolute: true,
+            }
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn on_orientation_test() {
+        let subscription = on_orientation(MotionOptions::default(), |_| {});
+
+        match subscription {
+            Ok(subscription) => {
+                assert!(has(&js_sys::global(), "DeviceOrientationEvent"));
+                assert!(subscription.is_active());
+            }
+            Err(error) => {
+                assert!(!has(&js_sys::global(), "DeviceOrientationEvent"));
+                assert_eq!(error, ClientError::Unsupported("Device orientation events"));
+            }
+        }
+    }
+}
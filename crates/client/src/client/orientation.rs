@@ -0,0 +1,389 @@
+use std::fmt;
+
+use js_sys::{Function, Promise};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{DomException, OrientationLockType, ScreenOrientation};
+
+use crate::client::{
+    capabilities::{get, has},
+    error::{impl_js_error, ClientError, JsErrorName},
+    scope::{document, window},
+    subscription::Subscription,
+};
+
+/// Why an orientation lock failed.
+///
+/// Thrown as a JS `Error` whose `name` is [`OrientationError::name`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrientationError {
+    Client(ClientError),
+    /// Not a valid `OrientationLockType`. Holds the rejected value.
+    InvalidOrientation(String),
+    /// The browser only allows locking while fullscreen or when running as
+    /// an installed app.
+    NotFullscreen,
+    /// The document is hidden or sandboxed.
+    Security,
+    /// Another `lockOrientation`/`unlockOrientation` call superseded this one.
+    Aborted,
+    /// Any other rejection. Holds the browser's message.
+    Failed(String),
+}
+
+impl JsErrorName for OrientationError {
+    fn name(&self) -> &'static str {
+        match self {
+            OrientationError::Client(error) => error.name(),
+            OrientationError::InvalidOrientation(_) => "TypeError",
+            OrientationError::NotFullscreen => "NotFullscreenError",
+            OrientationError::Security => "SecurityError",
+            OrientationError::Aborted => "AbortError",
+            OrientationError::Failed(_) => "OrientationLockError",
+        }
+    }
+}
+
+impl OrientationError {
+    /// Classifies a `DOMException` thrown by `screen.orientation.lock()`.
+    /// Browsers report both a missing fullscreen and a device that can't lock
+    /// at all as a `NotSupportedError`. It only counts as the former when the
+    /// document isn't fullscreen or installed and the browser is known to
+    /// need it: mobile browsers (`touch`) or a message that says so.
+    fn from_dom(name: &str, message: String, fullscreen_or_installed: bool, touch: bool) -> OrientationError {
+        let needs_fullscreen = touch || message.to_ascii_lowercase().contains("fullscreen");
+
+        match name {
+            "NotSupportedError" if !fullscreen_or_installed && needs_fullscreen => OrientationError::NotFullscreen,
+            "NotSupportedError" => OrientationError::Client(ClientError::Unsupported("Screen orientation lock")),
+            "SecurityError" => OrientationError::Security,
+            "AbortError" => OrientationError::Aborted,
+            _ => OrientationError::Failed(message),
+        }
+    }
+}
+
+impl fmt::Display for OrientationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrientationError::Client(error) => write!(f, "{}", error),
+            OrientationError::InvalidOrientation(orientation) => {
+                write!(f, "\"{}\" is not a valid orientation", orientation)
+            }
+            OrientationError::NotFullscreen => write!(
+                f,
+                "Orientation can only be locked in fullscreen or in an installed app"
+            ),
+            OrientationError::Security => write!(f, "Orientation lock isn't allowed in this document"),
+            OrientationError::Aborted => write!(f, "Orientation lock was superseded by another call"),
+            OrientationError::Failed(message) => write!(f, "Failed to lock orientation: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for OrientationError {}
+
+impl From<ClientError> for OrientationError {
+    fn from(error: ClientError) -> Self {
+        OrientationError::Client(error)
+    }
+}
+
+impl_js_error!(OrientationError);
+
+/// Parses an orientation such as `"portrait"` or `"landscape-primary"`.
+pub fn parse_lock_type(orientation: &str) -> Result<OrientationLockType, OrientationError> {
+    Ok(match orientation {
+        "any" => OrientationLockType::Any,
+        "natural" => OrientationLockType::Natural,
+        "landscape" => OrientationLockType::Landscape,
+        "portrait" => OrientationLockType::Portrait,
+        "portrait-primary" => OrientationLockType::PortraitPrimary,
+        "portrait-secondary" => OrientationLockType::PortraitSecondary,
+        "landscape-primary" => OrientationLockType::LandscapePrimary,
+        "landscape-secondary" => OrientationLockType::LandscapeSecondary,
+        _ => return Err(OrientationError::InvalidOrientation(orientation.to_string())),
+    })
+}
+
+/// The screen's current orientation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct OrientationState {
+    /// e.g. `"portrait-primary"`
+    #[serde(rename = "type")]
+    pub orientation_type: String,
+    /// Rotation from the natural orientation, in degrees
+    pub angle: u16,
+}
+
+impl OrientationState {
+    fn from_orientation(orientation: &ScreenOrientation) -> Result<OrientationState, JsValue> {
+        let orientation_type = orientation.type_()?;
+
+        Ok(OrientationState {
+            orientation_type: JsValue::from(orientation_type).as_string().unwrap_or_default(),
+            angle: orientation.angle()?,
+        })
+    }
+}
+
+/// Options for `lockOrientation`.
+///
+/// ## Attributes
+/// `fullscreen`: request fullscreen on the document first, since most
+/// browsers refuse to lock otherwise. Defaults to `false`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LockOptions {
+    pub fullscreen: bool,
+}
+
+fn screen_orientation() -> Result<ScreenOrientation, ClientError> {
+    let screen = window()?.screen().map_err(|_| ClientError::Unsupported("Screen Orientation API"))?;
+
+    if !has(&screen, "orientation") {
+        return Err(ClientError::Unsupported("Screen Orientation API"));
+    }
+
+    Ok(screen.orientation())
+}
+
+fn is_fullscreen_or_installed() -> bool {
+    let fullscreen = document().map(|document| document.fullscreen()).unwrap_or(false);
+    let installed = window()
+        .ok()
+        .and_then(|window| window.match_media("(display-mode: standalone)").ok().flatten())
+        .map(|query| query.matches())
+        .unwrap_or(false);
+
+    fullscreen || installed
+}
+
+/// Whether this is a touch-first device, where browsers that support locking
+/// only allow it in fullscreen. Desktop browsers can't lock at all.
+fn is_touch_device() -> bool {
+    window()
+        .ok()
+        .and_then(|window| window.match_media("(pointer: coarse)").ok().flatten())
+        .map(|query| query.matches())
+        .unwrap_or(false)
+}
+
+async fn enter_fullscreen() -> Result<(), OrientationError> {
+    let document = document()?;
+    if document.fullscreen() {
+        return Ok(());
+    }
+
+    let element = document
+        .document_element()
+        .ok_or(OrientationError::Failed("no document element".to_string()))?;
+
+    // `Element::request_fullscreen` discards the promise, and the lock must
+    // only be requested once fullscreen has actually been entered
+    let request = get(&element, "requestFullscreen")
+        .and_then(|request| request.dyn_into::<Function>().ok())
+        .ok_or(ClientError::Unsupported("Fullscreen API"))?;
+
+    let result = request.call0(&element).map_err(|e| lock_error(e, false))?;
+    if let Ok(promise) = result.dyn_into::<Promise>() {
+        JsFuture::from(promise).await.map_err(|e| lock_error(e, false))?;
+    }
+
+    Ok(())
+}
+
+fn lock_error(error: JsValue, fullscreen_or_installed: bool) -> OrientationError {
+    match error.dyn_into::<DomException>() {
+        Ok(exception) => OrientationError::from_dom(
+            &exception.name(),
+            exception.message(),
+            fullscreen_or_installed,
+            is_touch_device(),
+        ),
+        Err(error) => OrientationError::Failed(format!("{:?}", error)),
+    }
+}
+
+/// The screen's current orientation.
+pub fn get_orientation() -> Result<OrientationState, ClientError> {
+    Ok(OrientationState::from_orientation(&screen_orientation()?)?)
+}
+
+/// Locks the screen to `lock_type`, entering fullscreen first when
+/// `options.fullscreen` is set.
+pub async fn lock_orientation(lock_type: OrientationLockType, options: &LockOptions) -> Result<(), OrientationError> {
+    let screen_orientation = screen_orientation()?;
+
+    if options.fullscreen {
+        enter_fullscreen().await?;
+    }
+
+    let fullscreen_or_installed = is_fullscreen_or_installed();
+    let lock = screen_orientation
+        .lock(lock_type)
+        .map_err(|e| lock_error(e, fullscreen_or_installed))?;
+
+    JsFuture::from(lock)
+        .await
+        .map_err(|e| lock_error(e, fullscreen_or_installed))?;
+
+    Ok(())
+}
+
+/// Unlocks the screen orientation. Doesn't exit fullscreen.
+pub fn unlock_orientation() -> Result<(), ClientError> {
+    Ok(screen_orientation()?.unlock()?)
+}
+
+/// Calls `callback` with the new orientation whenever the screen rotates.
+pub fn on_orientation_change(
+    mut callback: impl FnMut(OrientationState) + 'static,
+) -> Result<Subscription, ClientError> {
+    let orientation = screen_orientation()?;
+    let target = orientation.clone().unchecked_into();
+
+    let subscription = Subscription::listen(&target, "change", move |_| {
+        match OrientationState::from_orientation(&orientation) {
+            Ok(state) => callback(state),
+            Err(e) => log::error!("Error reading orientation: {:?}", e),
+        }
+    })?;
+
+    Ok(subscription)
+}
+
+/// Returns the screen's current orientation as `{ type, angle }`.
+///
+/// ## Example
+///
+/// ```tsx
+/// const { type, angle } = await getOrientation();
+/// // { type: "landscape-primary", angle: 90 }
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/ScreenOrientation)
+#[wasm_bindgen(js_name = "getOrientation")]
+pub async fn get_orientation_js() -> Result<JsValue, JsValue> {
+    Ok(serde_wasm_bindgen::to_value(&get_orientation()?)?)
+}
+
+/// Locks the screen to an orientation (`"portrait"`, `"landscape"`,
+/// `"portrait-primary"`, ...). Pass `{ fullscreen: true }` to enter
+/// fullscreen first, which most browsers require unless the app is installed.
+///
+/// Throws a `NotFullscreenError` when locking isn't allowed outside of
+/// fullscreen, a `NotSupportedError` when the API is missing or the device
+/// can't lock at all, and a `TypeError` for unknown orientations.
+///
+/// ## Example
+///
+/// ```tsx
+/// try {
+///   await lockOrientation("landscape", { fullscreen: true });
+/// } catch (e) {
+///   if (e.name === "NotFullscreenError") showRotateHint();
+/// }
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/ScreenOrientation/lock)
+#[wasm_bindgen(js_name = "lockOrientation")]
+pub async fn lock_orientation_js(orientation: String, options: JsValue) -> Result<(), JsValue> {
+    let options: LockOptions = if options.is_undefined() || options.is_null() {
+        LockOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)?
+    };
+
+    Ok(lock_orientation(parse_lock_type(&orientation)?, &options).await?)
+}
+
+/// Unlocks the screen orientation. Doesn't exit fullscreen.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/ScreenOrientation/unlock)
+#[wasm_bindgen(js_name = "unlockOrientation")]
+pub async fn unlock_orientation_js() -> Result<(), JsValue> {
+    Ok(unlock_orientation()?)
+}
+
+/// Calls `callback({ type, angle })` whenever the screen rotates.
+///
+/// ## Example
+///
+/// ```tsx
+/// useEffect(() => {
+///   const subscription = onOrientationChange(({ type }) => setLandscape(type.startsWith("landscape")));
+///   return () => subscription.unsubscribe();
+/// }, []);
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/ScreenOrientation/change_event)
+#[wasm_bindgen(js_name = "onOrientationChange")]
+pub fn on_orientation_change_js(callback: Function) -> Result<Subscription, JsValue> {
+    Ok(on_orientation_change(move |state| {
+        let result = serde_wasm_bindgen::to_value(&state)
+            .map_err(JsValue::from)
+            .and_then(|state| callback.call1(&JsValue::NULL, &state));
+
+        if let Err(e) = result {
+            log::error!("Error in orientation change callback: {:?}", e);
+        }
+    })?)
+}
+
+#[cfg(test)]
+mod orientation_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn parse_lock_type_test() {
+        assert_eq!(parse_lock_type("portrait"), Ok(OrientationLockType::Portrait));
+        assert_eq!(
+            parse_lock_type("landscape-secondary"),
+            Ok(OrientationLockType::LandscapeSecondary)
+        );
+        assert_eq!(
+            parse_lock_type("sideways"),
+            Err(OrientationError::InvalidOrientation("sideways".to_string()))
+        );
+    }
+
+    #[test]
+    fn from_dom_test() {
+        let error =
+            |name, fullscreen, touch| OrientationError::from_dom(name, "message".to_string(), fullscreen, touch);
+        let unsupported = OrientationError::Client(ClientError::Unsupported("Screen orientation lock"));
+
+        assert_eq!(error("NotSupportedError", false, true), OrientationError::NotFullscreen);
+        assert_eq!(error("NotSupportedError", true, true), unsupported);
+        assert_eq!(error("NotSupportedError", false, false), unsupported);
+        assert_eq!(
+            OrientationError::from_dom("NotSupportedError", "Must be fullscreen".to_string(), false, false),
+            OrientationError::NotFullscreen
+        );
+        assert_eq!(error("SecurityError", true, false), OrientationError::Security);
+        assert_eq!(error("AbortError", false, true), OrientationError::Aborted);
+        assert_eq!(error("InvalidStateError", false, true), OrientationError::Failed("message".to_string()));
+        assert_eq!(OrientationError::NotFullscreen.name(), "NotFullscreenError");
+    }
+
+    #[wasm_bindgen_test]
+    async fn get_orientation_test() {
+        let result = get_orientation();
+        assert!(result.is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    async fn lock_without_fullscreen_test() {
+        let error = lock_orientation(OrientationLockType::Portrait, &LockOptions::default())
+            .await
+            .unwrap_err();
+
+        // Desktop browsers can't lock at all, mobile ones need fullscreen
+        assert!(["NotFullscreenError", "NotSupportedError"].contains(&error.name()));
+    }
+}
@@ -0,0 +1,707 @@
+use std::{fmt, future::Future};
+
+use js_sys::{Array, Function, Promise, Reflect};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+use web_sys::{DomException, EventTarget, PaymentComplete, PaymentRequestUpdateEvent, PaymentResponse};
+
+use crate::client::{
+    address::Address,
+    capabilities::get,
+    error::{impl_js_error, ClientError, JsErrorName},
+    subscription::Subscription,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = EventTarget)]
+    #[derive(Clone, Debug)]
+    type PaymentRequest;
+
+    #[wasm_bindgen(constructor, catch)]
+    fn new(methods: &JsValue, details: &JsValue, options: &JsValue) -> Result<PaymentRequest, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn show(this: &PaymentRequest) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn abort(this: &PaymentRequest) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = "canMakePayment")]
+    fn can_make_payment(this: &PaymentRequest) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(method, getter, js_name = "shippingAddress")]
+    fn shipping_address(this: &PaymentRequest) -> JsValue;
+
+    #[wasm_bindgen(method, getter, js_name = "shippingOption")]
+    fn shipping_option(this: &PaymentRequest) -> Option<String>;
+}
+
+/// Why a payment request failed.
+///
+/// Thrown as a JS `Error` whose `name` is [`PaymentError::name`], which
+/// matches the browser's own `DOMException` names where there is one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PaymentError {
+    Client(ClientError),
+    /// The methods, details or options are malformed. Holds what's wrong.
+    InvalidDetails(String),
+    /// The user closed the payment sheet, or `abort()` was called.
+    Cancelled,
+    /// Another payment sheet is already showing, or this one was shown
+    /// before.
+    InvalidState,
+    /// The page isn't allowed to request payments, e.g. in a cross-origin
+    /// iframe without the `payment` permission policy.
+    Security,
+    /// Any other failure. Holds the browser's message.
+    Failed(String),
+}
+
+impl JsErrorName for PaymentError {
+    fn name(&self) -> &'static str {
+        match self {
+            PaymentError::Client(error) => error.name(),
+            PaymentError::InvalidDetails(_) => "TypeError",
+            PaymentError::Cancelled => "AbortError",
+            PaymentError::InvalidState => "InvalidStateError",
+            PaymentError::Security => "SecurityError",
+            PaymentError::Failed(_) => "PaymentError",
+        }
+    }
+}
+
+impl PaymentError {
+    /// Classifies an error thrown by `PaymentRequest`.
+    fn from_dom(name: &str, message: String) -> PaymentError {
+        match name {
+            "AbortError" => PaymentError::Cancelled,
+            "InvalidStateError" => PaymentError::InvalidState,
+            "SecurityError" => PaymentError::Security,
+            "NotSupportedError" => PaymentError::Client(ClientError::Unsupported("These payment methods")),
+            "TypeError" | "RangeError" => PaymentError::InvalidDetails(message),
+            _ => PaymentError::Failed(message),
+        }
+    }
+}
+
+impl fmt::Display for PaymentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaymentError::Client(error) => write!(f, "{}", error),
+            PaymentError::InvalidDetails(message) => write!(f, "Invalid payment request: {}", message),
+            PaymentError::Cancelled => write!(f, "The payment was cancelled"),
+            PaymentError::InvalidState => write!(f, "A payment sheet is already showing"),
+            PaymentError::Security => write!(f, "Payments aren't allowed in this context"),
+            PaymentError::Failed(message) => write!(f, "Payment failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for PaymentError {}
+
+impl From<ClientError> for PaymentError {
+    fn from(error: ClientError) -> Self {
+        PaymentError::Client(error)
+    }
+}
+
+impl_js_error!(PaymentError);
+
+fn payment_error(error: JsValue) -> PaymentError {
+    if let Some(exception) = error.dyn_ref::<DomException>() {
+        return PaymentError::from_dom(&exception.name(), exception.message());
+    }
+
+    match error.dyn_into::<js_sys::Error>() {
+        Ok(error) => PaymentError::from_dom(&String::from(error.name()), error.message().into()),
+        Err(error) => PaymentError::Failed(format!("{:?}", error)),
+    }
+}
+
+/// A payment method, such as `"https://google.com/pay"` or a card network.
+///
+/// `data` is method specific and passed to the browser as is; from Rust,
+/// set it with [`PaymentMethod::with_data`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentMethod {
+    pub supported_methods: String,
+    #[serde(skip)]
+    pub data: Option<JsValue>,
+}
+
+impl PaymentMethod {
+    pub fn new(supported_methods: &str) -> PaymentMethod {
+        PaymentMethod {
+            supported_methods: supported_methods.to_string(),
+            data: None,
+        }
+    }
+
+    pub fn with_data<T: Serialize>(mut self, data: &T) -> Result<PaymentMethod, PaymentError> {
+        self.data = Some(to_js(data).map_err(|e| PaymentError::InvalidDetails(format!("{:?}", e)))?);
+        Ok(self)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Amount {
+    /// An ISO 4217 currency code, such as `"EUR"`
+    pub currency: String,
+    /// A decimal string, such as `"19.99"`
+    pub value: String,
+}
+
+impl Amount {
+    /// Whether `value` is a valid decimal monetary value, e.g. `"-4.50"`.
+    fn is_valid(&self) -> bool {
+        let digits = self.value.strip_prefix('-').unwrap_or(&self.value);
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (digits, None),
+        };
+        let numeric = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        let currency = self.currency.len() == 3 && self.currency.bytes().all(|b| b.is_ascii_alphabetic());
+        let fraction = match fraction {
+            Some(fraction) => numeric(fraction),
+            None => true,
+        };
+
+        currency && numeric(whole) && fraction
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentItem {
+    pub label: String,
+    pub amount: Amount,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pending: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShippingOption {
+    pub id: String,
+    pub label: String,
+    pub amount: Amount,
+    #[serde(default)]
+    pub selected: bool,
+}
+
+/// What the user pays for, shown on the payment sheet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub total: PaymentItem,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub display_items: Vec<PaymentItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shipping_options: Vec<ShippingOption>,
+}
+
+/// Validates the amounts of a `total` and its line items and shipping
+/// options, which browsers reject with a vague `TypeError`.
+fn validate(
+    total: Option<&PaymentItem>,
+    items: &[PaymentItem],
+    shipping_options: &[ShippingOption],
+) -> Result<(), PaymentError> {
+    if let Some(total) = total {
+        if !total.amount.is_valid() || total.amount.value.starts_with('-') {
+            return Err(PaymentError::InvalidDetails(format!(
+                "the total must be a positive amount, got `{}`",
+                total.amount.value
+            )));
+        }
+    }
+
+    let amounts = items
+        .iter()
+        .map(|item| (&item.label, &item.amount))
+        .chain(shipping_options.iter().map(|option| (&option.label, &option.amount)));
+    for (label, amount) in amounts {
+        if !amount.is_valid() {
+            return Err(PaymentError::InvalidDetails(format!(
+                "`{}` has an invalid amount `{} {}`",
+                label, amount.value, amount.currency
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+impl PaymentDetails {
+    pub fn validate(&self) -> Result<(), PaymentError> {
+        validate(Some(&self.total), &self.display_items, &self.shipping_options)
+    }
+}
+
+/// The new details after a shipping change. Leave a field out to keep it
+/// as is, or set `error` to tell the user why the address can't be used.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetailsUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<PaymentItem>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_items: Option<Vec<PaymentItem>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shipping_options: Option<Vec<ShippingOption>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl DetailsUpdate {
+    pub fn validate(&self) -> Result<(), PaymentError> {
+        validate(
+            self.total.as_ref(),
+            self.display_items.as_deref().unwrap_or_default(),
+            self.shipping_options.as_deref().unwrap_or_default(),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShippingType {
+    Shipping,
+    Delivery,
+    Pickup,
+}
+
+/// What to ask the user for, besides the payment method.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PaymentOptions {
+    pub request_payer_name: bool,
+    pub request_payer_email: bool,
+    pub request_payer_phone: bool,
+    pub request_shipping: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipping_type: Option<ShippingType>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompleteStatus {
+    Success,
+    Fail,
+    #[default]
+    Unknown,
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    Ok(value.serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
+
+/// A payment sheet for one checkout. Create one per payment: a sheet can
+/// only be shown once.
+#[wasm_bindgen]
+pub struct Checkout {
+    request: PaymentRequest,
+}
+
+impl Checkout {
+    pub fn new(methods: &[PaymentMethod], details: &PaymentDetails, options: &PaymentOptions) -> Result<Checkout, PaymentError> {
+        if get(&js_sys::global(), "PaymentRequest").is_none() {
+            return Err(ClientError::Unsupported("Payment Request API").into());
+        }
+        if methods.is_empty() {
+            return Err(PaymentError::InvalidDetails("at least one payment method is needed".to_string()));
+        }
+        details.validate()?;
+
+        let js_methods = Array::new();
+        for method in methods {
+            let js_method = to_js(method).map_err(payment_error)?;
+            if let Some(data) = &method.data {
+                Reflect::set(&js_method, &"data".into(), data).map_err(payment_error)?;
+            }
+            js_methods.push(&js_method);
+        }
+
+        let details = to_js(details).map_err(payment_error)?;
+        let options = to_js(options).map_err(payment_error)?;
+        let request = PaymentRequest::new(&js_methods, &details, &options).map_err(payment_error)?;
+
+        Ok(Checkout { request })
+    }
+
+    /// Calls `f` with the address whenever the user picks a shipping
+    /// address, and updates the sheet with the details it resolves to.
+    pub fn on_shipping_address_change<F, Fut>(&self, mut f: F) -> Result<Subscription, PaymentError>
+    where
+        F: FnMut(Address) -> Fut + 'static,
+        Fut: Future<Output = DetailsUpdate> + 'static,
+    {
+        let request = self.request.clone();
+        self.on_update("shippingaddresschange", move || {
+            let address = Address::from_js(&request.shipping_address()).unwrap_or_default();
+            f(address)
+        })
+    }
+
+    /// Calls `f` with the option's `id` whenever the user picks a shipping
+    /// option, and updates the sheet with the details it resolves to.
+    pub fn on_shipping_option_change<F, Fut>(&self, mut f: F) -> Result<Subscription, PaymentError>
+    where
+        F: FnMut(String) -> Fut + 'static,
+        Fut: Future<Output = DetailsUpdate> + 'static,
+    {
+        let request = self.request.clone();
+        self.on_update("shippingoptionchange", move || f(request.shipping_option().unwrap_or_default()))
+    }
+
+    /// Listens to `event`, and hands the update `f` resolves to to
+    /// `updateWith()`. The sheet shows a spinner until then.
+    fn on_update<F, Fut>(&self, event: &str, mut f: F) -> Result<Subscription, PaymentError>
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = DetailsUpdate> + 'static,
+    {
+        Subscription::listen(&self.request, event, move |event| {
+            let event = event.unchecked_into::<PaymentRequestUpdateEvent>();
+            let update = f();
+            let promise = future_to_promise(async move {
+                let update = update.await;
+                update.validate()?;
+                to_js(&update)
+            });
+
+            if let Err(e) = event.update_with(&promise) {
+                log::error!("Failed to update the payment sheet: {:?}", e);
+            }
+        })
+        .map_err(payment_error)
+    }
+
+    /// Whether the user can pay with one of the methods, e.g. has a card
+    /// set up. `false` when the browser can't tell.
+    pub async fn can_make_payment(&self) -> bool {
+        match self.request.can_make_payment() {
+            Ok(promise) => JsFuture::from(promise).await.ok().and_then(|v| v.as_bool()).unwrap_or(false),
+            Err(_) => false,
+        }
+    }
+
+    /// Shows the payment sheet, and resolves once the user authorized the
+    /// payment. Has to be called from a user gesture.
+    pub async fn show(&self) -> Result<PaymentResult, PaymentError> {
+        let response = JsFuture::from(self.request.show().map_err(payment_error)?)
+            .await
+            .map_err(payment_error)?;
+
+        Ok(PaymentResult {
+            response: response.unchecked_into(),
+        })
+    }
+
+    /// Closes the sheet, rejecting `show()` with an `AbortError`.
+    pub async fn abort(&self) -> Result<(), PaymentError> {
+        let promise = self.request.abort().map_err(payment_error)?;
+        JsFuture::from(promise).await.map(|_| ()).map_err(payment_error)
+    }
+}
+
+/// An authorized payment. Process `details` on the server, then call
+/// `complete()` to close the sheet.
+#[wasm_bindgen]
+pub struct PaymentResult {
+    response: PaymentResponse,
+}
+
+impl PaymentResult {
+    /// The shipping address, if `requestShipping` was set.
+    pub fn shipping_address(&self) -> Option<Address> {
+        Address::from_js(&self.response.shipping_address()?.into())
+    }
+
+    /// Closes the sheet, showing the outcome to the user.
+    pub async fn complete(&self, status: CompleteStatus) -> Result<(), PaymentError> {
+        let status = match status {
+            CompleteStatus::Success => PaymentComplete::Success,
+            CompleteStatus::Fail => PaymentComplete::Fail,
+            CompleteStatus::Unknown => PaymentComplete::Unknown,
+        };
+
+        JsFuture::from(self.response.complete_with_result(status))
+            .await
+            .map(|_| ())
+            .map_err(payment_error)
+    }
+}
+
+#[wasm_bindgen]
+impl PaymentResult {
+    /// The payment method the user paid with.
+    #[wasm_bindgen(getter, js_name = "methodName")]
+    pub fn method_name(&self) -> String {
+        self.response.method_name()
+    }
+
+    /// Method specific data to process the payment with, e.g. a card
+    /// token.
+    #[wasm_bindgen(getter)]
+    pub fn details(&self) -> JsValue {
+        self.response.details().into()
+    }
+
+    #[wasm_bindgen(getter, js_name = "payerName")]
+    pub fn payer_name(&self) -> Option<String> {
+        self.response.payer_name()
+    }
+
+    #[wasm_bindgen(getter, js_name = "payerEmail")]
+    pub fn payer_email(&self) -> Option<String> {
+        self.response.payer_email()
+    }
+
+    #[wasm_bindgen(getter, js_name = "payerPhone")]
+    pub fn payer_phone(&self) -> Option<String> {
+        self.response.payer_phone()
+    }
+
+    #[wasm_bindgen(getter, js_name = "shippingAddress")]
+    pub fn shipping_address_js(&self) -> Result<JsValue, JsValue> {
+        to_js(&self.shipping_address())
+    }
+
+    /// The `id` of the picked shipping option.
+    #[wasm_bindgen(getter, js_name = "shippingOption")]
+    pub fn shipping_option(&self) -> Option<String> {
+        self.response.shipping_option()
+    }
+
+    /// Closes the sheet with `"success"`, `"fail"` or `"unknown"`.
+    #[wasm_bindgen(js_name = "complete")]
+    pub async fn complete_js(&self, status: JsValue) -> Result<(), JsValue> {
+        let status = match status.is_undefined() {
+            true => CompleteStatus::default(),
+            false => serde_wasm_bindgen::from_value(status)?,
+        };
+
+        Ok(self.complete(status).await?)
+    }
+
+    /// The whole response as JSON, e.g. to send it to the server.
+    #[wasm_bindgen(js_name = "toJSON")]
+    pub fn to_json(&self) -> JsValue {
+        self.response.to_json().into()
+    }
+}
+
+/// Wraps a JS shipping callback, which may return the update or a promise
+/// of it.
+async fn call_update(callback: &Function, argument: JsValue) -> DetailsUpdate {
+    let result = match callback.call1(&JsValue::NULL, &argument) {
+        Ok(result) => JsFuture::from(Promise::resolve(&result)).await,
+        Err(e) => Err(e),
+    };
+
+    match result.map(serde_wasm_bindgen::from_value::<DetailsUpdate>) {
+        Ok(Ok(update)) => update,
+        Ok(Err(e)) => {
+            log::error!("Invalid payment details update: {:?}", e);
+            DetailsUpdate::default()
+        }
+        Err(e) => {
+            log::error!("Error in shipping callback: {:?}", e);
+            DetailsUpdate::default()
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Checkout {
+    /// Builds a payment sheet from `methods`, `details` and `options`, as
+    /// `new PaymentRequest()` takes them. Amounts are checked upfront.
+    ///
+    /// ## Example
+    ///
+    /// ```tsx
+    /// const checkout = new Checkout(
+    ///   [{ supportedMethods: "https://google.com/pay", data: googlePayConfig }],
+    ///   {
+    ///     total: { label: "Total", amount: { currency: "EUR", value: "24.99" } },
+    ///     displayItems: [{ label: "Hoodie", amount: { currency: "EUR", value: "19.99" } }],
+    ///     shippingOptions: [{ id: "standard", label: "Standard", amount: { currency: "EUR", value: "5.00" }, selected: true }],
+    ///   },
+    ///   { requestShipping: true, requestPayerEmail: true },
+    /// );
+    ///
+    /// checkout.onShippingAddressChange(async (address) => {
+    ///   return await fetch("/cart/shipping", { method: "POST", body: JSON.stringify(address) }).then((r) => r.json());
+    /// });
+    ///
+    /// const payment = await checkout.show();
+    /// const { ok } = await fetch("/cart/pay", { method: "POST", body: JSON.stringify(payment) });
+    /// await payment.complete(ok ? "success" : "fail");
+    /// ```
+    ///
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/PaymentRequest/PaymentRequest)
+    #[wasm_bindgen(constructor)]
+    pub fn new_js(methods: JsValue, details: JsValue, options: JsValue) -> Result<Checkout, JsValue> {
+        let mut payment_methods: Vec<PaymentMethod> = serde_wasm_bindgen::from_value(methods.clone())?;
+        for (method, js_method) in payment_methods.iter_mut().zip(Array::from(&methods).iter()) {
+            method.data = get(&js_method, "data");
+        }
+
+        let details: PaymentDetails = serde_wasm_bindgen::from_value(details)?;
+        let options: PaymentOptions = if options.is_undefined() || options.is_null() {
+            PaymentOptions::default()
+        } else {
+            serde_wasm_bindgen::from_value(options)?
+        };
+
+        Ok(Checkout::new(&payment_methods, &details, &options)?)
+    }
+
+    /// Calls `callback(address)` when the user picks a shipping address.
+    /// Return the updated details (or a promise of them), e.g. with new
+    /// `shippingOptions`, or `{ error }` if you don't ship there.
+    #[wasm_bindgen(js_name = "onShippingAddressChange")]
+    pub fn on_shipping_address_change_js(&self, callback: Function) -> Result<Subscription, JsValue> {
+        Ok(self.on_shipping_address_change(move |address| {
+            let callback = callback.clone();
+            async move {
+                match to_js(&address) {
+                    Ok(address) => call_update(&callback, address).await,
+                    Err(_) => DetailsUpdate::default(),
+                }
+            }
+        })?)
+    }
+
+    /// Calls `callback(optionId)` when the user picks a shipping option.
+    /// Return the updated details (or a promise of them), e.g. a new
+    /// `total`.
+    #[wasm_bindgen(js_name = "onShippingOptionChange")]
+    pub fn on_shipping_option_change_js(&self, callback: Function) -> Result<Subscription, JsValue> {
+        Ok(self.on_shipping_option_change(move |option| {
+            let callback = callback.clone();
+            async move { call_update(&callback, option.into()).await }
+        })?)
+    }
+
+    /// Whether the user can pay with one of the methods. Check it before
+    /// showing a "Buy now" button.
+    #[wasm_bindgen(js_name = "canMakePayment")]
+    pub async fn can_make_payment_js(&self) -> bool {
+        self.can_make_payment().await
+    }
+
+    /// Shows the payment sheet. Resolves with a `PaymentResult` once the
+    /// user authorized the payment, or rejects with an `AbortError` when
+    /// they close it. Call it from a click handler.
+    #[wasm_bindgen(js_name = "show")]
+    pub async fn show_js(&self) -> Result<PaymentResult, JsValue> {
+        Ok(self.show().await?)
+    }
+
+    /// Closes the payment sheet.
+    #[wasm_bindgen(js_name = "abort")]
+    pub async fn abort_js(&self) -> Result<(), JsValue> {
+        Ok(self.abort().await?)
+    }
+}
+
+#[cfg(test)]
+mod payments_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn amount(value: &str) -> Amount {
+        Amount {
+            currency: "EUR".to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn item(value: &str) -> PaymentItem {
+        PaymentItem {
+            label: "Hoodie".to_string(),
+            amount: amount(value),
+            pending: false,
+        }
+    }
+
+    #[test]
+    fn amount_test() {
+        assert!(amount("19.99").is_valid());
+        assert!(amount("-4.5").is_valid());
+        assert!(amount("10").is_valid());
+        assert!(!amount("19,99").is_valid());
+        assert!(!amount("1.").is_valid());
+        assert!(!amount("").is_valid());
+
+        let currency = |currency: &str| Amount {
+            currency: currency.to_string(),
+            value: "1".to_string(),
+        };
+        assert!(currency("usd").is_valid());
+        assert!(!currency("euro").is_valid());
+        assert!(!currency("E1R").is_valid());
+        assert!(!currency("€").is_valid());
+    }
+
+    #[test]
+    fn validate_test() {
+        let details = PaymentDetails {
+            id: None,
+            total: item("24.99"),
+            display_items: vec![item("19.99"), item("-5.00")],
+            shipping_options: vec![],
+        };
+        assert!(details.validate().is_ok());
+
+        let negative = PaymentDetails {
+            total: item("-1"),
+            ..details.clone()
+        };
+        assert!(negative.validate().is_err());
+
+        let update = DetailsUpdate {
+            display_items: Some(vec![item("abc")]),
+            ..DetailsUpdate::default()
+        };
+        assert_eq!(
+            update.validate(),
+            Err(PaymentError::InvalidDetails("`Hoodie` has an invalid amount `abc EUR`".to_string()))
+        );
+        assert!(DetailsUpdate::default().validate().is_ok());
+    }
+
+    #[test]
+    fn from_dom_test() {
+        let error = |name| PaymentError::from_dom(name, "message".to_string());
+
+        assert_eq!(error("AbortError"), PaymentError::Cancelled);
+        assert_eq!(error("InvalidStateError"), PaymentError::InvalidState);
+        assert_eq!(error("TypeError"), PaymentError::InvalidDetails("message".to_string()));
+        assert_eq!(error("OperationError"), PaymentError::Failed("message".to_string()));
+    }
+
+    #[wasm_bindgen_test]
+    fn checkout_test() {
+        let details = PaymentDetails {
+            id: None,
+            total: item("24.99"),
+            display_items: vec![],
+            shipping_options: vec![],
+        };
+        let checkout = Checkout::new(&[PaymentMethod::new("basic-card")], &details, &PaymentOptions::default());
+        assert!(checkout.is_ok());
+
+        let no_methods = Checkout::new(&[], &details, &PaymentOptions::default());
+        assert!(matches!(no_methods, Err(PaymentError::InvalidDetails(_))));
+    }
+}
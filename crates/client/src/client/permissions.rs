@@ -1,10 +1,38 @@
 use js_sys::{Object, Reflect};
-use wasm_bindgen::prelude::*;
+use wasm_bindgen::{prelude::*, JsCast};
 use wasm_bindgen_futures::JsFuture;
+use web_sys::{PermissionState, PermissionStatus};
+
+use crate::client::{error::ClientError, scope::navigator};
+
+/// Keeps the browser's own rejection, e.g. the `TypeError` for unknown
+/// permission names, so `getPermissionStatus` can reject with it as is.
+async fn query(permission: &str) -> Result<PermissionStatus, JsValue> {
+    let permissions = navigator()?.permissions()?;
+
+    let permission_object = Object::new();
+    Reflect::set(&permission_object, &"name".into(), &permission.into())?;
+
+    let status = JsFuture::from(permissions.query(&permission_object)?).await?;
+    Ok(status.unchecked_into())
+}
+
+/// Queries the `PermissionStatus` of a permission, such as `"geolocation"`.
+pub async fn permission_status(permission: &str) -> Result<PermissionStatus, ClientError> {
+    Ok(query(permission).await?)
+}
+
+/// Whether a permission is `Granted`, `Denied` or still to be asked for
+/// (`Prompt`).
+pub async fn permission_state(permission: &str) -> Result<PermissionState, ClientError> {
+    Ok(permission_status(permission).await?.state())
+}
 
 /// Returns the current permission status of the an API.
 /// Takes in the permission string as a parameter.
 /// 
+/// *Also available in workers*
+/// 
 /// ## Examplae 
 /// 
 /// ```tsx
@@ -14,23 +42,9 @@ use wasm_bindgen_futures::JsFuture;
 /// ```
 /// 
 /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Permissions/query)
-#[wasm_bindgen]
-pub async fn get_permission_status(permission: String) -> Result<JsValue, JsValue> {
-    let window = web_sys::window().expect("no global `window` exists. Make sure you are calling this function in the browser");
-    let navigator = window.navigator();
-
-    let permissions = navigator.permissions().expect("no permissions object found");
-
-    let permission_object = Object::new();
-    Reflect::set(&permission_object, &"name".into(), &permission.as_str().into()).unwrap();
-    
-    let permission_status = JsFuture::from(permissions.query(&permission_object).expect("no permission status found")).await;
-
-    if permission_status.is_ok() {
-      Ok(permission_status.unwrap())
-    } else {
-      Err(permission_status.err().unwrap())
-    }
+#[wasm_bindgen(js_name = "get_permission_status")]
+pub async fn get_permission_status_js(permission: String) -> Result<JsValue, JsValue> {
+    Ok(query(&permission).await?.into())
 }
 
 #[cfg(test)]
@@ -42,7 +56,13 @@ mod permissions_tests {
 
     #[wasm_bindgen_test]
     async fn get_permissions_test() {
-        let permission_status = get_permission_status("clipboard-read".to_string()).await;
+        let permission_status = get_permission_status_js("clipboard-read".to_string()).await;
         assert!(permission_status.is_ok());
     }
+
+    #[wasm_bindgen_test]
+    async fn unknown_permission_test() {
+        let error = get_permission_status_js("not-a-permission".to_string()).await.unwrap_err();
+        assert_eq!(String::from(error.unchecked_into::<js_sys::Error>().name()), "TypeError");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,287 @@
+use std::fmt;
+
+use js_sys::Function;
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::Url;
+
+use crate::client::{
+    capabilities::get,
+    error::{impl_js_error, ClientError, JsErrorName},
+    scope::{document, window},
+};
+
+/// Schemes any site may register without the `web+` prefix
+pub const SAFELISTED_SCHEMES: [&str; 33] = [
+    "bitcoin",
+    "cabal",
+    "dat",
+    "did",
+    "doi",
+    "dweb",
+    "ethereum",
+    "ftp",
+    "ftps",
+    "geo",
+    "im",
+    "ipfs",
+    "ipns",
+    "irc",
+    "ircs",
+    "magnet",
+    "mailto",
+    "matrix",
+    "mms",
+    "news",
+    "nntp",
+    "openpgp4fpr",
+    "sftp",
+    "sip",
+    "sms",
+    "smsto",
+    "ssb",
+    "ssh",
+    "tel",
+    "urn",
+    "webcal",
+    "wtai",
+    "xmpp",
+];
+
+/// Why a protocol handler was rejected.
+///
+/// Thrown as a JS `Error` whose `name` is [`ProtocolError::name`]. Unlike
+/// the browser's own `SecurityError`, the message says what to fix.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProtocolError {
+    Client(ClientError),
+    /// Neither safelisted nor a valid `web+` scheme. Holds why.
+    InvalidScheme(String),
+    /// The URL template has no `%s` placeholder.
+    MissingPlaceholder,
+    /// The URL template can't be parsed. Holds the template.
+    InvalidUrl(String),
+    /// The URL template isn't `http:` or `https:`. Holds its scheme.
+    InsecureUrl(String),
+    /// The URL template points to another origin. Holds that origin.
+    CrossOrigin(String),
+    /// The browser refused anyway. Holds its message.
+    Failed(String),
+}
+
+impl JsErrorName for ProtocolError {
+    fn name(&self) -> &'static str {
+        match self {
+            ProtocolError::Client(error) => error.name(),
+            ProtocolError::InvalidScheme(_) => "InvalidSchemeError",
+            ProtocolError::MissingPlaceholder | ProtocolError::InvalidUrl(_) => "SyntaxError",
+            ProtocolError::InsecureUrl(_) => "InsecureUrlError",
+            ProtocolError::CrossOrigin(_) => "CrossOriginError",
+            ProtocolError::Failed(_) => "ProtocolHandlerError",
+        }
+    }
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::Client(error) => write!(f, "{}", error),
+            ProtocolError::InvalidScheme(reason) => write!(f, "Invalid protocol scheme: {}", reason),
+            ProtocolError::MissingPlaceholder => {
+                write!(f, "The URL template needs a `%s` placeholder for the link, e.g. `/open?url=%s`")
+            }
+            ProtocolError::InvalidUrl(template) => write!(f, "`{}` isn't a valid URL", template),
+            ProtocolError::InsecureUrl(scheme) => {
+                write!(f, "The URL template must be http: or https:, not {}", scheme)
+            }
+            ProtocolError::CrossOrigin(origin) => {
+                write!(f, "The URL template must be on this site's origin, not {}", origin)
+            }
+            ProtocolError::Failed(message) => write!(f, "Failed to register the protocol handler: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl From<ClientError> for ProtocolError {
+    fn from(error: ClientError) -> Self {
+        ProtocolError::Client(error)
+    }
+}
+
+impl_js_error!(ProtocolError);
+
+/// Checks `scheme` against the safelist and the `web+` rules, and returns
+/// it lowercased, as browsers store it.
+pub fn validate_scheme(scheme: &str) -> Result<String, ProtocolError> {
+    let scheme = scheme.trim_end_matches(':').to_ascii_lowercase();
+
+    if let Some(name) = scheme.strip_prefix("web+") {
+        return match name {
+            "" => Err(ProtocolError::InvalidScheme("`web+` needs a name, e.g. `web+invoice`".to_string())),
+            name if name.bytes().all(|b| b.is_ascii_lowercase()) => Ok(scheme),
+            _ => Err(ProtocolError::InvalidScheme(format!(
+                "`{}` may only contain ASCII letters after `web+`",
+                scheme
+            ))),
+        };
+    }
+
+    match SAFELISTED_SCHEMES.contains(&scheme.as_str()) {
+        true => Ok(scheme),
+        false => Err(ProtocolError::InvalidScheme(format!(
+            "`{}` isn't safelisted, use `web+{}` instead",
+            scheme,
+            scheme.chars().filter(|c| c.is_ascii_alphabetic()).collect::<String>()
+        ))),
+    }
+}
+
+/// Checks the parts of the parsed URL template: it has to be served over
+/// HTTP(S) from the document's own origin.
+fn validate_target(protocol: &str, origin: &str, document_origin: &str) -> Result<(), ProtocolError> {
+    if protocol != "http:" && protocol != "https:" {
+        return Err(ProtocolError::InsecureUrl(protocol.to_string()));
+    }
+    if origin != document_origin {
+        return Err(ProtocolError::CrossOrigin(origin.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Resolves `url_template` against the document and validates it.
+fn validate_url(url_template: &str) -> Result<String, ProtocolError> {
+    if !url_template.contains("%s") {
+        return Err(ProtocolError::MissingPlaceholder);
+    }
+
+    let document = document()?;
+    let base = document.base_uri().ok().flatten().unwrap_or_default();
+    let url = Url::new_with_base(url_template, &base).map_err(|_| ProtocolError::InvalidUrl(url_template.to_string()))?;
+
+    let document_origin = window()?.location().origin().unwrap_or_default();
+    validate_target(&url.protocol(), &url.origin(), &document_origin)?;
+
+    Ok(url.href())
+}
+
+/// Calls `navigator[method](scheme, url)`.
+fn call(method: &str, scheme: &str, url: &str) -> Result<(), ProtocolError> {
+    let navigator = window()?.navigator();
+    let function = get(&navigator, method)
+        .and_then(|function| function.dyn_into::<Function>().ok())
+        .ok_or(ClientError::Unsupported("Custom protocol handlers"))?;
+
+    function
+        .call2(&navigator, &scheme.into(), &url.into())
+        .map(|_| ())
+        .map_err(|e| match e.dyn_into::<js_sys::Error>() {
+            Ok(e) => ProtocolError::Failed(e.message().into()),
+            Err(e) => ProtocolError::Failed(format!("{:?}", e)),
+        })
+}
+
+/// Validates `scheme` and `url_template`, then asks the browser to open
+/// `scheme:` links with this app.
+pub fn register_protocol_handler(scheme: &str, url_template: &str) -> Result<(), ProtocolError> {
+    let scheme = validate_scheme(scheme)?;
+    let url = validate_url(url_template)?;
+    call("registerProtocolHandler", &scheme, &url)
+}
+
+/// Removes a handler registered with [`register_protocol_handler`].
+pub fn unregister_protocol_handler(scheme: &str, url_template: &str) -> Result<(), ProtocolError> {
+    let scheme = validate_scheme(scheme)?;
+    let url = validate_url(url_template)?;
+    call("unregisterProtocolHandler", &scheme, &url)
+}
+
+/// Asks the browser to open `scheme:` links with this app, at
+/// `urlTemplate` with `%s` replaced by the escaped link. The browser may
+/// ask the user for confirmation.
+///
+/// `scheme` has to be safelisted (`mailto`, `tel`, `geo`, ...) or start
+/// with `web+` followed by letters. `urlTemplate` has to be on the app's
+/// origin. Both are checked before calling the browser, so mistakes throw
+/// an `InvalidSchemeError`, `SyntaxError`, `InsecureUrlError` or
+/// `CrossOriginError` saying what to fix.
+///
+/// Installed apps can declare `protocol_handlers` in their manifest
+/// instead.
+///
+/// ## Example
+///
+/// ```tsx
+/// registerProtocolHandler("web+invoice", "/invoices/open?link=%s");
+///
+/// // app/routes/invoices.open.tsx
+/// const link = new URL(request.url).searchParams.get("link"); // "web+invoice:2024-0042"
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Navigator/registerProtocolHandler)
+#[wasm_bindgen(js_name = "registerProtocolHandler")]
+pub fn register_protocol_handler_js(scheme: String, url_template: String) -> Result<(), JsValue> {
+    Ok(register_protocol_handler(&scheme, &url_template)?)
+}
+
+/// Removes a handler registered with `registerProtocolHandler`. Takes the
+/// same `scheme` and `urlTemplate`.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Navigator/unregisterProtocolHandler)
+#[wasm_bindgen(js_name = "unregisterProtocolHandler")]
+pub fn unregister_protocol_handler_js(scheme: String, url_template: String) -> Result<(), JsValue> {
+    Ok(unregister_protocol_handler(&scheme, &url_template)?)
+}
+
+#[cfg(test)]
+mod protocol_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn scheme_test() {
+        assert_eq!(validate_scheme("web+invoice"), Ok("web+invoice".to_string()));
+        assert_eq!(validate_scheme("Web+Invoice:"), Ok("web+invoice".to_string()));
+        assert_eq!(validate_scheme("mailto"), Ok("mailto".to_string()));
+
+        assert!(matches!(validate_scheme("web+"), Err(ProtocolError::InvalidScheme(_))));
+        assert!(matches!(validate_scheme("web+invoice2"), Err(ProtocolError::InvalidScheme(_))));
+        assert!(matches!(validate_scheme("web+in-voice"), Err(ProtocolError::InvalidScheme(_))));
+        assert_eq!(
+            validate_scheme("invoice"),
+            Err(ProtocolError::InvalidScheme(
+                "`invoice` isn't safelisted, use `web+invoice` instead".to_string()
+            ))
+        );
+        assert!(validate_scheme("https").is_err());
+    }
+
+    #[test]
+    fn target_test() {
+        let origin = "https://shop.example";
+
+        assert!(validate_target("https:", origin, origin).is_ok());
+        assert_eq!(
+            validate_target("data:", "null", origin),
+            Err(ProtocolError::InsecureUrl("data:".to_string()))
+        );
+        assert_eq!(
+            validate_target("https:", "https://evil.example", origin),
+            Err(ProtocolError::CrossOrigin("https://evil.example".to_string()))
+        );
+        assert_eq!(ProtocolError::MissingPlaceholder.name(), "SyntaxError");
+    }
+
+    #[wasm_bindgen_test]
+    fn validate_url_test() {
+        assert!(validate_url("/open?link=%s").is_ok());
+        assert_eq!(validate_url("/open"), Err(ProtocolError::MissingPlaceholder));
+        assert!(matches!(
+            validate_url("https://evil.example/?link=%s"),
+            Err(ProtocolError::CrossOrigin(_))
+        ));
+    }
+}
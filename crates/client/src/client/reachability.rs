@@ -0,0 +1,498 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    rc::{Rc, Weak},
+};
+
+use js_sys::{Date, Function, Promise};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::{future_to_promise, spawn_local, JsFuture};
+use web_sys::{AbortController, RequestCache, RequestInit, Response, VisibilityState, Window};
+
+use crate::client::{
+    error::ClientError,
+    scope::{document, window},
+    subscription::Subscription,
+};
+
+/// How reachable the network actually is, as opposed to what
+/// `navigator.onLine` claims.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReachabilityStatus {
+    Online,
+    /// Probes succeed, but slowly or not reliably.
+    Degraded,
+    Offline,
+}
+
+impl ReachabilityStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReachabilityStatus::Online => "online",
+            ReachabilityStatus::Degraded => "degraded",
+            ReachabilityStatus::Offline => "offline",
+        }
+    }
+}
+
+/// Options for [`Reachability`]. Every field is optional.
+///
+/// ## Attributes
+/// `endpoint`: URL to probe, defaults to `"/"`. Use something cheap that your
+/// service worker doesn't cache.
+///
+/// `method`: defaults to `"HEAD"`
+///
+/// `intervalMs`: time between probes, defaults to `30000`
+///
+/// `timeoutMs`: probes slower than this fail, defaults to `5000`
+///
+/// `sampleSize`: how many recent probes the success rate and latency are
+/// computed over, defaults to `10`
+///
+/// `maxLatencyMs`: average latency above which the network is degraded,
+/// defaults to `1000`
+///
+/// `minSuccessRate`: success rate below which the network is degraded,
+/// defaults to `0.8`
+///
+/// `offlineAfter`: consecutive failed probes after which the network is
+/// offline, defaults to `2`
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReachabilityOptions {
+    pub endpoint: String,
+    pub method: String,
+    pub interval_ms: u32,
+    pub timeout_ms: u32,
+    pub sample_size: usize,
+    pub max_latency_ms: f64,
+    pub min_success_rate: f64,
+    pub offline_after: usize,
+}
+
+impl Default for ReachabilityOptions {
+    fn default() -> Self {
+        ReachabilityOptions {
+            endpoint: "/".to_string(),
+            method: "HEAD".to_string(),
+            interval_ms: 30_000,
+            timeout_ms: 5_000,
+            sample_size: 10,
+            max_latency_ms: 1_000.0,
+            min_success_rate: 0.8,
+            offline_after: 2,
+        }
+    }
+}
+
+/// The most recent probe results, `Some(latency)` for successful probes and
+/// `None` for failed ones.
+#[derive(Clone, Debug, Default)]
+pub struct ProbeHistory {
+    samples: VecDeque<Option<f64>>,
+    capacity: usize,
+}
+
+impl ProbeHistory {
+    pub fn new(capacity: usize) -> ProbeHistory {
+        ProbeHistory {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn record(&mut self, latency: Option<f64>) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency);
+    }
+
+    pub fn success_rate(&self) -> Option<f64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let successes = self.samples.iter().filter(|sample| sample.is_some()).count();
+        Some(successes as f64 / self.samples.len() as f64)
+    }
+
+    /// Average latency of the successful probes, in milliseconds.
+    pub fn average_latency(&self) -> Option<f64> {
+        let latencies = self.samples.iter().flatten().copied().collect::<Vec<f64>>();
+        if latencies.is_empty() {
+            return None;
+        }
+
+        Some(latencies.iter().sum::<f64>() / latencies.len() as f64)
+    }
+
+    pub fn consecutive_failures(&self) -> usize {
+        self.samples.iter().rev().take_while(|sample| sample.is_none()).count()
+    }
+
+    /// Works out the status from the history. `navigator_online` short
+    /// circuits to offline, since the browser is never wrong about that.
+    pub fn status(&self, options: &ReachabilityOptions, navigator_online: bool) -> ReachabilityStatus {
+        if !navigator_online || self.consecutive_failures() >= options.offline_after.max(1) {
+            return ReachabilityStatus::Offline;
+        }
+
+        let unreliable = self.success_rate().is_some_and(|rate| rate < options.min_success_rate);
+        let slow = self.average_latency().is_some_and(|latency| latency > options.max_latency_ms);
+
+        if unreliable || slow {
+            ReachabilityStatus::Degraded
+        } else {
+            ReachabilityStatus::Online
+        }
+    }
+}
+
+type Listener = Rc<RefCell<dyn FnMut(ReachabilityStatus, ReachabilityStatus)>>;
+
+struct Inner {
+    options: ReachabilityOptions,
+    history: ProbeHistory,
+    status: ReachabilityStatus,
+    listeners: Vec<(u32, Listener)>,
+    next_listener_id: u32,
+    timer: Option<(i32, Closure<dyn FnMut()>)>,
+    subscriptions: Vec<Subscription>,
+}
+
+/// Checks whether the network is actually reachable by periodically probing
+/// an endpoint, instead of trusting `navigator.onLine` (which happily reports
+/// `true` behind captive portals and on dead Wi-Fi).
+///
+/// Probes pause while the page is hidden and resume, with an immediate
+/// probe, when it becomes visible again.
+///
+/// ## Example
+///
+/// ```tsx
+/// const reachability = new Reachability({ endpoint: "/healthcheck", intervalMs: 15000 });
+/// reachability.start();
+///
+/// const subscription = reachability.onChange((status: "online" | "degraded" | "offline") => {
+///   setBanner(status === "online" ? null : status);
+/// });
+/// ```
+///
+/// [Source](https://github.com/remix-pwa/remix-pwa-ecosystem/blob/main/crates/client/src/client/reachability.rs)
+#[wasm_bindgen]
+pub struct Reachability {
+    inner: Rc<RefCell<Inner>>,
+}
+
+#[wasm_bindgen]
+impl Reachability {
+    #[wasm_bindgen(constructor)]
+    pub fn new_js(options: JsValue) -> Result<Reachability, JsValue> {
+        let options: ReachabilityOptions = if options.is_undefined() || options.is_null() {
+            ReachabilityOptions::default()
+        } else {
+            serde_wasm_bindgen::from_value(options)?
+        };
+
+        Ok(Reachability::with_options(options))
+    }
+
+    /// Starts probing, and listens for visibility and `online`/`offline`
+    /// changes. Calling it again restarts the probes.
+    pub fn start(&self) -> Result<(), ClientError> {
+        self.stop();
+
+        let window = window()?;
+        let document = document()?;
+
+        let weak = Rc::downgrade(&self.inner);
+        let visibility_document = document.clone();
+        let on_visibility_change = Subscription::listen(&document, "visibilitychange", move |_| {
+            if let Some(inner) = weak.upgrade() {
+                if visibility_document.visibility_state() == VisibilityState::Hidden {
+                    pause(&inner);
+                } else {
+                    resume(&inner);
+                }
+            }
+        })?;
+
+        let weak = Rc::downgrade(&self.inner);
+        let on_online = Subscription::listen(&window, "online", move |_| spawn_probe(&weak))?;
+        let weak = Rc::downgrade(&self.inner);
+        let on_offline = Subscription::listen(&window, "offline", move |_| spawn_probe(&weak))?;
+
+        self.inner.borrow_mut().subscriptions = vec![on_visibility_change, on_online, on_offline];
+
+        if document.visibility_state() != VisibilityState::Hidden {
+            resume(&self.inner);
+        }
+
+        Ok(())
+    }
+
+    /// Stops probing and removes every listener added by `start()`.
+    pub fn stop(&self) {
+        pause(&self.inner);
+        let subscriptions = std::mem::take(&mut self.inner.borrow_mut().subscriptions);
+        drop(subscriptions);
+    }
+
+    /// Probes right away. Resolves with the resulting status.
+    #[wasm_bindgen(js_name = "probe")]
+    pub fn probe_js(&self) -> Promise {
+        let inner = self.inner.clone();
+
+        future_to_promise(async move {
+            run_probe(&inner).await?;
+            let status = inner.borrow().status;
+            Ok(JsValue::from_str(status.as_str()))
+        })
+    }
+
+    /// `"online"`, `"degraded"` or `"offline"`.
+    #[wasm_bindgen(getter)]
+    pub fn status(&self) -> String {
+        self.inner.borrow().status.as_str().to_string()
+    }
+
+    /// Average latency of recent successful probes, in milliseconds.
+    #[wasm_bindgen(getter)]
+    pub fn latency(&self) -> Option<f64> {
+        self.inner.borrow().history.average_latency()
+    }
+
+    /// Share of recent probes that succeeded, between `0` and `1`.
+    #[wasm_bindgen(getter, js_name = "successRate")]
+    pub fn success_rate(&self) -> Option<f64> {
+        self.inner.borrow().history.success_rate()
+    }
+
+    /// Calls `callback(status, previousStatus)` whenever the status changes.
+    #[wasm_bindgen(js_name = "onChange")]
+    pub fn on_change_js(&self, callback: Function) -> Subscription {
+        self.on_status_change(move |status, previous| {
+            let result = callback.call2(
+                &JsValue::NULL,
+                &JsValue::from_str(status.as_str()),
+                &JsValue::from_str(previous.as_str()),
+            );
+            if let Err(e) = result {
+                log::error!("Error in reachability callback: {:?}", e);
+            }
+        })
+    }
+}
+
+impl Reachability {
+    pub fn with_options(options: ReachabilityOptions) -> Reachability {
+        Reachability {
+            inner: Rc::new(RefCell::new(Inner {
+                history: ProbeHistory::new(options.sample_size),
+                options,
+                status: ReachabilityStatus::Online,
+                listeners: Vec::new(),
+                next_listener_id: 0,
+                timer: None,
+                subscriptions: Vec::new(),
+            })),
+        }
+    }
+
+    pub fn current_status(&self) -> ReachabilityStatus {
+        self.inner.borrow().status
+    }
+
+    /// Probes right away and returns the resulting status.
+    pub async fn check(&self) -> Result<ReachabilityStatus, ClientError> {
+        run_probe(&self.inner).await?;
+        Ok(self.current_status())
+    }
+
+    /// Calls `callback(status, previous)` whenever the status changes, until
+    /// the returned subscription is dropped or unsubscribed.
+    pub fn on_status_change<F>(&self, callback: F) -> Subscription
+    where
+        F: FnMut(ReachabilityStatus, ReachabilityStatus) + 'static,
+    {
+        let mut inner = self.inner.borrow_mut();
+        let id = inner.next_listener_id;
+        inner.next_listener_id += 1;
+        inner.listeners.push((id, Rc::new(RefCell::new(callback))));
+
+        let weak = Rc::downgrade(&self.inner);
+        Subscription::new(move || {
+            if let Some(inner) = weak.upgrade() {
+                inner.borrow_mut().listeners.retain(|(listener_id, _)| *listener_id != id);
+            }
+        })
+    }
+}
+
+impl Drop for Reachability {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn resume(inner: &Rc<RefCell<Inner>>) {
+    if inner.borrow().timer.is_some() {
+        return;
+    }
+
+    let window = match window() {
+        Ok(window) => window,
+        Err(_) => return,
+    };
+
+    let weak = Rc::downgrade(inner);
+    spawn_probe(&weak);
+    let tick = Closure::<dyn FnMut()>::new(move || spawn_probe(&weak));
+
+    let interval = inner.borrow().options.interval_ms as i32;
+    match window.set_interval_with_callback_and_timeout_and_arguments_0(tick.as_ref().unchecked_ref(), interval) {
+        Ok(handle) => inner.borrow_mut().timer = Some((handle, tick)),
+        Err(e) => log::error!("Error starting reachability probes: {:?}", e),
+    }
+}
+
+fn pause(inner: &Rc<RefCell<Inner>>) {
+    if let Some((handle, _)) = inner.borrow_mut().timer.take() {
+        if let Ok(window) = window() {
+            window.clear_interval_with_handle(handle);
+        }
+    }
+}
+
+fn spawn_probe(weak: &Weak<RefCell<Inner>>) {
+    if let Some(inner) = weak.upgrade() {
+        spawn_local(async move {
+            if let Err(e) = run_probe(&inner).await {
+                log::error!("Error probing reachability: {:?}", e);
+            }
+        });
+    }
+}
+
+async fn run_probe(inner: &Rc<RefCell<Inner>>) -> Result<(), ClientError> {
+    let window = window()?;
+    let options = inner.borrow().options.clone();
+    let online = window.navigator().on_line();
+
+    let latency = if online { probe(&window, &options).await } else { None };
+
+    let (status, previous, listeners) = {
+        let mut inner = inner.borrow_mut();
+        inner.history.record(latency);
+
+        let previous = inner.status;
+        inner.status = inner.history.status(&inner.options, online);
+        let listeners = inner.listeners.iter().map(|(_, listener)| listener.clone()).collect::<Vec<_>>();
+
+        (inner.status, previous, listeners)
+    };
+
+    if status != previous {
+        for listener in listeners {
+            (listener.borrow_mut())(status, previous);
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends a single probe. Returns its latency, or `None` if it failed, timed
+/// out or got redirected (a telltale sign of a captive portal).
+async fn probe(window: &Window, options: &ReachabilityOptions) -> Option<f64> {
+    let controller = AbortController::new().ok()?;
+    let mut init = RequestInit::new();
+    init.method(&options.method)
+        .cache(RequestCache::NoStore)
+        .signal(Some(&controller.signal()));
+
+    let abort = Closure::once(move || controller.abort());
+    let timeout = window
+        .set_timeout_with_callback_and_timeout_and_arguments_0(abort.as_ref().unchecked_ref(), options.timeout_ms as i32)
+        .ok();
+
+    let started = Date::now();
+    let result = JsFuture::from(window.fetch_with_str_and_init(&options.endpoint, &init)).await;
+    let latency = Date::now() - started;
+
+    if let Some(timeout) = timeout {
+        window.clear_timeout_with_handle(timeout);
+    }
+
+    let response: Response = result.ok()?.dyn_into().ok()?;
+    (response.ok() && !response.redirected()).then_some(latency)
+}
+
+#[cfg(test)]
+mod reachability_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn history(samples: &[Option<f64>]) -> ProbeHistory {
+        let mut history = ProbeHistory::new(5);
+        for sample in samples {
+            history.record(*sample);
+        }
+        history
+    }
+
+    #[test]
+    fn history_stats_test() {
+        let history = history(&[Some(100.0), None, Some(300.0), None]);
+
+        assert_eq!(history.success_rate(), Some(0.5));
+        assert_eq!(history.average_latency(), Some(200.0));
+        assert_eq!(history.consecutive_failures(), 1);
+        assert_eq!(ProbeHistory::new(5).success_rate(), None);
+    }
+
+    #[test]
+    fn history_capacity_test() {
+        let history = history(&[None, None, None, Some(10.0), Some(10.0), Some(10.0), Some(10.0), Some(10.0)]);
+        assert_eq!(history.success_rate(), Some(1.0));
+    }
+
+    #[test]
+    fn status_test() {
+        let options = ReachabilityOptions::default();
+
+        assert_eq!(history(&[]).status(&options, true), ReachabilityStatus::Online);
+        assert_eq!(history(&[Some(50.0)]).status(&options, false), ReachabilityStatus::Offline);
+        assert_eq!(history(&[Some(50.0), Some(60.0)]).status(&options, true), ReachabilityStatus::Online);
+        assert_eq!(history(&[Some(50.0), Some(3000.0)]).status(&options, true), ReachabilityStatus::Degraded);
+        assert_eq!(history(&[Some(50.0), None, Some(50.0)]).status(&options, true), ReachabilityStatus::Degraded);
+        assert_eq!(history(&[Some(50.0), None, None]).status(&options, true), ReachabilityStatus::Offline);
+    }
+
+    #[wasm_bindgen_test]
+    async fn probe_test() {
+        // `wasm-pack test` serves the test page, so `/` is a local static server
+        let reachability = Reachability::new_js(JsValue::UNDEFINED).unwrap();
+        let status = JsFuture::from(reachability.probe_js()).await.unwrap();
+
+        assert_eq!(status.as_string().unwrap(), "online");
+        assert_eq!(reachability.success_rate(), Some(1.0));
+    }
+
+    #[wasm_bindgen_test]
+    async fn unreachable_probe_test() {
+        let options = js_sys::Object::new();
+        js_sys::Reflect::set(&options, &"endpoint".into(), &"http://127.0.0.1:9/".into()).unwrap();
+        js_sys::Reflect::set(&options, &"offlineAfter".into(), &1.into()).unwrap();
+
+        let reachability = Reachability::new_js(options.into()).unwrap();
+        let status = JsFuture::from(reachability.probe_js()).await.unwrap();
+
+        assert_eq!(status.as_string().unwrap(), "offline");
+    }
+}
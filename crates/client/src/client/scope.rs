@@ -0,0 +1,159 @@
+use js_sys::{Array, Function, Reflect};
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{Document, NetworkInformation, Permissions, Window, WorkerGlobalScope, WorkerNavigator};
+
+use crate::client::{capabilities::has, error::ClientError};
+
+/// The global scope the module is running in: a browser tab or a Web/Service
+/// Worker.
+pub enum GlobalScope {
+    Window(Window),
+    Worker(WorkerGlobalScope),
+}
+
+impl GlobalScope {
+    /// Resolves the current global scope. Fails with
+    /// [`ClientError::NotInBrowser`] when there is neither a `window` nor a
+    /// worker global, e.g. during server rendering.
+    pub fn current() -> Result<GlobalScope, ClientError> {
+        if let Some(window) = web_sys::window() {
+            return Ok(GlobalScope::Window(window));
+        }
+
+        js_sys::global()
+            .dyn_into::<WorkerGlobalScope>()
+            .map(GlobalScope::Worker)
+            .map_err(|_| ClientError::NotInBrowser)
+    }
+
+    pub fn navigator(&self) -> Navigator {
+        match self {
+            GlobalScope::Window(window) => Navigator::Window(window.navigator()),
+            GlobalScope::Worker(worker) => Navigator::Worker(worker.navigator()),
+        }
+    }
+
+    /// `setTimeout`, returning the timer's handle.
+    pub fn set_timeout(&self, callback: &Function, ms: i32) -> Result<i32, JsValue> {
+        match self {
+            GlobalScope::Window(window) => window.set_timeout_with_callback_and_timeout_and_arguments_0(callback, ms),
+            GlobalScope::Worker(worker) => worker.set_timeout_with_callback_and_timeout_and_arguments_0(callback, ms),
+        }
+    }
+
+    pub fn clear_timeout(&self, handle: i32) {
+        match self {
+            GlobalScope::Window(window) => window.clear_timeout_with_handle(handle),
+            GlobalScope::Worker(worker) => worker.clear_timeout_with_handle(handle),
+        }
+    }
+}
+
+/// `navigator`, resolved from either a `Window` or a `WorkerGlobalScope`.
+/// Only exposes what both of them have in common.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/WorkerNavigator)
+pub enum Navigator {
+    Window(web_sys::Navigator),
+    Worker(WorkerNavigator),
+}
+
+impl Navigator {
+    pub fn on_line(&self) -> bool {
+        match self {
+            Navigator::Window(navigator) => navigator.on_line(),
+            Navigator::Worker(navigator) => navigator.on_line(),
+        }
+    }
+
+    pub fn language(&self) -> Option<String> {
+        match self {
+            Navigator::Window(navigator) => navigator.language(),
+            Navigator::Worker(navigator) => navigator.language(),
+        }
+    }
+
+    pub fn languages(&self) -> Array {
+        match self {
+            Navigator::Window(navigator) => navigator.languages(),
+            Navigator::Worker(navigator) => navigator.languages(),
+        }
+    }
+
+    /// Fails with [`ClientError::Unsupported`] on browsers without the Network
+    /// Information API (Firefox, Safari).
+    pub fn connection(&self) -> Result<NetworkInformation, ClientError> {
+        if !has(self.as_js(), "connection") {
+            return Err(ClientError::Unsupported("Network Information API"));
+        }
+
+        let connection = match self {
+            Navigator::Window(navigator) => navigator.connection(),
+            Navigator::Worker(navigator) => navigator.connection(),
+        };
+        Ok(connection?)
+    }
+
+    pub fn permissions(&self) -> Result<Permissions, ClientError> {
+        if !has(self.as_js(), "permissions") {
+            return Err(ClientError::Unsupported("Permissions API"));
+        }
+
+        let permissions = match self {
+            Navigator::Window(navigator) => navigator.permissions(),
+            // `WorkerNavigator.permissions` has no binding in web-sys yet
+            Navigator::Worker(navigator) => {
+                Reflect::get(navigator, &"permissions".into()).map(JsCast::unchecked_into)
+            }
+        };
+        Ok(permissions?)
+    }
+
+    /// The underlying JS object, for probing properties that have no binding.
+    pub fn as_js(&self) -> &JsValue {
+        match self {
+            Navigator::Window(navigator) => navigator.as_ref(),
+            Navigator::Worker(navigator) => navigator.as_ref(),
+        }
+    }
+}
+
+/// Returns `navigator` from whichever global scope is available.
+pub fn navigator() -> Result<Navigator, ClientError> {
+    Ok(GlobalScope::current()?.navigator())
+}
+
+/// Returns `window`, for APIs that only exist in browser tabs.
+pub fn window() -> Result<Window, ClientError> {
+    web_sys::window().ok_or(ClientError::NotInBrowser)
+}
+
+/// Returns `window.document`, for APIs that only exist in browser tabs.
+pub fn document() -> Result<Document, ClientError> {
+    window()?.document().ok_or(ClientError::NotInBrowser)
+}
+
+#[cfg(test)]
+mod scope_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn current_scope_test() {
+        let scope = GlobalScope::current();
+        assert!(matches!(scope, Ok(GlobalScope::Window(_))));
+    }
+
+    #[wasm_bindgen_test]
+    fn navigator_test() {
+        let navigator = navigator().unwrap();
+        assert!(navigator.language().is_some());
+    }
+
+    #[wasm_bindgen_test]
+    fn document_test() {
+        assert!(document().is_ok());
+    }
+}
@@ -0,0 +1,176 @@
+use js_sys::Array;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Cache, File, FormData, Response, Url};
+
+use crate::client::{error::ClientError, files::FileHandle, scope::window};
+
+/// Cache the `@remix-pwa/sw` router hands shares over through. Must match
+/// `crates/sw/src/share_target.rs`.
+const SHARE_TARGET_CACHE: &str = "remix-pwa-share-target";
+const SHARE_PARAM: &str = "share-target";
+const SHARE_KEY_PREFIX: &str = "/__remix-pwa-share-target/";
+
+/// Content shared to the installed app from another app.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/Manifest/share_target)
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct SharedData {
+    #[wasm_bindgen(skip)]
+    pub title: String,
+    #[wasm_bindgen(skip)]
+    pub text: String,
+    /// Falls back to the first link found in `text`
+    #[wasm_bindgen(skip)]
+    pub url: String,
+    #[wasm_bindgen(skip)]
+    pub files: Vec<FileHandle>,
+}
+
+impl SharedData {
+    fn from_form(form: &FormData) -> SharedData {
+        let field = |name: &str| form.get(name).as_string().unwrap_or_default();
+
+        SharedData {
+            title: field("title"),
+            text: field("text"),
+            url: field("url"),
+            files: form
+                .get_all("files")
+                .iter()
+                .filter_map(|file| file.dyn_into::<File>().ok())
+                .map(FileHandle::from_file)
+                .collect(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl SharedData {
+    #[wasm_bindgen(getter)]
+    pub fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// An array of `FileHandle`s
+    #[wasm_bindgen(getter, js_name = "files")]
+    pub fn files_js(&self) -> Array {
+        self.files.iter().cloned().map(JsValue::from).collect()
+    }
+}
+
+/// Reads and removes the share stored under `id`.
+async fn take(id: &str) -> Result<Option<SharedData>, JsValue> {
+    let cache = JsFuture::from(window()?.caches()?.open(SHARE_TARGET_CACHE))
+        .await?
+        .unchecked_into::<Cache>();
+    let key = format!("{}{}", SHARE_KEY_PREFIX, id);
+
+    let response = match JsFuture::from(cache.match_with_str(&key)).await?.dyn_into::<Response>() {
+        Ok(response) => response,
+        Err(_) => return Ok(None),
+    };
+    let form = JsFuture::from(response.form_data()?).await?.unchecked_into::<FormData>();
+    JsFuture::from(cache.delete_with_str(&key)).await?;
+
+    Ok(Some(SharedData::from_form(&form)))
+}
+
+/// Takes the share the service worker redirected to this page with, if any,
+/// and drops its id from the address bar.
+async fn take_from_location() -> Result<Option<SharedData>, JsValue> {
+    let window = window()?;
+    let url = Url::new(&window.location().href()?)?;
+
+    let id = match url.search_params().get(SHARE_PARAM) {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+
+    // Only drop the id from the address bar once the share is taken, so a
+    // failed read can be retried
+    let share = take(&id).await?;
+
+    url.search_params().delete(SHARE_PARAM);
+    window
+        .history()?
+        .replace_state_with_url(&JsValue::NULL, "", Some(&url.href()))?;
+
+    Ok(share)
+}
+
+/// The content shared to the app, on the page the service worker redirected
+/// the share to, and `None` anywhere else. Each share can only be taken once.
+pub async fn take_shared_data() -> Result<Option<SharedData>, ClientError> {
+    Ok(take_from_location().await?)
+}
+
+/// Returns the content shared to the app, on the page the service worker
+/// redirected the share to, and `undefined` anywhere else. Each share can
+/// only be taken once, so reloading the page doesn't share it again.
+///
+/// Needs the `@remix-pwa/sw` router to handle the manifest's
+/// `share_target` with `router.shareTarget(...)`.
+///
+/// ## Example
+///
+/// ```tsx
+/// useEffect(() => {
+///   takeSharedData().then((share) => {
+///     if (share) createNote({ title: share.title, body: share.text, link: share.url, attachments: share.files });
+///   });
+/// }, []);
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/Manifest/share_target)
+#[wasm_bindgen(js_name = "takeSharedData")]
+pub async fn take_shared_data_js() -> Result<Option<SharedData>, JsValue> {
+    take_from_location().await
+}
+
+#[cfg(test)]
+mod share_target_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    async fn take_test() {
+        let form = FormData::new().unwrap();
+        form.append_with_str("title", "Remix").unwrap();
+        form.append_with_str("text", "Check this out https://remix.run").unwrap();
+        form.append_with_str("url", "https://remix.run").unwrap();
+        let file = File::new_with_str_sequence(&Array::of1(&"hello".into()), "hello.txt").unwrap();
+        form.append_with_blob_and_filename("files", &file, "hello.txt").unwrap();
+
+        let cache = JsFuture::from(window().unwrap().caches().unwrap().open(SHARE_TARGET_CACHE))
+            .await
+            .unwrap()
+            .unchecked_into::<Cache>();
+        let response = Response::new_with_opt_form_data(Some(&form)).unwrap();
+        JsFuture::from(cache.put_with_str(&format!("{}test", SHARE_KEY_PREFIX), &response))
+            .await
+            .unwrap();
+
+        let share = take("test").await.unwrap().unwrap();
+        assert_eq!(share.title, "Remix");
+        assert_eq!(share.url, "https://remix.run");
+        assert_eq!(share.files.len(), 1);
+        assert_eq!(share.files[0].name(), "hello.txt");
+
+        // Shares can only be taken once
+        assert!(take("test").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,130 @@
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::EventTarget;
+
+/// A handle to an active subscription (event listener, timer, ...).
+///
+/// Call `unsubscribe()` to stop receiving updates. Freeing the handle from
+/// JS, or dropping it from Rust, unsubscribes as well.
+///
+/// ## Example
+///
+/// ```tsx
+/// useEffect(() => {
+///   const subscription = policy.onTierChange((tier) => setTier(tier));
+///   return () => subscription.unsubscribe();
+/// }, []);
+/// ```
+///
+/// [Source](https://github.com/remix-pwa/remix-pwa-ecosystem/blob/main/crates/client/src/client/subscription.rs)
+#[wasm_bindgen]
+pub struct Subscription {
+    teardown: Option<Box<dyn FnOnce()>>,
+}
+
+impl Subscription {
+    /// Wraps an arbitrary teardown function.
+    pub fn new(teardown: impl FnOnce() + 'static) -> Subscription {
+        Subscription {
+            teardown: Some(Box::new(teardown)),
+        }
+    }
+
+    /// A subscription with nothing to tear down, for events that can never
+    /// fire in the current browser.
+    pub fn noop() -> Subscription {
+        Subscription { teardown: None }
+    }
+
+    /// Adds `callback` as an `event` listener on `target`; unsubscribing
+    /// removes it again.
+    pub fn listen(
+        target: &EventTarget,
+        event: &str,
+        callback: impl FnMut(JsValue) + 'static,
+    ) -> Result<Subscription, JsValue> {
+        let listener = Closure::<dyn FnMut(JsValue)>::new(callback);
+        target.add_event_listener_with_callback(event, listener.as_ref().unchecked_ref())?;
+
+        let target = target.clone();
+        let event = event.to_string();
+        Ok(Subscription::new(move || {
+            let _ = target.remove_event_listener_with_callback(&event, listener.as_ref().unchecked_ref());
+            drop(listener);
+        }))
+    }
+
+    /// Combines several subscriptions into one that tears all of them down.
+    pub fn all(subscriptions: Vec<Subscription>) -> Subscription {
+        Subscription::new(move || drop(subscriptions))
+    }
+}
+
+#[wasm_bindgen]
+impl Subscription {
+    /// Stops the subscription. Calling it more than once is a no-op.
+    pub fn unsubscribe(&mut self) {
+        if let Some(teardown) = self.teardown.take() {
+            teardown();
+        }
+    }
+
+    /// Whether there is still a teardown to run. Turns `false` after
+    /// `unsubscribe()`, and is always `false` for a no-op subscription.
+    #[wasm_bindgen(getter, js_name = "isActive")]
+    pub fn is_active(&self) -> bool {
+        self.teardown.is_some()
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.unsubscribe();
+    }
+}
+
+#[cfg(test)]
+mod subscription_tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    #[test]
+    fn unsubscribe_test() {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let mut subscription = Subscription::new(move || counter.set(counter.get() + 1));
+
+        assert!(subscription.is_active());
+        subscription.unsubscribe();
+        subscription.unsubscribe();
+
+        assert!(!subscription.is_active());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn noop_test() {
+        let mut subscription = Subscription::noop();
+
+        assert!(!subscription.is_active());
+        subscription.unsubscribe();
+        assert!(!subscription.is_active());
+    }
+
+    #[test]
+    fn drop_test() {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let subscriptions = vec![
+            Subscription::new({
+                let counter = counter.clone();
+                move || counter.set(counter.get() + 1)
+            }),
+            Subscription::new(move || counter.set(counter.get() + 1)),
+            Subscription::noop(),
+        ];
+
+        drop(Subscription::all(subscriptions));
+        assert_eq!(calls.get(), 2);
+    }
+}
@@ -0,0 +1,838 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use js_sys::{Date, Function, Promise, Reflect};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{AbortController, BroadcastChannel, Storage};
+
+use crate::client::{
+    capabilities::get,
+    error::{js_error, ClientError},
+    locks::{with_lock, LockError, LockOptions},
+    scope::{window, GlobalScope},
+    subscription::Subscription,
+};
+
+/// Prefix of the `BroadcastChannel`s, locks and storage keys used by this
+/// module, so they don't clash with the app's own.
+const PREFIX: &str = "remix-pwa";
+
+/// How long a storage-based leader lease lasts without being renewed, in
+/// milliseconds.
+const LEASE_MS: f64 = 3_000.0;
+
+/// How often the storage-based leader renews its lease, and followers check
+/// for an expired one.
+const HEARTBEAT_MS: i32 = 1_000;
+
+/// A random id for this tab, unique enough to tell tabs apart.
+fn tab_id() -> String {
+    format!("{:x}-{:x}", Date::now() as u64, (js_sys::Math::random() * u32::MAX as f64) as u32)
+}
+
+/// What's sent over the channel. The payload travels next to it as
+/// `payload`, untouched by serde, so any structured-clonable value works.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Envelope {
+    Message { topic: String, from: String },
+    Request { id: String, topic: String, from: String },
+    Response { id: String, to: String, ok: bool },
+}
+
+type Listener = Rc<RefCell<dyn FnMut(JsValue, String)>>;
+type Handler = Rc<RefCell<dyn FnMut(JsValue) -> Result<JsValue, JsValue>>>;
+
+struct PendingRequest {
+    resolve: Function,
+    reject: Function,
+    timeout: i32,
+    _expire: Closure<dyn FnMut()>,
+}
+
+struct BusInner {
+    channel: BroadcastChannel,
+    tab_id: String,
+    listeners: Vec<(u32, String, Listener)>,
+    handlers: HashMap<String, (u32, Handler)>,
+    pending: HashMap<String, PendingRequest>,
+    next_id: u32,
+    subscription: Option<Subscription>,
+}
+
+/// A message bus between every tab (and worker) of the app on the same
+/// origin, over a `BroadcastChannel`.
+///
+/// Messages are published on a topic. Requests expect an answer from
+/// whichever tab handles the topic, and fail with a `TimeoutError` when
+/// nobody answers in time.
+///
+/// ## Example
+///
+/// ```tsx
+/// const bus = new TabBus("app");
+///
+/// bus.on("cart-updated", (cart) => setCart(cart));
+/// bus.post("cart-updated", cart);
+///
+/// // In the leader tab
+/// bus.handle("sync-status", () => syncer.status());
+/// // Anywhere else
+/// const status = await bus.request("sync-status", null, 1000);
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/BroadcastChannel)
+#[wasm_bindgen]
+pub struct TabBus {
+    inner: Rc<RefCell<BusInner>>,
+}
+
+impl TabBus {
+    /// Publishes a serde payload on `topic`.
+    pub fn post_typed<T: Serialize>(&self, topic: &str, payload: &T) -> Result<(), ClientError> {
+        Ok(self.post_js(topic, serde_wasm_bindgen::to_value(payload)?)?)
+    }
+
+    /// Calls `callback(payload, senderTabId)` for messages on `topic` that
+    /// deserialize to `T`. Others are logged and skipped.
+    pub fn on_typed<T: DeserializeOwned>(&self, topic: &str, mut callback: impl FnMut(T, String) + 'static) -> Subscription {
+        self.listen(topic, move |payload, from| match serde_wasm_bindgen::from_value(payload) {
+            Ok(payload) => callback(payload, from),
+            Err(e) => log::error!("Invalid tab message payload: {:?}", e),
+        })
+    }
+
+    /// Sends a request and deserializes the answer. Fails with a
+    /// `TimeoutError` message if no tab answers within `timeout_ms`.
+    pub async fn request_typed<Req: Serialize, Res: DeserializeOwned>(
+        &self,
+        topic: &str,
+        payload: &Req,
+        timeout_ms: u32,
+    ) -> Result<Res, ClientError> {
+        let response = JsFuture::from(self.request_js(topic, serde_wasm_bindgen::to_value(payload)?, timeout_ms)).await?;
+        Ok(serde_wasm_bindgen::from_value(response)?)
+    }
+
+    /// Answers requests on `topic` with `handler`. Errors are sent back to
+    /// the requesting tab.
+    pub fn handle_typed<Req: DeserializeOwned, Res: Serialize>(
+        &self,
+        topic: &str,
+        mut handler: impl FnMut(Req) -> Result<Res, ClientError> + 'static,
+    ) -> Subscription {
+        self.add_handler(topic, move |payload| {
+            let response = handler(serde_wasm_bindgen::from_value(payload)?)?;
+            Ok(serde_wasm_bindgen::to_value(&response)?)
+        })
+    }
+
+    pub fn listen(&self, topic: &str, callback: impl FnMut(JsValue, String) + 'static) -> Subscription {
+        let mut inner = self.inner.borrow_mut();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.listeners.push((id, topic.to_string(), Rc::new(RefCell::new(callback))));
+
+        let weak = Rc::downgrade(&self.inner);
+        Subscription::new(move || {
+            if let Some(inner) = weak.upgrade() {
+                inner.borrow_mut().listeners.retain(|(listener_id, _, _)| *listener_id != id);
+            }
+        })
+    }
+
+    fn add_handler(&self, topic: &str, handler: impl FnMut(JsValue) -> Result<JsValue, JsValue> + 'static) -> Subscription {
+        let mut inner = self.inner.borrow_mut();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.handlers.insert(topic.to_string(), (id, Rc::new(RefCell::new(handler))));
+
+        let weak = Rc::downgrade(&self.inner);
+        let topic = topic.to_string();
+        Subscription::new(move || {
+            if let Some(inner) = weak.upgrade() {
+                let mut inner = inner.borrow_mut();
+                // Only remove the handler if it hasn't been replaced since
+                if inner.handlers.get(&topic).is_some_and(|(handler_id, _)| *handler_id == id) {
+                    inner.handlers.remove(&topic);
+                }
+            }
+        })
+    }
+
+    fn send(&self, envelope: &Envelope, payload: &JsValue) -> Result<(), JsValue> {
+        send(&self.inner.borrow().channel, envelope, payload)
+    }
+}
+
+fn send(channel: &BroadcastChannel, envelope: &Envelope, payload: &JsValue) -> Result<(), JsValue> {
+    let message = serde_wasm_bindgen::to_value(envelope)?;
+    Reflect::set(&message, &"payload".into(), payload)?;
+
+    channel.post_message(&message)
+}
+
+#[wasm_bindgen]
+impl TabBus {
+    /// Opens the bus named `name`. Every tab opening the same name talks to
+    /// each other.
+    #[wasm_bindgen(constructor)]
+    pub fn new(name: String) -> Result<TabBus, ClientError> {
+        GlobalScope::current()?;
+        if get(&js_sys::global(), "BroadcastChannel").is_none() {
+            return Err(ClientError::Unsupported("BroadcastChannel API"));
+        }
+
+        let channel = BroadcastChannel::new(&format!("{}:{}", PREFIX, name))?;
+        let inner = Rc::new(RefCell::new(BusInner {
+            channel: channel.clone(),
+            tab_id: tab_id(),
+            listeners: Vec::new(),
+            handlers: HashMap::new(),
+            pending: HashMap::new(),
+            next_id: 0,
+            subscription: None,
+        }));
+
+        let weak = Rc::downgrade(&inner);
+        let subscription = Subscription::listen(&channel, "message", move |event| {
+            if let Some(inner) = weak.upgrade() {
+                receive(&inner, &event);
+            }
+        })?;
+        inner.borrow_mut().subscription = Some(subscription);
+
+        Ok(TabBus { inner })
+    }
+
+    /// This tab's id, as passed to other tabs' callbacks.
+    #[wasm_bindgen(getter, js_name = "tabId")]
+    pub fn tab_id(&self) -> String {
+        self.inner.borrow().tab_id.clone()
+    }
+
+    /// Publishes `payload` (any structured-clonable value) on `topic` to
+    /// every other tab.
+    #[wasm_bindgen(js_name = "post")]
+    pub fn post_js(&self, topic: &str, payload: JsValue) -> Result<(), JsValue> {
+        let from = self.tab_id();
+        self.send(
+            &Envelope::Message {
+                topic: topic.to_string(),
+                from,
+            },
+            &payload,
+        )
+    }
+
+    /// Calls `callback(payload, senderTabId)` for every message on `topic`.
+    #[wasm_bindgen(js_name = "on")]
+    pub fn on_js(&self, topic: &str, callback: Function) -> Subscription {
+        self.listen(topic, move |payload, from| {
+            if let Err(e) = callback.call2(&JsValue::NULL, &payload, &JsValue::from(from)) {
+                log::error!("Error in tab message callback: {:?}", e);
+            }
+        })
+    }
+
+    /// Answers requests on `topic`. `handler(payload)` may return a value or
+    /// a promise; a throw or rejection is sent back as the request's error.
+    /// Replaces any previous handler for the topic in this tab.
+    #[wasm_bindgen(js_name = "handle")]
+    pub fn handle_js(&self, topic: &str, handler: Function) -> Subscription {
+        self.add_handler(topic, move |payload| handler.call1(&JsValue::NULL, &payload))
+    }
+
+    /// Sends a request on `topic` and resolves with the first answer.
+    /// Rejects with a `TimeoutError` if no tab answers within `timeoutMs`.
+    #[wasm_bindgen(js_name = "request")]
+    pub fn request_js(&self, topic: &str, payload: JsValue, timeout_ms: u32) -> Promise {
+        let id = {
+            let mut inner = self.inner.borrow_mut();
+            inner.next_id += 1;
+            format!("{}:{}", inner.tab_id, inner.next_id)
+        };
+
+        let weak = Rc::downgrade(&self.inner);
+        let request_id = id.clone();
+        let promise = Promise::new(&mut |resolve, reject| {
+            let expire = Closure::once({
+                let weak = weak.clone();
+                let request_id = request_id.clone();
+                let topic = topic.to_string();
+                move || {
+                    let pending = weak.upgrade().and_then(|inner| inner.borrow_mut().pending.remove(&request_id));
+                    if let Some(pending) = pending {
+                        let message = format!("No tab answered \"{}\" in time", topic);
+                        let _ = pending.reject.call1(&JsValue::NULL, &js_error("TimeoutError", &message));
+                        // This closure is still running, free it once it has returned
+                        spawn_local(async move { drop(pending) });
+                    }
+                }
+            });
+
+            let timeout = GlobalScope::current()
+                .map_err(JsValue::from)
+                .and_then(|scope| scope.set_timeout(expire.as_ref().unchecked_ref(), timeout_ms as i32));
+
+            match (timeout, weak.upgrade()) {
+                (Ok(timeout), Some(inner)) => {
+                    inner.borrow_mut().pending.insert(
+                        request_id.clone(),
+                        PendingRequest {
+                            resolve,
+                            reject,
+                            timeout,
+                            _expire: expire,
+                        },
+                    );
+                }
+                (Err(e), _) => {
+                    let _ = reject.call1(&JsValue::NULL, &e);
+                }
+                (_, None) => {}
+            }
+        });
+
+        let from = self.tab_id();
+        let envelope = Envelope::Request {
+            id: id.clone(),
+            topic: topic.to_string(),
+            from,
+        };
+        if let Err(e) = self.send(&envelope, &payload) {
+            if let Some(pending) = self.inner.borrow_mut().pending.remove(&id) {
+                clear_timeout(pending.timeout);
+                let _ = pending.reject.call1(&JsValue::NULL, &e);
+            }
+        }
+
+        promise
+    }
+
+    /// Closes the channel. Pending requests are left to time out.
+    pub fn close(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.subscription.take();
+        inner.channel.close();
+    }
+}
+
+impl Drop for TabBus {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+fn clear_timeout(handle: i32) {
+    if let Ok(scope) = GlobalScope::current() {
+        scope.clear_timeout(handle);
+    }
+}
+
+fn receive(inner: &Rc<RefCell<BusInner>>, event: &JsValue) {
+    let data = get(event, "data").unwrap_or(JsValue::UNDEFINED);
+    let payload = get(&data, "payload").unwrap_or(JsValue::UNDEFINED);
+
+    // Other code may share the channel name, ignore what isn't ours
+    let envelope: Envelope = match serde_wasm_bindgen::from_value(data) {
+        Ok(envelope) => envelope,
+        Err(_) => return,
+    };
+
+    match envelope {
+        Envelope::Message { topic, from } => {
+            let listeners = inner
+                .borrow()
+                .listeners
+                .iter()
+                .filter(|(_, listener_topic, _)| *listener_topic == topic)
+                .map(|(_, _, listener)| listener.clone())
+                .collect::<Vec<_>>();
+
+            for listener in listeners {
+                (listener.borrow_mut())(payload.clone(), from.clone());
+            }
+        }
+        Envelope::Request { id, topic, from } => {
+            let handler = match inner.borrow().handlers.get(&topic) {
+                Some((_, handler)) => handler.clone(),
+                None => return,
+            };
+
+            let result = (handler.borrow_mut())(payload);
+            let weak = Rc::downgrade(inner);
+            spawn_local(async move {
+                let result = match result {
+                    Ok(value) => JsFuture::from(Promise::resolve(&value)).await,
+                    Err(e) => Err(e),
+                };
+                respond(&weak, id, from, result);
+            });
+        }
+        Envelope::Response { id, to, ok } => {
+            let pending = {
+                let mut inner = inner.borrow_mut();
+                if to != inner.tab_id {
+                    return;
+                }
+                inner.pending.remove(&id)
+            };
+
+            // Several tabs may answer, only the first one counts
+            if let Some(pending) = pending {
+                clear_timeout(pending.timeout);
+                let settle = if ok { &pending.resolve } else { &pending.reject };
+                let _ = settle.call1(&JsValue::NULL, &payload);
+            }
+        }
+    }
+}
+
+fn respond(weak: &Weak<RefCell<BusInner>>, id: String, to: String, result: Result<JsValue, JsValue>) {
+    let inner = match weak.upgrade() {
+        Some(inner) => inner,
+        None => return,
+    };
+
+    let ok = result.is_ok();
+    let payload = match result {
+        Ok(value) => value,
+        // Errors can't always be cloned across tabs, send their message
+        Err(e) => e
+            .dyn_ref::<js_sys::Error>()
+            .map(|e| JsValue::from(e.message()))
+            .unwrap_or(e),
+    };
+
+    let channel = inner.borrow().channel.clone();
+    if let Err(e) = send(&channel, &Envelope::Response { id, to, ok }, &payload) {
+        log::error!("Error answering tab request: {:?}", e);
+    }
+}
+
+/// A storage-based leader lease, used where the Web Locks API isn't
+/// available.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Lease {
+    pub id: String,
+    /// When the lease runs out, in milliseconds since the epoch
+    pub expires: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeaseAction {
+    /// This tab holds the lease: it leads, and extends it.
+    Renew,
+    /// Nobody holds a valid lease: try to take it. Leadership is only
+    /// confirmed on the next check, once competing tabs had a chance to
+    /// overwrite it.
+    Claim,
+    /// Another tab leads.
+    Follow,
+}
+
+/// Decides what a tab should do given the current lease.
+pub fn decide(lease: Option<&Lease>, tab_id: &str, now: f64) -> LeaseAction {
+    match lease {
+        Some(lease) if lease.id == tab_id => LeaseAction::Renew,
+        Some(lease) if lease.expires > now => LeaseAction::Follow,
+        _ => LeaseAction::Claim,
+    }
+}
+
+type LeadershipListener = Rc<RefCell<dyn FnMut(bool)>>;
+
+struct ElectionInner {
+    name: String,
+    tab_id: String,
+    running: bool,
+    is_leader: bool,
+    listeners: Vec<(u32, LeadershipListener)>,
+    next_listener_id: u32,
+    /// Web Locks: resolving this releases the lock
+    release: Option<Function>,
+    /// Web Locks: aborts a lock request that's still queued
+    abort: Option<AbortController>,
+    /// Storage fallback: heartbeat timer and `storage`/`pagehide` listeners
+    heartbeat: Option<(i32, Closure<dyn FnMut()>)>,
+    subscriptions: Vec<Subscription>,
+}
+
+/// Elects exactly one leader among the app's open tabs, e.g. to own a sync
+/// loop or the app badge.
+///
+/// Uses the Web Locks API, where the leader holds a lock for as long as it
+/// runs and the next tab in line takes over when it closes. Browsers without
+/// it fall back to a lease in `localStorage`, renewed every second, which
+/// hands over within a few seconds when the leader disappears.
+///
+/// ## Example
+///
+/// ```tsx
+/// const election = new LeaderElection("sync");
+/// election.onChange((isLeader) => (isLeader ? syncer.start() : syncer.stop()));
+/// election.start();
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Web_Locks_API)
+#[wasm_bindgen]
+pub struct LeaderElection {
+    inner: Rc<RefCell<ElectionInner>>,
+}
+
+#[wasm_bindgen]
+impl LeaderElection {
+    #[wasm_bindgen(constructor)]
+    pub fn new(name: String) -> LeaderElection {
+        LeaderElection {
+            inner: Rc::new(RefCell::new(ElectionInner {
+                name: format!("{}:leader:{}", PREFIX, name),
+                tab_id: tab_id(),
+                running: false,
+                is_leader: false,
+                listeners: Vec::new(),
+                next_listener_id: 0,
+                release: None,
+                abort: None,
+                heartbeat: None,
+                subscriptions: Vec::new(),
+            })),
+        }
+    }
+
+    /// Enters the election. Calling it while running is a no-op.
+    pub fn start(&self) -> Result<(), ClientError> {
+        if self.inner.borrow().running {
+            return Ok(());
+        }
+
+        let navigator = GlobalScope::current()?.navigator();
+        self.inner.borrow_mut().running = true;
+
+        Ok(match get(navigator.as_js(), "locks") {
+            Some(_) => request_lock(&self.inner),
+            None => start_lease(&self.inner),
+        }?)
+    }
+
+    /// Leaves the election, stepping down if this tab leads.
+    pub fn stop(&self) {
+        let (release, abort, heartbeat, subscriptions, lease_holder) = {
+            let mut inner = self.inner.borrow_mut();
+            inner.running = false;
+            let lease_holder = inner.heartbeat.is_some() && inner.is_leader;
+
+            (
+                inner.release.take(),
+                inner.abort.take(),
+                inner.heartbeat.take(),
+                std::mem::take(&mut inner.subscriptions),
+                lease_holder,
+            )
+        };
+
+        if let Some(release) = release {
+            let _ = release.call0(&JsValue::NULL);
+        }
+        if let Some(abort) = abort {
+            abort.abort();
+        }
+        if let Some((handle, _)) = heartbeat {
+            if let Ok(window) = window() {
+                window.clear_interval_with_handle(handle);
+            }
+        }
+        drop(subscriptions);
+
+        if lease_holder {
+            resign_lease(&self.inner);
+        }
+        set_leader(&self.inner, false);
+    }
+
+    /// Whether this tab currently leads.
+    #[wasm_bindgen(js_name = "isLeader")]
+    pub fn is_leader(&self) -> bool {
+        self.inner.borrow().is_leader
+    }
+
+    /// Calls `callback(isLeader)` whenever this tab gains or loses
+    /// leadership.
+    #[wasm_bindgen(js_name = "onChange")]
+    pub fn on_change_js(&self, callback: Function) -> Subscription {
+        self.on_leader_change(move |is_leader| {
+            if let Err(e) = callback.call1(&JsValue::NULL, &JsValue::from(is_leader)) {
+                log::error!("Error in leadership callback: {:?}", e);
+            }
+        })
+    }
+}
+
+impl LeaderElection {
+    /// Calls `callback(is_leader)` whenever this tab gains or loses
+    /// leadership, until the returned subscription is dropped.
+    pub fn on_leader_change(&self, callback: impl FnMut(bool) + 'static) -> Subscription {
+        let mut inner = self.inner.borrow_mut();
+        let id = inner.next_listener_id;
+        inner.next_listener_id += 1;
+        inner.listeners.push((id, Rc::new(RefCell::new(callback))));
+
+        let weak = Rc::downgrade(&self.inner);
+        Subscription::new(move || {
+            if let Some(inner) = weak.upgrade() {
+                inner.borrow_mut().listeners.retain(|(listener_id, _)| *listener_id != id);
+            }
+        })
+    }
+}
+
+impl Drop for LeaderElection {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn set_leader(inner: &Rc<RefCell<ElectionInner>>, is_leader: bool) {
+    let listeners = {
+        let mut inner = inner.borrow_mut();
+        if inner.is_leader == is_leader {
+            return;
+        }
+        inner.is_leader = is_leader;
+        inner.listeners.iter().map(|(_, listener)| listener.clone()).collect::<Vec<_>>()
+    };
+
+    // A listener that calls `stop()` re-enters here while it's still running;
+    // it already knows it stepped down, so it's skipped.
+    for listener in listeners {
+        if let Ok(mut listener) = listener.try_borrow_mut() {
+            (listener)(is_leader);
+        }
+    }
+}
+
+fn request_lock(inner: &Rc<RefCell<ElectionInner>>) -> Result<(), JsValue> {
+    let controller = AbortController::new()?;
+    let options = LockOptions {
+        signal: Some(controller.signal()),
+        ..LockOptions::default()
+    };
+
+    let weak = Rc::downgrade(inner);
+    let name = inner.borrow().name.clone();
+    spawn_local(async move {
+        // Fails with `Aborted` when `stop()` cancels a queued request
+        let result = with_lock(&name, options, {
+            let weak = weak.clone();
+            move |_| async move {
+                let held = match weak.upgrade() {
+                    Some(inner) if inner.borrow().running => Promise::new(&mut |resolve, _| {
+                        let mut inner = inner.borrow_mut();
+                        inner.abort = None;
+                        inner.release = Some(resolve);
+                    }),
+                    // Stopped while queued: release right away
+                    _ => return,
+                };
+
+                if let Some(inner) = weak.upgrade() {
+                    set_leader(&inner, true);
+                }
+                let _ = JsFuture::from(held).await;
+            }
+        })
+        .await;
+
+        if let Some(inner) = weak.upgrade() {
+            match result {
+                Ok(()) | Err(LockError::Aborted) => {}
+                Err(LockError::Stolen) => {
+                    // Settle the callback that still waits on the stolen lock
+                    let release = inner.borrow_mut().release.take();
+                    if let Some(release) = release {
+                        let _ = release.call0(&JsValue::NULL);
+                    }
+                    set_leader(&inner, false);
+                }
+                Err(e) => {
+                    // Leave the election so a later `start()` can try again
+                    log::error!("Error requesting leader lock: {:?}", e);
+                    {
+                        let mut inner = inner.borrow_mut();
+                        inner.running = false;
+                        inner.abort = None;
+                        inner.release = None;
+                    }
+                    set_leader(&inner, false);
+                }
+            }
+        }
+    });
+
+    inner.borrow_mut().abort = Some(controller);
+    Ok(())
+}
+
+fn resign_lease(inner: &Rc<RefCell<ElectionInner>>) {
+    if inner.borrow().is_leader {
+        let name = inner.borrow().name.clone();
+        if let Some(storage) = local_storage() {
+            let _ = storage.remove_item(&name);
+        }
+    }
+
+    set_leader(inner, false);
+}
+
+fn local_storage() -> Option<Storage> {
+    window().ok()?.local_storage().ok().flatten()
+}
+
+fn start_lease(inner: &Rc<RefCell<ElectionInner>>) -> Result<(), JsValue> {
+    let window = window()?;
+    if local_storage().is_none() {
+        return Err(ClientError::Unsupported("Web Locks API and localStorage").into());
+    }
+
+    let weak = Rc::downgrade(inner);
+    let tick = Closure::<dyn FnMut()>::new(move || check_lease(&weak));
+    let handle = window.set_interval_with_callback_and_timeout_and_arguments_0(tick.as_ref().unchecked_ref(), HEARTBEAT_MS)?;
+
+    // React right away when another tab takes or drops the lease
+    let weak = Rc::downgrade(inner);
+    let name = inner.borrow().name.clone();
+    let on_storage = Subscription::listen(&window, "storage", move |event| {
+        if get(&event, "key").and_then(|key| key.as_string()).as_deref() == Some(name.as_str()) {
+            check_lease(&weak);
+        }
+    })?;
+
+    // Hand over quickly when the leading tab closes. If it's only put in
+    // the back/forward cache, the heartbeat picks up again on restore
+    let weak = Rc::downgrade(inner);
+    let on_page_hide = Subscription::listen(&window, "pagehide", move |_| {
+        if let Some(inner) = weak.upgrade() {
+            resign_lease(&inner);
+        }
+    })?;
+
+    {
+        let mut inner = inner.borrow_mut();
+        inner.heartbeat = Some((handle, tick));
+        inner.subscriptions = vec![on_storage, on_page_hide];
+    }
+
+    check_lease(&Rc::downgrade(inner));
+    Ok(())
+}
+
+fn check_lease(weak: &Weak<RefCell<ElectionInner>>) {
+    let (inner, storage) = match (weak.upgrade(), local_storage()) {
+        (Some(inner), Some(storage)) => (inner, storage),
+        _ => return,
+    };
+    if !inner.borrow().running {
+        return;
+    }
+
+    let (name, tab_id) = {
+        let inner = inner.borrow();
+        (inner.name.clone(), inner.tab_id.clone())
+    };
+
+    let lease = storage.get_item(&name).ok().flatten().and_then(|lease| {
+        let lease = js_sys::JSON::parse(&lease).ok()?;
+        serde_wasm_bindgen::from_value::<Lease>(lease).ok()
+    });
+    let now = Date::now();
+
+    let action = decide(lease.as_ref(), &tab_id, now);
+    if action != LeaseAction::Follow {
+        let lease = Lease {
+            id: tab_id,
+            expires: now + LEASE_MS,
+        };
+        let written = serde_wasm_bindgen::to_value(&lease)
+            .map_err(JsValue::from)
+            .and_then(|lease| js_sys::JSON::stringify(&lease))
+            .and_then(|lease| storage.set_item(&name, &String::from(lease)));
+
+        if let Err(e) = written {
+            log::error!("Error writing leader lease: {:?}", e);
+        }
+    }
+
+    set_leader(&inner, action == LeaseAction::Renew);
+}
+
+#[cfg(test)]
+mod tabs_tests {
+    use super::*;
+    use std::cell::Cell;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn lease(id: &str, expires: f64) -> Lease {
+        Lease {
+            id: id.to_string(),
+            expires,
+        }
+    }
+
+    #[test]
+    fn decide_test() {
+        assert_eq!(decide(None, "a", 1_000.0), LeaseAction::Claim);
+        assert_eq!(decide(Some(&lease("a", 500.0)), "a", 1_000.0), LeaseAction::Renew);
+        assert_eq!(decide(Some(&lease("b", 2_000.0)), "a", 1_000.0), LeaseAction::Follow);
+        assert_eq!(decide(Some(&lease("b", 500.0)), "a", 1_000.0), LeaseAction::Claim);
+    }
+
+    #[wasm_bindgen_test]
+    async fn request_test() {
+        let leader = TabBus::new("test".to_string()).unwrap();
+        let follower = TabBus::new("test".to_string()).unwrap();
+
+        let _handler = leader.handle_typed("double", |value: u32| Ok(value * 2));
+        let doubled: u32 = follower.request_typed("double", &21, 1_000).await.unwrap();
+        assert_eq!(doubled, 42);
+    }
+
+    #[wasm_bindgen_test]
+    async fn request_timeout_test() {
+        let bus = TabBus::new("timeout".to_string()).unwrap();
+
+        let error = JsFuture::from(bus.request_js("nobody", JsValue::NULL, 10)).await.unwrap_err();
+        assert_eq!(String::from(error.unchecked_into::<js_sys::Error>().name()), "TimeoutError");
+    }
+
+    #[wasm_bindgen_test]
+    async fn election_test() {
+        let election = LeaderElection::new("test".to_string());
+        let changes = Rc::new(Cell::new(0));
+        let counter = changes.clone();
+        let _subscription = election.on_change_js(
+            Closure::<dyn FnMut(JsValue)>::new(move |_| counter.set(counter.get() + 1))
+                .into_js_value()
+                .unchecked_into(),
+        );
+
+        election.start().unwrap();
+        JsFuture::from(js_sys::Promise::new(&mut |resolve, _| {
+            window().unwrap().set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, 100).unwrap();
+        }))
+        .await
+        .unwrap();
+        assert!(election.is_leader());
+
+        election.stop();
+        assert!(!election.is_leader());
+        assert_eq!(changes.get(), 2);
+    }
+}
@@ -0,0 +1,193 @@
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::client::{capabilities::has, error::ClientError, scope::window};
+
+/// Longest duration, in milliseconds, browsers accept for a single
+/// vibration or pause. Longer values are clamped.
+pub const MAX_DURATION: u32 = 10_000;
+
+/// Named haptic feedback patterns, so the whole app feels consistent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    /// A short double tap
+    Success,
+    /// Two longer pulses
+    Warning,
+    /// Three strong pulses, e.g. for a failed form submission
+    Error,
+    /// A barely noticeable tick, for pickers and toggles
+    Selection,
+}
+
+impl Preset {
+    pub fn from_name(name: &str) -> Option<Preset> {
+        match name {
+            "success" => Some(Preset::Success),
+            "warning" => Some(Preset::Warning),
+            "error" => Some(Preset::Error),
+            "selection" => Some(Preset::Selection),
+            _ => None,
+        }
+    }
+
+    /// Alternating vibration and pause durations, in milliseconds.
+    pub fn pattern(&self) -> &'static [u32] {
+        match self {
+            Preset::Success => &[20, 60, 20],
+            Preset::Warning => &[60, 100, 60],
+            Preset::Error => &[80, 60, 80, 60, 80],
+            Preset::Selection => &[10],
+        }
+    }
+}
+
+/// A single duration or an alternating vibrate/pause sequence, as accepted
+/// by `navigator.vibrate`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Pattern {
+    Duration(u32),
+    Sequence(Vec<u32>),
+}
+
+impl Pattern {
+    /// The pattern as a sequence, with every duration clamped to
+    /// [`MAX_DURATION`].
+    pub fn normalize(&self) -> Vec<u32> {
+        let durations = match self {
+            Pattern::Duration(duration) => std::slice::from_ref(duration),
+            Pattern::Sequence(durations) => durations.as_slice(),
+        };
+
+        durations.iter().map(|duration| (*duration).min(MAX_DURATION)).collect()
+    }
+}
+
+fn navigator() -> Result<web_sys::Navigator, ClientError> {
+    let navigator = window()?.navigator();
+
+    if !has(&navigator, "vibrate") {
+        return Err(ClientError::Unsupported("Vibration API"));
+    }
+
+    Ok(navigator)
+}
+
+fn vibrate_pattern(pattern: &[u32]) -> Result<bool, ClientError> {
+    let pattern = pattern.iter().map(|duration| JsValue::from(*duration)).collect::<js_sys::Array>();
+
+    Ok(navigator()?.vibrate_with_pattern(&pattern))
+}
+
+/// Vibrates the device, returning `false` when the browser refused.
+pub fn vibrate(pattern: &Pattern) -> Result<bool, ClientError> {
+    vibrate_pattern(&pattern.normalize())
+}
+
+/// Plays one of the built-in haptic presets.
+pub fn vibrate_preset(preset: Preset) -> Result<bool, ClientError> {
+    vibrate_pattern(preset.pattern())
+}
+
+/// Stops any ongoing vibration.
+pub fn cancel_vibration() -> Result<(), ClientError> {
+    navigator()?.vibrate_with_duration(0);
+
+    Ok(())
+}
+
+/// Vibrates the device. Takes a duration in milliseconds or an array of
+/// alternating vibration and pause durations.
+///
+/// Resolves to `false` when the browser refused, usually because the user
+/// hasn't interacted with the page yet. Throws a `NotSupportedError` on
+/// devices without vibration support (e.g. iOS), instead of silently doing
+/// nothing.
+///
+/// ## Example
+///
+/// ```tsx
+/// await vibrate([100, 50, 100]);
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Navigator/vibrate)
+#[wasm_bindgen(js_name = "vibrate")]
+pub async fn vibrate_js(pattern: JsValue) -> Result<bool, JsValue> {
+    let pattern: Pattern = serde_wasm_bindgen::from_value(pattern)?;
+
+    Ok(vibrate(&pattern)?)
+}
+
+/// Plays one of the built-in haptic presets: `"success"`, `"warning"`,
+/// `"error"` or `"selection"`.
+///
+/// ## Example
+///
+/// ```tsx
+/// if (!form.checkValidity()) {
+///   await vibratePreset("error").catch(() => {});
+/// }
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Navigator/vibrate)
+#[wasm_bindgen(js_name = "vibratePreset")]
+pub async fn vibrate_preset_js(preset: String) -> Result<bool, JsValue> {
+    let preset = Preset::from_name(&preset)
+        .ok_or_else(|| JsValue::from(js_sys::TypeError::new(&format!("Unknown vibration preset: {}", preset))))?;
+
+    Ok(vibrate_preset(preset)?)
+}
+
+/// Stops any ongoing vibration.
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Navigator/vibrate#return_value)
+#[wasm_bindgen(js_name = "cancelVibration")]
+pub async fn cancel_vibration_js() -> Result<(), JsValue> {
+    Ok(cancel_vibration()?)
+}
+
+#[cfg(test)]
+mod vibrate_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn preset_test() {
+        assert_eq!(Preset::from_name("success"), Some(Preset::Success));
+        assert_eq!(Preset::from_name("Success"), None);
+        assert_eq!(Preset::Selection.pattern(), &[10]);
+
+        // Patterns alternate vibration and pause, so they always end on a vibration
+        for preset in [Preset::Success, Preset::Warning, Preset::Error, Preset::Selection] {
+            assert_eq!(preset.pattern().len() % 2, 1);
+        }
+    }
+
+    #[test]
+    fn normalize_test() {
+        assert_eq!(Pattern::Duration(200).normalize(), vec![200]);
+        assert_eq!(Pattern::Sequence(vec![100, 30_000, 50]).normalize(), vec![100, MAX_DURATION, 50]);
+        assert!(Pattern::Sequence(Vec::new()).normalize().is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    async fn vibrate_test() {
+        let result = vibrate(&Pattern::Duration(50));
+
+        // Headless browsers either don't support vibration or refuse it
+        // without user activation, but neither should panic
+        match result {
+            Ok(vibrated) => assert!(!vibrated),
+            Err(e) => assert_eq!(e.name(), "NotSupportedError"),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    async fn unknown_preset_test() {
+        assert!(vibrate_preset_js("rumble".to_string()).await.is_err());
+    }
+}
@@ -1,18 +1,19 @@
 use wasm_bindgen::prelude::*;
 use web_sys::VisibilityState;
 
+use crate::client::{error::ClientError, scope::document};
+
+/// Whether the document is `Visible` or `Hidden`, e.g. in a background tab.
+pub fn visibility_state() -> Result<VisibilityState, ClientError> {
+    Ok(document()?.visibility_state())
+}
+
 /// Returns the current visibility state of the document
 /// 
 /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/Document/visibilityState)
 #[wasm_bindgen(js_name = "getVisibilityState")]
-pub fn get_visibility_state() -> Result<VisibilityState, JsValue> {
-    let window = web_sys::window()
-        .expect("`window` doesn't exists. Make sure you are calling this function in the browser");
-    let document = window
-        .document()
-        .expect("type `Document` doesn't exist in the current scope!");
-
-    Ok(document.visibility_state())
+pub fn get_visibility_state_js() -> Result<VisibilityState, JsValue> {
+    Ok(visibility_state()?)
 }
 
 #[cfg(test)]
@@ -24,6 +25,6 @@ mod tests {
 
     #[wasm_bindgen_test]
     fn document_visibility_test() {
-        assert_eq!(get_visibility_state().unwrap(), VisibilityState::Visible);
+        assert_eq!(get_visibility_state_js().unwrap(), VisibilityState::Visible);
     }
 }
@@ -0,0 +1,176 @@
+use js_sys::Function;
+use serde::Serialize;
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::EventTarget;
+
+use crate::client::{capabilities::get, scope::window, subscription::Subscription};
+
+/// The title bar area an installed app can draw into, in CSS pixels. The
+/// same values are exposed to CSS as `env(titlebar-area-x)` and friends.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct TitlebarRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl TitlebarRect {
+    fn from_js(rect: &JsValue) -> Option<TitlebarRect> {
+        let number = |key| get(rect, key).and_then(|value| value.as_f64());
+
+        Some(TitlebarRect {
+            x: number("x")?,
+            y: number("y")?,
+            width: number("width")?,
+            height: number("height")?,
+        })
+    }
+}
+
+/// What `onTitlebarGeometryChange` reports.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct TitlebarGeometry {
+    pub visible: bool,
+    pub rect: TitlebarRect,
+}
+
+/// `navigator.windowControlsOverlay`, in installed desktop apps on
+/// browsers that support it.
+fn overlay() -> Option<JsValue> {
+    get(&window().ok()?.navigator(), "windowControlsOverlay")
+}
+
+fn titlebar_area_rect(overlay: &JsValue) -> Option<TitlebarRect> {
+    let rect = get(overlay, "getTitlebarAreaRect")?
+        .dyn_into::<Function>()
+        .ok()?
+        .call0(overlay)
+        .ok()?;
+    TitlebarRect::from_js(&rect)
+}
+
+/// Whether the app draws into the title bar, i.e. it's installed with
+/// `"display_override": ["window-controls-overlay"]` and the user didn't
+/// collapse the overlay. `false` in browsers without the API.
+///
+/// ## Example
+///
+/// ```tsx
+/// <header className={isWindowControlsOverlayVisible() ? "titlebar" : "header"} />
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/WindowControlsOverlay/visible)
+#[wasm_bindgen(js_name = "isWindowControlsOverlayVisible")]
+pub fn is_window_controls_overlay_visible() -> bool {
+    overlay()
+        .and_then(|overlay| get(&overlay, "visible"))
+        .and_then(|visible| visible.as_bool())
+        .unwrap_or(false)
+}
+
+/// The title bar area next to the window controls, or `None` in browsers
+/// without the API.
+pub fn get_titlebar_area_rect() -> Option<TitlebarRect> {
+    titlebar_area_rect(&overlay()?)
+}
+
+/// The title bar area left of (or right of, on macOS) the window controls,
+/// as `{ x, y, width, height }`. All zeros while the overlay isn't visible.
+/// Returns `undefined` in browsers without the API.
+///
+/// ## Example
+///
+/// ```tsx
+/// const rect = getTitlebarAreaRect();
+/// if (rect) searchBar.style.maxWidth = `${rect.width - 200}px`;
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/WindowControlsOverlay/getTitlebarAreaRect)
+#[wasm_bindgen(js_name = "getTitlebarAreaRect")]
+pub fn get_titlebar_area_rect_js() -> Result<JsValue, JsValue> {
+    Ok(serde_wasm_bindgen::to_value(&get_titlebar_area_rect())?)
+}
+
+/// Calls `callback` whenever the overlay is toggled or the window resized.
+/// Does nothing in browsers without the API.
+pub fn on_titlebar_geometry_change(mut callback: impl FnMut(TitlebarGeometry) + 'static) -> Subscription {
+    let overlay = match overlay().and_then(|overlay| overlay.dyn_into::<EventTarget>().ok()) {
+        Some(overlay) => overlay,
+        None => return Subscription::noop(),
+    };
+
+    let listener = Subscription::listen(&overlay, "geometrychange", move |event| {
+        let rect = get(&event, "titlebarAreaRect").and_then(|rect| TitlebarRect::from_js(&rect));
+        let visible = get(&event, "visible").and_then(|visible| visible.as_bool());
+
+        callback(TitlebarGeometry {
+            visible: visible.unwrap_or(false),
+            rect: rect.unwrap_or_default(),
+        });
+    });
+
+    listener.unwrap_or_else(|e| {
+        log::error!("Failed to listen to geometrychange: {:?}", e);
+        Subscription::noop()
+    })
+}
+
+/// Calls `callback` with `{ visible, rect }` whenever the overlay is
+/// toggled or the window resized. Returns a handle whose `unsubscribe()`
+/// stops the updates. Never throws: in browsers without the API the
+/// callback is simply never called.
+///
+/// ## Example
+///
+/// ```tsx
+/// useEffect(() => {
+///   const subscription = onTitlebarGeometryChange(({ visible, rect }) => {
+///     setTitlebar(visible ? rect : null);
+///   });
+///   return () => subscription.unsubscribe();
+/// }, []);
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/WindowControlsOverlay/geometrychange_event)
+#[wasm_bindgen(js_name = "onTitlebarGeometryChange")]
+pub fn on_titlebar_geometry_change_js(callback: Function) -> Subscription {
+    on_titlebar_geometry_change(move |geometry| {
+        let geometry = match serde_wasm_bindgen::to_value(&geometry) {
+            Ok(geometry) => geometry,
+            Err(e) => return log::error!("Failed to serialize the title bar geometry: {:?}", e),
+        };
+        if let Err(e) = callback.call1(&JsValue::NULL, &geometry) {
+            log::error!("Error in geometrychange callback: {:?}", e);
+        }
+    })
+}
+
+#[cfg(test)]
+mod window_controls_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn rect_test() {
+        let rect = js_sys::JSON::parse(r#"{ "x": 80, "y": 0, "width": 1000, "height": 33 }"#).unwrap();
+        assert_eq!(
+            TitlebarRect::from_js(&rect),
+            Some(TitlebarRect {
+                x: 80.0,
+                y: 0.0,
+                width: 1000.0,
+                height: 33.0
+            })
+        );
+        assert_eq!(TitlebarRect::from_js(&JsValue::NULL), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn overlay_test() {
+        // Tests don't run as an installed app
+        assert!(!is_window_controls_overlay_visible());
+    }
+}
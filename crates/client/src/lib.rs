@@ -1,8 +1,15 @@
+#[cfg(any(feature = "console_error_panic_hook", feature = "start"))]
 use wasm_bindgen::prelude::*;
 
 pub mod client;
 pub mod utils;
 
+pub use client::*;
+pub use client::{
+    error::{ClientError, JsErrorName},
+    subscription::Subscription,
+};
+
 #[cfg(feature = "wee_alloc")]
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
@@ -18,14 +25,18 @@ pub fn init_console_panic() {
     utils::set_panic_hook();
 }
 
+#[cfg(feature = "start")]
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_namespace = console)]
     fn debug(s: &str);
 }
 
+#[cfg(feature = "start")]
 #[wasm_bindgen(start)]
 fn run() {
+    #[cfg(feature = "console_error_panic_hook")]
     init_console_panic();
+    client::launch::init();
     debug("@remix-pwa/client WASM module loaded successfully");
 }
\ No newline at end of file
@@ -3,6 +3,7 @@ use wasm_bindgen_test::*;
 wasm_bindgen_test_configure!(run_in_browser);
 
 #[wasm_bindgen_test]
+#[allow(clippy::eq_op)]
 fn pass() {
     assert_eq!(1, 1);
 }
\ No newline at end of file
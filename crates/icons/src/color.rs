@@ -0,0 +1,48 @@
+use image::Rgba;
+
+use crate::error::Error;
+
+/// Parses a `#rrggbb` or `#rrggbbaa` hex color.
+pub fn parse_hex_color(color: &str) -> Result<Rgba<u8>, Error> {
+    let invalid = || Error::InvalidColor(color.to_string());
+    let hex = color.strip_prefix('#').ok_or_else(invalid)?;
+
+    if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+        return Err(invalid());
+    }
+
+    let mut channels = [255u8; 4];
+    for (i, channel) in channels.iter_mut().enumerate().take(hex.len() / 2) {
+        *channel = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+    }
+
+    Ok(Rgba(channels))
+}
+
+#[cfg(test)]
+mod color_tests {
+    use super::*;
+
+    #[test]
+    fn parse_hex_color_test() {
+        assert_eq!(
+            parse_hex_color("#ffffff").unwrap(),
+            Rgba([255, 255, 255, 255])
+        );
+        assert_eq!(
+            parse_hex_color("#1a2B3c80").unwrap(),
+            Rgba([26, 43, 60, 128])
+        );
+    }
+
+    #[test]
+    fn invalid_hex_color_test() {
+        for color in ["ffffff", "#fff", "#gggggg", "#ffffffff00", "#ffé000"] {
+            assert!(
+                parse_hex_color(color).is_err(),
+                "{} should be invalid",
+                color
+            );
+        }
+    }
+}
@@ -0,0 +1,109 @@
+/// An iOS device that needs its own `apple-touch-startup-image`.
+///
+/// Safari only shows a splash screen when one matches the device's CSS
+/// viewport and pixel ratio exactly, so every resolution gets its own image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Device {
+    pub name: &'static str,
+    /// Viewport width in CSS pixels, portrait.
+    pub width: u32,
+    /// Viewport height in CSS pixels, portrait.
+    pub height: u32,
+    pub pixel_ratio: u32,
+}
+
+impl Device {
+    /// Splash screen dimensions in device pixels, portrait.
+    pub fn portrait(&self) -> (u32, u32) {
+        (
+            self.width * self.pixel_ratio,
+            self.height * self.pixel_ratio,
+        )
+    }
+
+    /// Splash screen dimensions in device pixels, landscape.
+    pub fn landscape(&self) -> (u32, u32) {
+        let (width, height) = self.portrait();
+        (height, width)
+    }
+
+    /// The media query Safari matches the splash screen against.
+    pub fn media_query(&self, landscape: bool) -> String {
+        format!(
+            "(device-width: {}px) and (device-height: {}px) and (-webkit-device-pixel-ratio: {}) and (orientation: {})",
+            self.width,
+            self.height,
+            self.pixel_ratio,
+            if landscape { "landscape" } else { "portrait" }
+        )
+    }
+}
+
+const fn device(name: &'static str, width: u32, height: u32, pixel_ratio: u32) -> Device {
+    Device {
+        name,
+        width,
+        height,
+        pixel_ratio,
+    }
+}
+
+/// Every distinct iPhone and iPad resolution.
+pub const IOS_DEVICES: &[Device] = &[
+    device("iPhone SE", 375, 667, 2),
+    device("iPhone 8 Plus", 414, 736, 3),
+    device("iPhone 13 mini", 375, 812, 3),
+    device("iPhone 11", 414, 896, 2),
+    device("iPhone 11 Pro Max", 414, 896, 3),
+    device("iPhone 14", 390, 844, 3),
+    device("iPhone 14 Plus", 428, 926, 3),
+    device("iPhone 15", 393, 852, 3),
+    device("iPhone 15 Pro Max", 430, 932, 3),
+    device("iPhone 16 Pro", 402, 874, 3),
+    device("iPhone 16 Pro Max", 440, 956, 3),
+    device("iPad 9.7\"", 768, 1024, 2),
+    device("iPad mini", 744, 1133, 2),
+    device("iPad 10.2\"", 810, 1080, 2),
+    device("iPad Air 10.5\"", 834, 1112, 2),
+    device("iPad Air 10.9\"", 820, 1180, 2),
+    device("iPad Pro 11\"", 834, 1194, 2),
+    device("iPad Pro 12.9\"", 1024, 1366, 2),
+];
+
+#[cfg(test)]
+mod devices_tests {
+    use super::*;
+
+    #[test]
+    fn dimensions_test() {
+        let iphone = device("iPhone 14", 390, 844, 3);
+
+        assert_eq!(iphone.portrait(), (1170, 2532));
+        assert_eq!(iphone.landscape(), (2532, 1170));
+    }
+
+    #[test]
+    fn media_query_test() {
+        let iphone = device("iPhone SE", 375, 667, 2);
+
+        assert_eq!(
+            iphone.media_query(false),
+            "(device-width: 375px) and (device-height: 667px) and (-webkit-device-pixel-ratio: 2) and (orientation: portrait)"
+        );
+    }
+
+    #[test]
+    fn unique_resolutions_test() {
+        for (i, a) in IOS_DEVICES.iter().enumerate() {
+            for b in &IOS_DEVICES[i + 1..] {
+                assert_ne!(
+                    a.portrait(),
+                    b.portrait(),
+                    "{} and {} collide",
+                    a.name,
+                    b.name
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::{fmt, io};
+
+/// Errors that can occur whilst generating icons.
+#[derive(Debug)]
+pub enum Error {
+    /// A color couldn't be parsed. Colors must be `#rrggbb` or `#rrggbbaa`.
+    InvalidColor(String),
+    /// A raster source couldn't be decoded, or an asset couldn't be encoded.
+    Image(image::ImageError),
+    /// Reading the source or writing the assets failed.
+    Io(io::Error),
+    /// An SVG source couldn't be parsed.
+    Svg(resvg::usvg::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidColor(color) => {
+                write!(
+                    f,
+                    "invalid color `{}`, expected `#rrggbb` or `#rrggbbaa`",
+                    color
+                )
+            }
+            Error::Image(e) => write!(f, "{}", e),
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Svg(e) => write!(f, "invalid svg: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidColor(_) => None,
+            Error::Image(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Svg(e) => Some(e),
+        }
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(e: image::ImageError) -> Self {
+        Error::Image(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<resvg::usvg::Error> for Error {
+    fn from(e: resvg::usvg::Error) -> Self {
+        Error::Svg(e)
+    }
+}
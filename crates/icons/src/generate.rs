@@ -0,0 +1,329 @@
+use std::io::Cursor;
+
+use image::{
+    codecs::ico::{IcoEncoder, IcoFrame},
+    imageops, ExtendedColorType, ImageFormat, Rgba, RgbaImage,
+};
+
+use crate::{
+    devices::{Device, IOS_DEVICES},
+    error::Error,
+    source::Source,
+};
+
+/// Maskable icons may be cropped to any shape that contains a centered
+/// circle with a diameter of 80% of the icon, so content is kept inside it.
+///
+/// [Spec](https://www.w3.org/TR/appmanifest/#icon-masks)
+pub const MASKABLE_SAFE_ZONE: f32 = 0.8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssetKind {
+    /// A regular manifest icon, `purpose: "any"`.
+    Icon,
+    /// A manifest icon with `purpose: "maskable"`.
+    Maskable,
+    AppleTouchIcon,
+    Favicon,
+    SplashScreen,
+}
+
+/// A rendered and encoded asset, ready to be written to disk.
+#[derive(Clone, Debug)]
+pub struct Asset {
+    pub file_name: String,
+    pub kind: AssetKind,
+    /// The `sizes` attribute value, e.g. `"192x192"`.
+    pub sizes: String,
+    /// The media query a splash screen applies to.
+    pub media: Option<String>,
+    pub data: Vec<u8>,
+}
+
+impl Asset {
+    pub fn mime_type(&self) -> &'static str {
+        if self.file_name.ends_with(".ico") {
+            "image/x-icon"
+        } else {
+            "image/png"
+        }
+    }
+}
+
+/// What to generate and how it should look.
+///
+/// `padding` is the fraction of each side left empty around the source, e.g.
+/// `0.1` leaves a 10% margin. Maskable icons apply it within the safe zone.
+#[derive(Clone, Debug)]
+pub struct IconOptions {
+    pub background: Rgba<u8>,
+    pub padding: f32,
+    pub icon_sizes: Vec<u32>,
+    pub maskable_sizes: Vec<u32>,
+    pub favicon_sizes: Vec<u32>,
+    pub apple_touch_icon_size: u32,
+    pub devices: Vec<Device>,
+    /// Size of the icon on splash screens, relative to the shortest side.
+    pub splash_icon_ratio: f32,
+}
+
+impl Default for IconOptions {
+    fn default() -> Self {
+        IconOptions {
+            background: Rgba([255, 255, 255, 255]),
+            padding: 0.0,
+            icon_sizes: vec![72, 96, 128, 144, 152, 192, 384, 512],
+            maskable_sizes: vec![192, 512],
+            favicon_sizes: vec![16, 32, 48],
+            apple_touch_icon_size: 180,
+            devices: IOS_DEVICES.to_vec(),
+            splash_icon_ratio: 0.25,
+        }
+    }
+}
+
+/// Renders every asset described by `options` from `source`.
+pub fn generate(source: &Source, options: &IconOptions) -> Result<Vec<Asset>, Error> {
+    let mut assets = Vec::new();
+    let padded = |size: u32, scale: f32| {
+        (size as f32 * scale * (1.0 - 2.0 * options.padding.clamp(0.0, 0.5))).round() as u32
+    };
+
+    for &size in &options.icon_sizes {
+        let image = compose(source, size, size, padded(size, 1.0), None);
+        assets.push(png_asset(
+            format!("icon-{0}x{0}.png", size),
+            AssetKind::Icon,
+            &image,
+        )?);
+    }
+
+    for &size in &options.maskable_sizes {
+        let content = padded(size, MASKABLE_SAFE_ZONE);
+        let image = compose(source, size, size, content, Some(options.background));
+        let file_name = format!("maskable-icon-{0}x{0}.png", size);
+        assets.push(png_asset(file_name, AssetKind::Maskable, &image)?);
+    }
+
+    if options.apple_touch_icon_size > 0 {
+        // iOS fills transparent pixels with black, so the icon is flattened
+        let size = options.apple_touch_icon_size;
+        let image = compose(
+            source,
+            size,
+            size,
+            padded(size, 1.0),
+            Some(options.background),
+        );
+        assets.push(png_asset(
+            "apple-touch-icon.png".to_string(),
+            AssetKind::AppleTouchIcon,
+            &image,
+        )?);
+    }
+
+    if !options.favicon_sizes.is_empty() {
+        let favicons = options
+            .favicon_sizes
+            .iter()
+            .map(|&size| {
+                png_asset(
+                    format!("favicon-{0}x{0}.png", size),
+                    AssetKind::Favicon,
+                    &source.render(size),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assets.push(ico_asset(&options.favicon_sizes, &favicons)?);
+        assets.extend(favicons);
+    }
+
+    for device in &options.devices {
+        for landscape in [false, true] {
+            let (width, height) = if landscape {
+                device.landscape()
+            } else {
+                device.portrait()
+            };
+            let content = (width.min(height) as f32 * options.splash_icon_ratio).round() as u32;
+            let image = compose(source, width, height, content, Some(options.background));
+
+            let mut asset = png_asset(
+                format!("apple-splash-{}x{}.png", width, height),
+                AssetKind::SplashScreen,
+                &image,
+            )?;
+            asset.media = Some(device.media_query(landscape));
+            assets.push(asset);
+        }
+    }
+
+    Ok(assets)
+}
+
+/// Renders `source` at `content` pixels and centers it on a `width`x`height`
+/// canvas, optionally filled with `background`.
+fn compose(
+    source: &Source,
+    width: u32,
+    height: u32,
+    content: u32,
+    background: Option<Rgba<u8>>,
+) -> RgbaImage {
+    let mut canvas = RgbaImage::from_pixel(width, height, background.unwrap_or(Rgba([0, 0, 0, 0])));
+    let content = content.min(width).min(height);
+    let rendered = source.render(content);
+
+    let x = (width - content) / 2;
+    let y = (height - content) / 2;
+    imageops::overlay(&mut canvas, &rendered, x as i64, y as i64);
+
+    canvas
+}
+
+fn png_asset(file_name: String, kind: AssetKind, image: &RgbaImage) -> Result<Asset, Error> {
+    let mut data = Vec::new();
+    image.write_to(&mut Cursor::new(&mut data), ImageFormat::Png)?;
+
+    Ok(Asset {
+        file_name,
+        kind,
+        sizes: format!("{}x{}", image.width(), image.height()),
+        media: None,
+        data,
+    })
+}
+
+/// Bundles the PNG favicons into a single multi-resolution `favicon.ico`.
+fn ico_asset(sizes: &[u32], favicons: &[Asset]) -> Result<Asset, Error> {
+    let frames = sizes
+        .iter()
+        .zip(favicons)
+        .map(|(&size, favicon)| {
+            IcoFrame::with_encoded(
+                favicon.data.as_slice(),
+                size,
+                size,
+                ExtendedColorType::Rgba8,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut data = Vec::new();
+    IcoEncoder::new(&mut data).encode_images(&frames)?;
+
+    Ok(Asset {
+        file_name: "favicon.ico".to_string(),
+        kind: AssetKind::Favicon,
+        sizes: favicons
+            .iter()
+            .map(|favicon| favicon.sizes.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+        media: None,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod generate_tests {
+    use super::*;
+
+    fn source() -> Source {
+        Source::Raster(RgbaImage::from_pixel(32, 32, Rgba([255, 0, 0, 255])))
+    }
+
+    fn options() -> IconOptions {
+        IconOptions {
+            background: Rgba([0, 0, 255, 255]),
+            icon_sizes: vec![48],
+            maskable_sizes: vec![100],
+            favicon_sizes: vec![16, 32],
+            devices: vec![IOS_DEVICES[0]],
+            ..IconOptions::default()
+        }
+    }
+
+    fn decode(asset: &Asset) -> RgbaImage {
+        image::load_from_memory(&asset.data).unwrap().into_rgba8()
+    }
+
+    fn find<'a>(assets: &'a [Asset], file_name: &str) -> &'a Asset {
+        assets
+            .iter()
+            .find(|asset| asset.file_name == file_name)
+            .unwrap()
+    }
+
+    #[test]
+    fn generate_file_names_test() {
+        let assets = generate(&source(), &options()).unwrap();
+        let names = assets
+            .iter()
+            .map(|asset| asset.file_name.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            vec![
+                "icon-48x48.png",
+                "maskable-icon-100x100.png",
+                "apple-touch-icon.png",
+                "favicon.ico",
+                "favicon-16x16.png",
+                "favicon-32x32.png",
+                "apple-splash-750x1334.png",
+                "apple-splash-1334x750.png",
+            ]
+        );
+    }
+
+    #[test]
+    fn maskable_safe_zone_test() {
+        let assets = generate(&source(), &options()).unwrap();
+        let maskable = decode(find(&assets, "maskable-icon-100x100.png"));
+
+        assert_eq!(maskable.dimensions(), (100, 100));
+        assert_eq!(maskable.get_pixel(50, 50), &Rgba([255, 0, 0, 255]));
+        assert_eq!(maskable.get_pixel(5, 5), &Rgba([0, 0, 255, 255]));
+        assert_eq!(maskable.get_pixel(15, 50), &Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn padding_test() {
+        let mut options = options();
+        options.padding = 0.25;
+        let assets = generate(&source(), &options).unwrap();
+        let icon = decode(find(&assets, "icon-48x48.png"));
+
+        assert_eq!(icon.get_pixel(8, 24)[3], 0);
+        assert_eq!(icon.get_pixel(24, 24), &Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn favicon_ico_test() {
+        let assets = generate(&source(), &options()).unwrap();
+        let favicon = find(&assets, "favicon.ico");
+
+        assert_eq!(favicon.mime_type(), "image/x-icon");
+        assert_eq!(favicon.sizes, "16x16 32x32");
+        assert_eq!(decode(favicon).dimensions(), (32, 32));
+    }
+
+    #[test]
+    fn splash_screen_test() {
+        let assets = generate(&source(), &options()).unwrap();
+        let splash = find(&assets, "apple-splash-1334x750.png");
+        let image = decode(splash);
+
+        assert_eq!(image.dimensions(), (1334, 750));
+        assert_eq!(image.get_pixel(667, 375), &Rgba([255, 0, 0, 255]));
+        assert_eq!(image.get_pixel(10, 10), &Rgba([0, 0, 255, 255]));
+        assert!(splash
+            .media
+            .as_ref()
+            .unwrap()
+            .ends_with("(orientation: landscape)"));
+    }
+}
@@ -0,0 +1,17 @@
+//! PWA icon and splash screen generation.
+//!
+//! Takes a single PNG or SVG source and renders every asset an installable
+//! PWA needs: manifest icons, maskable icons, Apple touch icons, favicons and
+//! iOS splash screens, alongside the matching manifest `icons` array and
+//! `<link>` tags.
+
+pub mod color;
+pub mod devices;
+pub mod error;
+pub mod generate;
+pub mod output;
+pub mod source;
+
+pub use error::Error;
+pub use generate::{generate, Asset, AssetKind, IconOptions};
+pub use source::Source;
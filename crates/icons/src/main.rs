@@ -0,0 +1,71 @@
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use clap::Parser;
+use icons::{
+    color::parse_hex_color,
+    generate,
+    output::{link_tags, manifest_icons, write_assets},
+    Error, IconOptions, Source,
+};
+
+/// Generates every icon and splash screen a PWA needs from a single image.
+#[derive(Parser)]
+#[command(name = "remix-pwa-icons", version)]
+struct Cli {
+    /// Source PNG or SVG. Should be square and at least 512x512.
+    source: PathBuf,
+    /// Directory the assets are written to.
+    #[arg(long, default_value = "public/icons")]
+    out: PathBuf,
+    /// URL prefix the output directory is served from.
+    #[arg(long, default_value = "/icons/")]
+    prefix: String,
+    /// Background of maskable icons, Apple touch icons and splash screens.
+    #[arg(long, default_value = "#ffffff")]
+    background: String,
+    /// Margin left around the source on each side, as a fraction of the icon.
+    #[arg(long, default_value_t = 0.0)]
+    padding: f32,
+    /// Skip generating iOS splash screens.
+    #[arg(long)]
+    no_splash: bool,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Error> {
+    let mut options = IconOptions {
+        background: parse_hex_color(&cli.background)?,
+        padding: cli.padding,
+        ..IconOptions::default()
+    };
+    if cli.no_splash {
+        options.devices.clear();
+    }
+
+    let assets = generate(&Source::open(&cli.source)?, &options)?;
+    write_assets(&assets, &cli.out)?;
+
+    let icons = serde_json::to_string_pretty(&manifest_icons(&assets, &cli.prefix))
+        .expect("manifest icons are always serializable");
+    fs::write(cli.out.join("manifest-icons.json"), format!("{}\n", icons))?;
+    fs::write(
+        cli.out.join("links.html"),
+        format!("{}\n", link_tags(&assets, &cli.prefix)),
+    )?;
+
+    eprintln!(
+        "Generated {} assets in `{}`",
+        assets.len(),
+        cli.out.display()
+    );
+    Ok(())
+}
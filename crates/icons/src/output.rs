@@ -0,0 +1,146 @@
+use std::{fs, io, path::Path};
+
+use serde::Serialize;
+
+use crate::generate::{Asset, AssetKind};
+
+/// An entry of the web app manifest's `icons` array.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ManifestIcon {
+    pub src: String,
+    pub sizes: String,
+    #[serde(rename = "type")]
+    pub mime_type: String,
+    pub purpose: String,
+}
+
+/// Builds the manifest `icons` array. Only regular and maskable icons belong
+/// in the manifest; everything else is referenced through `<link>` tags.
+pub fn manifest_icons(assets: &[Asset], url_prefix: &str) -> Vec<ManifestIcon> {
+    assets
+        .iter()
+        .filter_map(|asset| {
+            let purpose = match asset.kind {
+                AssetKind::Icon => "any",
+                AssetKind::Maskable => "maskable",
+                _ => return None,
+            };
+
+            Some(ManifestIcon {
+                src: url(url_prefix, asset),
+                sizes: asset.sizes.clone(),
+                mime_type: asset.mime_type().to_string(),
+                purpose: purpose.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Builds the `<link>` tags for favicons, Apple touch icons and splash
+/// screens, one per line.
+pub fn link_tags(assets: &[Asset], url_prefix: &str) -> String {
+    assets
+        .iter()
+        .filter_map(|asset| {
+            let href = url(url_prefix, asset);
+
+            match asset.kind {
+                AssetKind::Icon | AssetKind::Maskable => None,
+                AssetKind::Favicon => Some(format!(
+                    r#"<link rel="icon" type="{}" sizes="{}" href="{}">"#,
+                    asset.mime_type(),
+                    asset.sizes,
+                    href
+                )),
+                AssetKind::AppleTouchIcon => Some(format!(
+                    r#"<link rel="apple-touch-icon" sizes="{}" href="{}">"#,
+                    asset.sizes, href
+                )),
+                AssetKind::SplashScreen => Some(format!(
+                    r#"<link rel="apple-touch-startup-image" media="{}" href="{}">"#,
+                    asset.media.as_deref().unwrap_or_default(),
+                    href
+                )),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Writes every asset into `directory`, creating it if needed.
+pub fn write_assets(assets: &[Asset], directory: &Path) -> io::Result<()> {
+    fs::create_dir_all(directory)?;
+    for asset in assets {
+        fs::write(directory.join(&asset.file_name), &asset.data)?;
+    }
+
+    Ok(())
+}
+
+fn url(url_prefix: &str, asset: &Asset) -> String {
+    format!("{}/{}", url_prefix.trim_end_matches('/'), asset.file_name)
+}
+
+#[cfg(test)]
+mod output_tests {
+    use super::*;
+
+    fn asset(file_name: &str, kind: AssetKind, sizes: &str, media: Option<&str>) -> Asset {
+        Asset {
+            file_name: file_name.to_string(),
+            kind,
+            sizes: sizes.to_string(),
+            media: media.map(str::to_string),
+            data: Vec::new(),
+        }
+    }
+
+    fn assets() -> Vec<Asset> {
+        vec![
+            asset("icon-192x192.png", AssetKind::Icon, "192x192", None),
+            asset(
+                "maskable-icon-512x512.png",
+                AssetKind::Maskable,
+                "512x512",
+                None,
+            ),
+            asset(
+                "apple-touch-icon.png",
+                AssetKind::AppleTouchIcon,
+                "180x180",
+                None,
+            ),
+            asset("favicon.ico", AssetKind::Favicon, "16x16 32x32", None),
+            asset(
+                "apple-splash-750x1334.png",
+                AssetKind::SplashScreen,
+                "750x1334",
+                Some("(orientation: portrait)"),
+            ),
+        ]
+    }
+
+    #[test]
+    fn manifest_icons_test() {
+        let icons = manifest_icons(&assets(), "/icons/");
+
+        assert_eq!(icons.len(), 2);
+        assert_eq!(
+            serde_json::to_string(&icons[1]).unwrap(),
+            r#"{"src":"/icons/maskable-icon-512x512.png","sizes":"512x512","type":"image/png","purpose":"maskable"}"#
+        );
+    }
+
+    #[test]
+    fn link_tags_test() {
+        assert_eq!(
+            link_tags(&assets(), "/icons"),
+            [
+                r#"<link rel="apple-touch-icon" sizes="180x180" href="/icons/apple-touch-icon.png">"#,
+                r#"<link rel="icon" type="image/x-icon" sizes="16x16 32x32" href="/icons/favicon.ico">"#,
+                r#"<link rel="apple-touch-startup-image" media="(orientation: portrait)" href="/icons/apple-splash-750x1334.png">"#,
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::{fs, path::Path};
+
+use image::{imageops, imageops::FilterType, RgbaImage};
+use resvg::{tiny_skia, usvg};
+
+use crate::error::Error;
+
+/// The image every asset is rendered from.
+///
+/// SVG sources are rasterized at each target size rather than once, so they
+/// stay crisp no matter how large the output is.
+pub enum Source {
+    Raster(RgbaImage),
+    Svg(Box<usvg::Tree>),
+}
+
+impl Source {
+    /// Opens a source file, treating `.svg` files as vectors and anything
+    /// else as a raster image.
+    pub fn open(path: &Path) -> Result<Source, Error> {
+        let data = fs::read(path)?;
+        let is_svg = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+
+        if is_svg {
+            Source::from_svg(&data)
+        } else {
+            Source::from_raster(&data)
+        }
+    }
+
+    pub fn from_svg(data: &[u8]) -> Result<Source, Error> {
+        Ok(Source::Svg(Box::new(usvg::Tree::from_data(
+            data,
+            &usvg::Options::default(),
+        )?)))
+    }
+
+    pub fn from_raster(data: &[u8]) -> Result<Source, Error> {
+        Ok(Source::Raster(image::load_from_memory(data)?.into_rgba8()))
+    }
+
+    /// Renders the source so that it fits inside a `size`x`size` square,
+    /// preserving its aspect ratio. The result is centered on a transparent
+    /// canvas.
+    pub fn render(&self, size: u32) -> RgbaImage {
+        let mut canvas = RgbaImage::new(size, size);
+        if size == 0 {
+            return canvas;
+        }
+
+        match self {
+            Source::Raster(image) => {
+                let (width, height) = fit(image.width() as f32, image.height() as f32, size);
+                let resized = imageops::resize(image, width, height, FilterType::Lanczos3);
+                let x = (size - width) / 2;
+                let y = (size - height) / 2;
+                imageops::overlay(&mut canvas, &resized, x as i64, y as i64);
+            }
+            Source::Svg(tree) => {
+                let svg_size = tree.size();
+                let scale = (size as f32 / svg_size.width()).min(size as f32 / svg_size.height());
+                let x = (size as f32 - svg_size.width() * scale) / 2.0;
+                let y = (size as f32 - svg_size.height() * scale) / 2.0;
+
+                let mut pixmap = tiny_skia::Pixmap::new(size, size).expect("size is non-zero");
+                let transform = tiny_skia::Transform::from_scale(scale, scale).post_translate(x, y);
+                resvg::render(tree, transform, &mut pixmap.as_mut());
+
+                for (pixel, rendered) in canvas.pixels_mut().zip(pixmap.pixels()) {
+                    let color = rendered.demultiply();
+                    pixel.0 = [color.red(), color.green(), color.blue(), color.alpha()];
+                }
+            }
+        }
+
+        canvas
+    }
+}
+
+/// Scales `width`x`height` down (or up) so its longest side equals `size`.
+fn fit(width: f32, height: f32, size: u32) -> (u32, u32) {
+    let scale = (size as f32 / width).min(size as f32 / height);
+    let width = ((width * scale).round() as u32).clamp(1, size);
+    let height = ((height * scale).round() as u32).clamp(1, size);
+
+    (width, height)
+}
+
+#[cfg(test)]
+mod source_tests {
+    use image::Rgba;
+
+    use super::*;
+
+    const SVG: &[u8] = br##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10"><rect width="20" height="10" fill="#ff0000"/></svg>"##;
+
+    #[test]
+    fn render_raster_test() {
+        let source = Source::Raster(RgbaImage::from_pixel(40, 20, Rgba([0, 0, 255, 255])));
+        let rendered = source.render(10);
+
+        assert_eq!(rendered.dimensions(), (10, 10));
+        assert_eq!(rendered.get_pixel(5, 5), &Rgba([0, 0, 255, 255]));
+        assert_eq!(rendered.get_pixel(5, 0)[3], 0);
+    }
+
+    #[test]
+    fn render_svg_test() {
+        let source = Source::from_svg(SVG).unwrap();
+        let rendered = source.render(40);
+
+        assert_eq!(rendered.dimensions(), (40, 40));
+        assert_eq!(rendered.get_pixel(20, 20), &Rgba([255, 0, 0, 255]));
+        assert_eq!(rendered.get_pixel(20, 2)[3], 0);
+    }
+
+    #[test]
+    fn invalid_svg_test() {
+        assert!(matches!(Source::from_svg(b"<nope"), Err(Error::Svg(_))));
+    }
+
+    #[test]
+    fn fit_test() {
+        assert_eq!(fit(100.0, 50.0, 10), (10, 5));
+        assert_eq!(fit(1.0, 1000.0, 10), (1, 10));
+    }
+}
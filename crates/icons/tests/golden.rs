@@ -0,0 +1,107 @@
+//! Golden image tests. Renders a fixed source and compares the output with
+//! the images in `tests/golden`. Run with `UPDATE_GOLDEN=1` to regenerate
+//! them after an intentional change, then review the images before committing.
+
+use std::path::PathBuf;
+
+use icons::{devices::IOS_DEVICES, generate, Asset, IconOptions, Source};
+use image::{Rgba, RgbaImage};
+
+/// Largest per-channel difference tolerated, to absorb resampling noise.
+const TOLERANCE: u8 = 2;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(name)
+}
+
+fn generate_from(source: &Source) -> Vec<Asset> {
+    let options = IconOptions {
+        background: Rgba([250, 250, 250, 255]),
+        padding: 0.05,
+        icon_sizes: vec![48],
+        maskable_sizes: vec![96],
+        favicon_sizes: vec![16],
+        apple_touch_icon_size: 60,
+        devices: Vec::new(),
+        ..IconOptions::default()
+    };
+
+    generate(source, &options).unwrap()
+}
+
+fn assert_golden(prefix: &str, assets: &[Asset]) {
+    for asset in assets
+        .iter()
+        .filter(|asset| asset.file_name.ends_with(".png"))
+    {
+        let path = fixture("golden").join(format!("{}-{}", prefix, asset.file_name));
+        let actual = image::load_from_memory(&asset.data).unwrap().into_rgba8();
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            actual.save(&path).unwrap();
+            continue;
+        }
+
+        let expected: RgbaImage = image::open(&path)
+            .unwrap_or_else(|e| panic!("missing golden image `{}`: {}", path.display(), e))
+            .into_rgba8();
+
+        assert_eq!(
+            actual.dimensions(),
+            expected.dimensions(),
+            "{}",
+            path.display()
+        );
+        for (a, e) in actual.pixels().zip(expected.pixels()) {
+            let close =
+                a.0.iter()
+                    .zip(e.0.iter())
+                    .all(|(a, e)| a.abs_diff(*e) <= TOLERANCE);
+            assert!(close, "`{}` differs from its golden image", path.display());
+        }
+    }
+}
+
+#[test]
+fn svg_source_golden_test() {
+    let source = Source::open(&fixture("fixtures").join("logo.svg")).unwrap();
+    assert_golden("svg", &generate_from(&source));
+}
+
+#[test]
+fn png_source_golden_test() {
+    let svg = Source::open(&fixture("fixtures").join("logo.svg")).unwrap();
+    let source = Source::Raster(svg.render(512));
+    assert_golden("png", &generate_from(&source));
+}
+
+#[test]
+fn splash_screen_names_test() {
+    let source = Source::open(&fixture("fixtures").join("logo.svg")).unwrap();
+    let options = IconOptions {
+        icon_sizes: Vec::new(),
+        maskable_sizes: Vec::new(),
+        favicon_sizes: Vec::new(),
+        apple_touch_icon_size: 0,
+        devices: IOS_DEVICES[..2].to_vec(),
+        ..IconOptions::default()
+    };
+
+    let assets = generate(&source, &options).unwrap();
+    let names = assets
+        .iter()
+        .map(|asset| asset.file_name.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        names,
+        vec![
+            "apple-splash-750x1334.png",
+            "apple-splash-1334x750.png",
+            "apple-splash-1242x2208.png",
+            "apple-splash-2208x1242.png",
+        ]
+    );
+}
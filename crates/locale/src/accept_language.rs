@@ -0,0 +1,137 @@
+use crate::negotiate::negotiate;
+
+/// A language range from an `Accept-Language` header with its quality
+/// value, e.g. `fr-CH;q=0.9`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageRange {
+    pub range: String,
+    pub quality: f32,
+}
+
+/// Parses an `Accept-Language` header into its language ranges, most
+/// preferred first. Ranges with equal quality keep their header order.
+///
+/// Ranges with `q=0` ("not acceptable") and malformed entries are dropped
+/// rather than failing the whole header.
+///
+/// [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#name-accept-language)
+pub fn parse_accept_language(header: &str) -> Vec<LanguageRange> {
+    let mut ranges = header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';').map(str::trim);
+            let range = parts.next().filter(|range| is_range(range))?;
+
+            let mut quality = 1.0;
+            for param in parts {
+                let (name, value) = param.split_once('=')?;
+                if name.trim().eq_ignore_ascii_case("q") {
+                    quality = value.trim().parse::<f32>().ok()?;
+                }
+            }
+
+            if !(0.0..=1.0).contains(&quality) || quality == 0.0 {
+                return None;
+            }
+
+            Some(LanguageRange {
+                range: range.to_string(),
+                quality,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // `sort_by` is stable, so ties keep the order they were sent in
+    ranges.sort_by(|a, b| b.quality.total_cmp(&a.quality));
+    ranges
+}
+
+/// Picks the best supported locale for an `Accept-Language` header, using
+/// the same rules as [`negotiate`]. A `*` range resolves to `default`.
+pub fn negotiate_accept_language(header: &str, supported: &[String], default: &str) -> String {
+    let requested = parse_accept_language(header)
+        .into_iter()
+        .map(|range| range.range)
+        .collect::<Vec<_>>();
+
+    negotiate(&requested, supported, default)
+}
+
+fn is_range(range: &str) -> bool {
+    range == "*"
+        || (!range.is_empty()
+            && range.split(['-', '_']).all(|subtag| {
+                (1..=8).contains(&subtag.len()) && subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+            }))
+}
+
+#[cfg(test)]
+mod accept_language_tests {
+    use super::*;
+
+    fn ranges(header: &str) -> Vec<(String, f32)> {
+        parse_accept_language(header)
+            .into_iter()
+            .map(|range| (range.range, range.quality))
+            .collect()
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(
+            ranges("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5"),
+            vec![
+                ("fr-CH".to_string(), 1.0),
+                ("fr".to_string(), 0.9),
+                ("en".to_string(), 0.8),
+                ("de".to_string(), 0.7),
+                ("*".to_string(), 0.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_orders_by_quality_test() {
+        assert_eq!(
+            ranges("en;q=0.5, de, fr;q=0.5, ja;Q=0.8"),
+            vec![
+                ("de".to_string(), 1.0),
+                ("ja".to_string(), 0.8),
+                ("en".to_string(), 0.5),
+                ("fr".to_string(), 0.5),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_drops_invalid_test() {
+        assert_eq!(
+            ranges("en;q=0, fr;q=abc, de;q=1.5, , not a tag, es"),
+            vec![("es".to_string(), 1.0)]
+        );
+        assert!(ranges("").is_empty());
+    }
+
+    #[test]
+    fn negotiate_accept_language_test() {
+        let supported = vec!["en-US".to_string(), "fr".to_string(), "zh-Hant".to_string()];
+
+        assert_eq!(
+            negotiate_accept_language("fr-CH, fr;q=0.9, en;q=0.8", &supported, "en-US"),
+            "fr"
+        );
+        assert_eq!(
+            negotiate_accept_language("de, en-GB;q=0.5", &supported, "fr"),
+            "en-US"
+        );
+        assert_eq!(
+            negotiate_accept_language("zh-TW", &supported, "en-US"),
+            "zh-Hant"
+        );
+        assert_eq!(
+            negotiate_accept_language("de, *;q=0.1", &supported, "fr"),
+            "fr"
+        );
+        assert_eq!(negotiate_accept_language("", &supported, "en-US"), "en-US");
+    }
+}
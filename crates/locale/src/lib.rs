@@ -0,0 +1,14 @@
+//! Target-independent locale negotiation for Remix apps.
+//!
+//! Parses BCP 47 language tags and `Accept-Language` headers, and picks the
+//! best supported locale with RFC 4647 lookup plus region and script
+//! fallback. Has no browser dependencies, so the exact same rules run in
+//! server-side loaders and, through the client crate, in the browser.
+
+pub mod accept_language;
+pub mod negotiate;
+pub mod tag;
+
+pub use accept_language::{negotiate_accept_language, parse_accept_language, LanguageRange};
+pub use negotiate::{filter, negotiate};
+pub use tag::LanguageTag;
@@ -0,0 +1,181 @@
+use crate::tag::LanguageTag;
+
+/// Picks the best supported locale for a list of requested locales, ordered
+/// by preference.
+///
+/// Each requested locale is tried in turn, and for each one:
+///
+/// 1. RFC 4647 lookup: the tag itself, then with subtags removed from the
+///    end (`zh-Hant-TW`, `zh-Hant`, `zh`), skipping truncated tags whose
+///    likely script differs (`sr-Latn` never falls back to Cyrillic `sr`)
+/// 2. region fallback: a supported locale with the same language and script
+///    but another region (`en-GB` matches `en-US`)
+/// 3. script fallback: scripts implied by the region count as a match, so
+///    `zh-TW` matches `zh-Hant` but never `zh-Hans`
+///
+/// Returns the matching entry of `supported` as written, or `default` when
+/// nothing matches.
+pub fn negotiate(requested: &[String], supported: &[String], default: &str) -> String {
+    let supported_tags = supported
+        .iter()
+        .filter_map(|locale| LanguageTag::parse(locale).map(|tag| (locale, tag)))
+        .collect::<Vec<_>>();
+
+    for requested in requested
+        .iter()
+        .filter_map(|locale| LanguageTag::parse(locale))
+    {
+        let mut candidate = Some(requested.clone());
+        while let Some(tag) = candidate {
+            if let Some((locale, _)) = supported_tags.iter().find(|(_, supported)| {
+                *supported == tag && supported.scripts_compatible(&requested)
+            }) {
+                return locale.to_string();
+            }
+            candidate = tag.truncate();
+        }
+
+        let same_language = supported_tags
+            .iter()
+            .filter(|(_, supported)| {
+                supported.language == requested.language && supported.scripts_compatible(&requested)
+            })
+            .collect::<Vec<_>>();
+
+        let same_region = same_language.iter().find(|(_, supported)| {
+            requested.region.is_some() && supported.region == requested.region
+        });
+
+        if let Some((locale, _)) = same_region.or_else(|| same_language.first()) {
+            return locale.to_string();
+        }
+    }
+
+    default.to_string()
+}
+
+/// RFC 4647 basic filtering: every supported locale matched by at least one
+/// requested language range, in order of the requested ranges. `*` matches
+/// everything.
+pub fn filter(requested: &[String], supported: &[String]) -> Vec<String> {
+    let mut filtered: Vec<String> = Vec::new();
+
+    for range in requested {
+        let range = range.to_ascii_lowercase().replace('_', "-");
+
+        for locale in supported {
+            let tag = locale.to_ascii_lowercase().replace('_', "-");
+            let matches = range == "*" || tag == range || tag.starts_with(&format!("{}-", range));
+
+            if matches && !filtered.contains(locale) {
+                filtered.push(locale.clone());
+            }
+        }
+    }
+
+    filtered
+}
+
+#[cfg(test)]
+mod negotiate_tests {
+    use super::*;
+
+    fn locales(locales: &[&str]) -> Vec<String> {
+        locales.iter().map(|locale| locale.to_string()).collect()
+    }
+
+    #[test]
+    fn negotiate_lookup_test() {
+        let supported = locales(&["en", "fr-FR", "zh-Hant"]);
+
+        assert_eq!(negotiate(&locales(&["fr-FR"]), &supported, "en"), "fr-FR");
+        assert_eq!(negotiate(&locales(&["en-GB"]), &supported, "fr-FR"), "en");
+        assert_eq!(
+            negotiate(&locales(&["zh-Hant-TW"]), &supported, "en"),
+            "zh-Hant"
+        );
+        assert_eq!(
+            negotiate(&locales(&["de", "fr-fr"]), &supported, "en"),
+            "fr-FR"
+        );
+    }
+
+    #[test]
+    fn negotiate_region_fallback_test() {
+        let supported = locales(&["en-US", "en-GB", "pt-BR"]);
+
+        assert_eq!(
+            negotiate(&locales(&["en-AU"]), &supported, "pt-BR"),
+            "en-US"
+        );
+        assert_eq!(
+            negotiate(&locales(&["en-GB"]), &supported, "pt-BR"),
+            "en-GB"
+        );
+        assert_eq!(
+            negotiate(&locales(&["pt-PT", "en"]), &supported, "en-US"),
+            "pt-BR"
+        );
+    }
+
+    #[test]
+    fn negotiate_script_fallback_test() {
+        let supported = locales(&["zh-Hans", "zh-Hant"]);
+
+        assert_eq!(negotiate(&locales(&["zh-TW"]), &supported, "en"), "zh-Hant");
+        assert_eq!(negotiate(&locales(&["zh-CN"]), &supported, "en"), "zh-Hans");
+        assert_eq!(
+            negotiate(&locales(&["zh-HK"]), &locales(&["zh-CN"]), "en"),
+            "en"
+        );
+    }
+
+    #[test]
+    fn negotiate_lookup_keeps_script_test() {
+        assert_eq!(
+            negotiate(&locales(&["sr-Latn"]), &locales(&["sr"]), "en"),
+            "en"
+        );
+        assert_eq!(
+            negotiate(
+                &locales(&["sr-Latn"]),
+                &locales(&["sr", "sr-Latn-RS"]),
+                "en"
+            ),
+            "sr-Latn-RS"
+        );
+        assert_eq!(
+            negotiate(&locales(&["sr-Cyrl-RS"]), &locales(&["sr"]), "en"),
+            "sr"
+        );
+    }
+
+    #[test]
+    fn negotiate_default_test() {
+        assert_eq!(
+            negotiate(&locales(&["ja", "ko"]), &locales(&["en", "fr"]), "en"),
+            "en"
+        );
+        assert_eq!(negotiate(&[], &locales(&["en"]), "fr"), "fr");
+        assert_eq!(
+            negotiate(&locales(&["not a tag"]), &locales(&["en"]), "fr"),
+            "fr"
+        );
+    }
+
+    #[test]
+    fn filter_test() {
+        let supported = locales(&["en-US", "en-GB", "fr", "de-CH"]);
+
+        assert_eq!(
+            filter(&locales(&["de", "en"]), &supported),
+            locales(&["de-CH", "en-US", "en-GB"])
+        );
+        assert_eq!(
+            filter(&locales(&["en-gb"]), &supported),
+            locales(&["en-GB"])
+        );
+        assert_eq!(filter(&locales(&["*"]), &supported), supported);
+        assert!(filter(&locales(&["e"]), &supported).is_empty());
+    }
+}
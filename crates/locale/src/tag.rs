@@ -0,0 +1,193 @@
+use std::fmt;
+
+/// A parsed BCP 47 language tag, e.g. `zh-Hant-TW` or `sl-rozaj-biske`.
+/// Subtags are normalised to their canonical case.
+///
+/// Extensions and private use subtags (`-u-...`, `-x-...`) are accepted but
+/// dropped, since they don't take part in negotiation.
+///
+/// [RFC 5646](https://www.rfc-editor.org/rfc/rfc5646)
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LanguageTag {
+    pub language: String,
+    pub script: Option<String>,
+    pub region: Option<String>,
+    pub variants: Vec<String>,
+}
+
+impl LanguageTag {
+    /// Parses a tag. Accepts `_` as a separator too, as some platforms
+    /// report locales like `en_US`.
+    pub fn parse(tag: &str) -> Option<LanguageTag> {
+        if tag.split(['-', '_']).any(str::is_empty) {
+            return None;
+        }
+
+        let mut subtags = tag.split(['-', '_']).peekable();
+
+        let language = subtags.next()?;
+        if !(2..=8).contains(&language.len()) || language.len() == 4 || !is_alpha(language) {
+            return None;
+        }
+
+        let mut parsed = LanguageTag {
+            language: language.to_ascii_lowercase(),
+            script: None,
+            region: None,
+            variants: Vec::new(),
+        };
+
+        // Extended language subtags, e.g. `zh-yue`, are folded into the
+        // language. Only 2-3 letter primary languages can have them.
+        while let Some(extlang) =
+            subtags.next_if(|s| language.len() <= 3 && s.len() == 3 && is_alpha(s))
+        {
+            parsed.language = extlang.to_ascii_lowercase();
+        }
+
+        if let Some(script) = subtags.next_if(|s| s.len() == 4 && is_alpha(s)) {
+            let mut script = script.to_ascii_lowercase();
+            script[..1].make_ascii_uppercase();
+            parsed.script = Some(script);
+        }
+
+        if let Some(region) = subtags.next_if(|s| {
+            (s.len() == 2 && is_alpha(s)) || (s.len() == 3 && s.bytes().all(|b| b.is_ascii_digit()))
+        }) {
+            parsed.region = Some(region.to_ascii_uppercase());
+        }
+
+        while let Some(variant) = subtags.next_if(|s| is_variant(s)) {
+            parsed.variants.push(variant.to_ascii_lowercase());
+        }
+
+        match subtags.next() {
+            None => Some(parsed),
+            Some(singleton)
+                if singleton.len() == 1 && singleton.bytes().all(|b| b.is_ascii_alphanumeric()) =>
+            {
+                Some(parsed)
+            }
+            Some(_) => None,
+        }
+    }
+
+    /// The script this tag is most likely written in, explicit or not.
+    /// Only covers languages where the script actually tells locales apart.
+    pub fn likely_script(&self) -> Option<&str> {
+        if let Some(script) = &self.script {
+            return Some(script);
+        }
+
+        match (self.language.as_str(), self.region.as_deref()) {
+            ("zh", Some("TW" | "HK" | "MO")) => Some("Hant"),
+            ("zh", _) => Some("Hans"),
+            ("sr", Some("ME")) => Some("Latn"),
+            ("sr", _) => Some("Cyrl"),
+            _ => None,
+        }
+    }
+
+    /// The tag with its last subtag removed, as per the lookup algorithm.
+    pub(crate) fn truncate(&self) -> Option<LanguageTag> {
+        let mut truncated = self.clone();
+
+        if truncated.variants.pop().is_some() {
+            return Some(truncated);
+        }
+        if truncated.region.take().is_some() {
+            return Some(truncated);
+        }
+        if truncated.script.take().is_some() {
+            return Some(truncated);
+        }
+
+        None
+    }
+
+    pub(crate) fn scripts_compatible(&self, other: &LanguageTag) -> bool {
+        match (self.likely_script(), other.likely_script()) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.language)?;
+        for subtag in self.script.iter().chain(&self.region).chain(&self.variants) {
+            write!(f, "-{}", subtag)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn is_alpha(subtag: &str) -> bool {
+    subtag.bytes().all(|b| b.is_ascii_alphabetic())
+}
+
+fn is_variant(subtag: &str) -> bool {
+    let alphanumeric = subtag.bytes().all(|b| b.is_ascii_alphanumeric());
+    let starts_with_digit = subtag.bytes().next().is_some_and(|b| b.is_ascii_digit());
+
+    alphanumeric && ((5..=8).contains(&subtag.len()) || (subtag.len() == 4 && starts_with_digit))
+}
+
+#[cfg(test)]
+mod tag_tests {
+    use super::*;
+
+    fn tag(tag: &str) -> LanguageTag {
+        LanguageTag::parse(tag).unwrap()
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(
+            tag("zh-hant-tw"),
+            LanguageTag {
+                language: "zh".to_string(),
+                script: Some("Hant".to_string()),
+                region: Some("TW".to_string()),
+                variants: Vec::new(),
+            }
+        );
+        assert_eq!(tag("en_us").to_string(), "en-US");
+        assert_eq!(tag("es-419").region.as_deref(), Some("419"));
+        assert_eq!(tag("sl-rozaj-biske").variants, vec!["rozaj", "biske"]);
+        assert_eq!(tag("de-CH-1901").variants, vec!["1901"]);
+        assert_eq!(tag("en-US-u-ca-buddhist").to_string(), "en-US");
+        assert_eq!(tag("zh-yue-HK").to_string(), "yue-HK");
+    }
+
+    #[test]
+    fn parse_invalid_test() {
+        for invalid in [
+            "",
+            "e",
+            "englishes-us",
+            "en-US-!",
+            "1234",
+            "en--US",
+            "en-a-",
+            "abcde-yue",
+        ] {
+            assert!(
+                LanguageTag::parse(invalid).is_none(),
+                "{} should be invalid",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn likely_script_test() {
+        assert_eq!(tag("zh-TW").likely_script(), Some("Hant"));
+        assert_eq!(tag("zh").likely_script(), Some("Hans"));
+        assert_eq!(tag("zh-Hant-CN").likely_script(), Some("Hant"));
+        assert_eq!(tag("en-US").likely_script(), None);
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::manifest::{Manifest, ManifestEntry};
+
+/// The difference between two manifests, e.g. the previous and the current
+/// build. `changed` holds the entries from the newer manifest.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ManifestDiff {
+    pub added: Vec<ManifestEntry>,
+    pub changed: Vec<ManifestEntry>,
+    pub removed: Vec<ManifestEntry>,
+}
+
+impl ManifestDiff {
+    pub fn between(old: &Manifest, new: &Manifest) -> ManifestDiff {
+        let mut diff = ManifestDiff::default();
+
+        for entry in new.entries() {
+            match old.get(&entry.url) {
+                None => diff.added.push(entry.clone()),
+                Some(previous) if previous.revision != entry.revision => {
+                    diff.changed.push(entry.clone())
+                }
+                Some(_) => {}
+            }
+        }
+
+        diff.removed = old
+            .entries()
+            .iter()
+            .filter(|entry| new.get(&entry.url).is_none())
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl fmt::Display for ManifestDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.added {
+            writeln!(f, "+ {}", entry.url)?;
+        }
+        for entry in &self.changed {
+            writeln!(f, "~ {}", entry.url)?;
+        }
+        for entry in &self.removed {
+            writeln!(f, "- {}", entry.url)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod diff_tests {
+    use super::*;
+
+    #[test]
+    fn between_test() {
+        let old = Manifest::new(vec![
+            ManifestEntry::new("/a.js", "1"),
+            ManifestEntry::new("/b.js", "1"),
+            ManifestEntry::new("/c.js", "1"),
+        ]);
+        let new = Manifest::new(vec![
+            ManifestEntry::new("/a.js", "1"),
+            ManifestEntry::new("/b.js", "2"),
+            ManifestEntry::new("/d.js", "1"),
+        ]);
+
+        let diff = ManifestDiff::between(&old, &new);
+
+        assert_eq!(diff.added, vec![ManifestEntry::new("/d.js", "1")]);
+        assert_eq!(diff.changed, vec![ManifestEntry::new("/b.js", "2")]);
+        assert_eq!(diff.removed, vec![ManifestEntry::new("/c.js", "1")]);
+        assert_eq!(diff.to_string(), "+ /d.js\n~ /b.js\n- /c.js\n");
+    }
+
+    #[test]
+    fn identical_manifests_test() {
+        let manifest = Manifest::new(vec![ManifestEntry::new("/a.js", "1")]);
+        assert!(ManifestDiff::between(&manifest, &manifest).is_empty());
+    }
+}
@@ -0,0 +1,62 @@
+use std::{fmt, io, path::PathBuf};
+
+/// Errors that can occur whilst generating or reading a manifest.
+#[derive(Debug)]
+pub enum Error {
+    /// The build directory doesn't exist or isn't a directory.
+    MissingDirectory(PathBuf),
+    /// An include/exclude pattern couldn't be compiled.
+    Glob(globset::Error),
+    /// Reading from or writing to the file system failed.
+    Io(io::Error),
+    /// A manifest file couldn't be parsed.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingDirectory(path) => {
+                write!(f, "build directory `{}` doesn't exist", path.display())
+            }
+            Error::Glob(e) => write!(f, "invalid glob pattern: {}", e),
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Json(e) => write!(f, "invalid manifest: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::MissingDirectory(_) => None,
+            Error::Glob(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Json(e) => Some(e),
+        }
+    }
+}
+
+impl From<globset::Error> for Error {
+    fn from(e: globset::Error) -> Self {
+        Error::Glob(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<walkdir::Error> for Error {
+    fn from(e: walkdir::Error) -> Self {
+        Error::Io(e.into())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
@@ -0,0 +1,16 @@
+//! Revisioned precache manifest generation for Remix service workers.
+//!
+//! Scans a Remix build output directory, hashes every matching file and emits
+//! a `{ url, revision }` manifest that can be fed straight into a service
+//! worker's precache step. Meant as a drop-in for `workbox-build`'s
+//! `generateSW`/`injectManifest` manifest generation.
+
+pub mod diff;
+pub mod error;
+pub mod manifest;
+pub mod scan;
+
+pub use diff::ManifestDiff;
+pub use error::Error;
+pub use manifest::{Manifest, ManifestEntry};
+pub use scan::{scan, ScanOptions, ScanResult};
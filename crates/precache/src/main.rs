@@ -0,0 +1,121 @@
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use clap::{Parser, Subcommand};
+use precache::{scan, Error, Manifest, ManifestDiff, ScanOptions};
+
+/// Generates revisioned precache manifests from a Remix build.
+#[derive(Parser)]
+#[command(name = "remix-pwa-precache", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Scan a build directory and write the manifest.
+    Generate {
+        /// Build output directory to scan.
+        #[arg(long, default_value = "public/build")]
+        dir: PathBuf,
+        /// URL prefix the build directory is served from.
+        #[arg(long, default_value = "/build/")]
+        prefix: String,
+        /// Only include files matching this glob. Can be repeated.
+        #[arg(long)]
+        include: Vec<String>,
+        /// Exclude files matching this glob. Can be repeated.
+        #[arg(long)]
+        exclude: Vec<String>,
+        /// Skip files larger than this many bytes.
+        #[arg(long)]
+        max_size: Option<u64>,
+        /// Where to write the JSON manifest.
+        #[arg(long)]
+        json: Option<PathBuf>,
+        /// Where to write the JS module manifest.
+        #[arg(long)]
+        js: Option<PathBuf>,
+        /// A previous JSON manifest to diff the new one against. The diff is
+        /// printed to stderr.
+        #[arg(long)]
+        previous: Option<PathBuf>,
+    },
+    /// List added, changed and removed entries between two JSON manifests.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        /// Print the diff as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), Error> {
+    match command {
+        Command::Generate {
+            dir,
+            prefix,
+            include,
+            exclude,
+            max_size,
+            json,
+            js,
+            previous,
+        } => {
+            let options = ScanOptions {
+                directory: dir,
+                url_prefix: prefix,
+                include,
+                exclude,
+                max_file_size: max_size,
+            };
+            let result = scan(&options)?;
+
+            for (path, size) in &result.oversized {
+                eprintln!("warning: skipped `{}` ({} bytes exceeds --max-size)", path, size);
+            }
+
+            // stdout may carry the manifest itself, so the diff goes to stderr
+            if let Some(previous) = previous {
+                eprint!("{}", ManifestDiff::between(&Manifest::read(&previous)?, &result.manifest));
+            }
+
+            match (json, js) {
+                (None, None) => println!("{}", result.manifest.to_json()),
+                (json, js) => {
+                    if let Some(path) = json {
+                        fs::write(path, format!("{}\n", result.manifest.to_json()))?;
+                    }
+                    if let Some(path) = js {
+                        fs::write(path, result.manifest.to_js_module())?;
+                    }
+                }
+            }
+
+            eprintln!("Precached {} files", result.manifest.len());
+            Ok(())
+        }
+        Command::Diff { old, new, json } => {
+            let diff = ManifestDiff::between(&Manifest::read(&old)?, &Manifest::read(&new)?);
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&diff)?);
+            } else {
+                print!("{}", diff);
+            }
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// A single precache entry. Same shape as workbox's `ManifestEntry`, so
+/// existing service worker code can consume it unchanged.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub url: String,
+    pub revision: String,
+}
+
+impl ManifestEntry {
+    pub fn new(url: &str, revision: &str) -> ManifestEntry {
+        ManifestEntry {
+            url: url.to_string(),
+            revision: revision.to_string(),
+        }
+    }
+}
+
+/// A precache manifest. Entries are always kept sorted by URL so that
+/// generating the manifest twice over the same build yields identical output.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Manifest {
+    entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    pub fn new(mut entries: Vec<ManifestEntry>) -> Manifest {
+        entries.sort_by(|a, b| a.url.cmp(&b.url));
+        entries.dedup_by(|a, b| a.url == b.url);
+        Manifest { entries }
+    }
+
+    pub fn entries(&self) -> &[ManifestEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, url: &str) -> Option<&ManifestEntry> {
+        self.entries
+            .binary_search_by(|entry| entry.url.as_str().cmp(url))
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Serializes the manifest as a pretty-printed JSON array.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.entries).expect("manifest entries are always serializable")
+    }
+
+    /// Serializes the manifest as an ES module with the entries as its
+    /// default export, ready to be imported by a bundled service worker.
+    pub fn to_js_module(&self) -> String {
+        format!(
+            "// Generated by remix-pwa-precache. Do not edit.\nconst manifest = {};\n\nexport default manifest;\n",
+            self.to_json()
+        )
+    }
+
+    pub fn from_json(json: &str) -> Result<Manifest, Error> {
+        let entries: Vec<ManifestEntry> = serde_json::from_str(json)?;
+        Ok(Manifest::new(entries))
+    }
+
+    /// Reads a manifest previously written with [`Manifest::to_json`].
+    pub fn read(path: &Path) -> Result<Manifest, Error> {
+        Manifest::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod manifest_tests {
+    use super::*;
+
+    #[test]
+    fn new_sorts_and_dedups_test() {
+        let manifest = Manifest::new(vec![
+            ManifestEntry::new("/build/b.js", "2"),
+            ManifestEntry::new("/build/a.js", "1"),
+            ManifestEntry::new("/build/b.js", "3"),
+        ]);
+
+        assert_eq!(manifest.len(), 2);
+        assert_eq!(manifest.entries()[0].url, "/build/a.js");
+        assert_eq!(manifest.get("/build/b.js").unwrap().revision, "2");
+        assert!(manifest.get("/build/c.js").is_none());
+    }
+
+    #[test]
+    fn json_round_trip_test() {
+        let manifest = Manifest::new(vec![ManifestEntry::new("/build/a.js", "abc")]);
+        let json = manifest.to_json();
+
+        assert!(json.starts_with('['));
+        assert_eq!(Manifest::from_json(&json).unwrap(), manifest);
+    }
+
+    #[test]
+    fn to_js_module_test() {
+        let manifest = Manifest::new(vec![ManifestEntry::new("/build/a.js", "abc")]);
+        let module = manifest.to_js_module();
+
+        assert!(module.contains("\"url\": \"/build/a.js\""));
+        assert!(module.ends_with("export default manifest;\n"));
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use md5::{Digest, Md5};
+use walkdir::WalkDir;
+
+use crate::{
+    error::Error,
+    manifest::{Manifest, ManifestEntry},
+};
+
+/// Options controlling which files end up in the manifest.
+///
+/// `include` and `exclude` are globs relative to `directory`. An empty
+/// `include` list matches every file; `exclude` always wins over `include`.
+#[derive(Clone, Debug)]
+pub struct ScanOptions {
+    pub directory: PathBuf,
+    pub url_prefix: String,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub max_file_size: Option<u64>,
+}
+
+impl ScanOptions {
+    pub fn new(directory: impl Into<PathBuf>) -> ScanOptions {
+        ScanOptions {
+            directory: directory.into(),
+            url_prefix: "/".to_string(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            max_file_size: None,
+        }
+    }
+}
+
+/// The outcome of a scan: the manifest plus every file that matched the
+/// globs but was left out because it exceeded `max_file_size`.
+#[derive(Clone, Debug, Default)]
+pub struct ScanResult {
+    pub manifest: Manifest,
+    pub oversized: Vec<(String, u64)>,
+}
+
+/// Walks `options.directory` and builds a revisioned manifest out of it.
+pub fn scan(options: &ScanOptions) -> Result<ScanResult, Error> {
+    if !options.directory.is_dir() {
+        return Err(Error::MissingDirectory(options.directory.clone()));
+    }
+
+    let include = build_glob_set(&options.include)?;
+    let exclude = build_glob_set(&options.exclude)?;
+    let prefix = normalize_prefix(&options.url_prefix);
+
+    let mut entries = Vec::new();
+    let mut oversized = Vec::new();
+
+    for entry in WalkDir::new(&options.directory).follow_links(true) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = relative_path(&options.directory, entry.path());
+        if !options.include.is_empty() && !include.is_match(&relative) {
+            continue;
+        }
+        if exclude.is_match(&relative) {
+            continue;
+        }
+
+        let size = entry.metadata()?.len();
+        if options.max_file_size.is_some_and(|max| size > max) {
+            oversized.push((relative, size));
+            continue;
+        }
+
+        let url = format!("{}{}", prefix, relative);
+        entries.push(ManifestEntry::new(&url, &hash_file(entry.path())?));
+    }
+
+    Ok(ScanResult {
+        manifest: Manifest::new(entries),
+        oversized,
+    })
+}
+
+/// Returns the hex-encoded MD5 digest of a file's contents, the same revision
+/// format `workbox-build` produces.
+pub fn hash_file(path: &Path) -> Result<String, Error> {
+    let mut file = File::open(path)?;
+    let mut hasher = Md5::new();
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet, Error> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+
+    Ok(builder.build()?)
+}
+
+fn normalize_prefix(prefix: &str) -> String {
+    if prefix.is_empty() || prefix.ends_with('/') {
+        prefix.to_string()
+    } else {
+        format!("{}/", prefix)
+    }
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod scan_tests {
+    use std::fs;
+
+    use super::*;
+
+    fn build_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("_shared")).unwrap();
+        fs::write(dir.path().join("entry.client-ABC123.js"), "console.log('hi')").unwrap();
+        fs::write(dir.path().join("entry.client-ABC123.js.map"), "{}").unwrap();
+        fs::write(dir.path().join("_shared/chunk-XYZ.js"), "export {}").unwrap();
+        fs::write(dir.path().join("large.png"), vec![0u8; 2048]).unwrap();
+        dir
+    }
+
+    #[test]
+    fn scan_all_files_test() {
+        let dir = build_dir();
+        let mut options = ScanOptions::new(dir.path());
+        options.url_prefix = "/build".to_string();
+
+        let result = scan(&options).unwrap();
+        let urls = result.manifest.entries().iter().map(|e| e.url.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(
+            urls,
+            vec![
+                "/build/_shared/chunk-XYZ.js",
+                "/build/entry.client-ABC123.js",
+                "/build/entry.client-ABC123.js.map",
+                "/build/large.png",
+            ]
+        );
+    }
+
+    #[test]
+    fn scan_with_globs_and_size_limit_test() {
+        let dir = build_dir();
+        let mut options = ScanOptions::new(dir.path());
+        options.include = vec!["**/*.{js,png}".to_string()];
+        options.exclude = vec!["_shared/**".to_string()];
+        options.max_file_size = Some(1024);
+
+        let result = scan(&options).unwrap();
+
+        assert_eq!(result.manifest.len(), 1);
+        assert_eq!(result.manifest.entries()[0].url, "/entry.client-ABC123.js");
+        assert_eq!(result.oversized, vec![("large.png".to_string(), 2048)]);
+    }
+
+    #[test]
+    fn revision_follows_content_test() {
+        let dir = build_dir();
+        let options = ScanOptions::new(dir.path());
+        let before = scan(&options).unwrap().manifest;
+
+        fs::write(dir.path().join("_shared/chunk-XYZ.js"), "export default 1").unwrap();
+        let after = scan(&options).unwrap().manifest;
+
+        let url = "/_shared/chunk-XYZ.js";
+        assert_ne!(before.get(url).unwrap().revision, after.get(url).unwrap().revision);
+        assert_eq!(
+            before.get("/large.png").unwrap().revision,
+            after.get("/large.png").unwrap().revision
+        );
+    }
+
+    #[test]
+    fn hash_file_test() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("empty.txt");
+        fs::write(&path, "").unwrap();
+
+        assert_eq!(hash_file(&path).unwrap(), "d41d8cd98f00b204e9800998ecf8427e");
+    }
+
+    #[test]
+    fn missing_directory_test() {
+        let result = scan(&ScanOptions::new("/definitely/not/here"));
+        assert!(matches!(result, Err(Error::MissingDirectory(_))));
+    }
+}
@@ -0,0 +1,137 @@
+use serde::Deserialize;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Cache, Headers, Request, Response, ResponseInit, ResponseType};
+
+/// Header the time a response was cached at is stored in, in milliseconds
+/// since the epoch.
+pub const CACHED_AT_HEADER: &str = "x-remix-pwa-cached-at";
+
+/// Expiration rules for a runtime cache.
+///
+/// `maxEntries` is enforced whenever a response is written, evicting the
+/// oldest entries first. `maxAgeSeconds` is checked whenever a cached
+/// response is read; expired responses are deleted and treated as a miss.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Expiration {
+    pub max_entries: Option<u32>,
+    pub max_age_seconds: Option<u32>,
+}
+
+impl Expiration {
+    pub fn is_empty(&self) -> bool {
+        self.max_entries.is_none() && self.max_age_seconds.is_none()
+    }
+
+    /// Whether a response cached at `cached_at` (ms) is stale at `now` (ms).
+    /// Responses without a timestamp never expire.
+    pub fn is_expired(&self, cached_at: Option<f64>, now: f64) -> bool {
+        match (self.max_age_seconds, cached_at) {
+            (Some(max_age), Some(cached_at)) => now - cached_at > max_age as f64 * 1000.0,
+            _ => false,
+        }
+    }
+
+    /// How many of the oldest entries to evict from a cache holding `entries`.
+    pub fn overflow(&self, entries: usize) -> usize {
+        match self.max_entries {
+            Some(max_entries) => entries.saturating_sub(max_entries as usize),
+            None => 0,
+        }
+    }
+
+    /// Checks a cached response, deleting it from `cache` if it has expired.
+    pub async fn check(
+        &self,
+        cache: &Cache,
+        request: &Request,
+        response: &Response,
+    ) -> Result<bool, JsValue> {
+        let cached_at = response
+            .headers()
+            .get(CACHED_AT_HEADER)?
+            .and_then(|value| value.parse::<f64>().ok());
+
+        if self.is_expired(cached_at, js_sys::Date::now()) {
+            JsFuture::from(cache.delete_with_request(request)).await?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Evicts the oldest entries of `cache` until it fits `maxEntries`.
+    /// `Cache.keys()` returns requests in insertion order.
+    pub async fn enforce(&self, cache: &Cache) -> Result<(), JsValue> {
+        if self.max_entries.is_none() {
+            return Ok(());
+        }
+
+        let keys: js_sys::Array = JsFuture::from(cache.keys()).await?.unchecked_into();
+        for key in keys.iter().take(self.overflow(keys.length() as usize)) {
+            JsFuture::from(cache.delete_with_request(&key.unchecked_into())).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns a copy of `response` carrying the current time in
+/// [`CACHED_AT_HEADER`]. Opaque responses can't be copied and are returned
+/// as-is, so they never expire by age.
+pub fn stamp(response: Response) -> Result<Response, JsValue> {
+    if response.type_() == ResponseType::Opaque {
+        return Ok(response);
+    }
+
+    let headers = Headers::new_with_headers(&response.headers())?;
+    headers.set(CACHED_AT_HEADER, &js_sys::Date::now().to_string())?;
+
+    let mut init = ResponseInit::new();
+    init.status(response.status())
+        .status_text(&response.status_text())
+        .headers(&headers);
+
+    Response::new_with_opt_readable_stream_and_init(response.body().as_ref(), &init)
+}
+
+#[cfg(test)]
+mod expiration_tests {
+    use super::*;
+
+    #[test]
+    fn is_expired_test() {
+        let expiration = Expiration {
+            max_entries: None,
+            max_age_seconds: Some(60),
+        };
+
+        assert!(!expiration.is_expired(Some(0.0), 60_000.0));
+        assert!(expiration.is_expired(Some(0.0), 60_001.0));
+        assert!(!expiration.is_expired(None, 1e12));
+        assert!(!Expiration::default().is_expired(Some(0.0), 1e12));
+    }
+
+    #[test]
+    fn overflow_test() {
+        let expiration = Expiration {
+            max_entries: Some(10),
+            max_age_seconds: None,
+        };
+
+        assert_eq!(expiration.overflow(8), 0);
+        assert_eq!(expiration.overflow(13), 3);
+        assert_eq!(Expiration::default().overflow(100), 0);
+    }
+
+    #[test]
+    fn deserialize_test() {
+        let expiration: Expiration =
+            serde_json::from_str(r#"{ "maxEntries": 50, "maxAgeSeconds": 3600 }"#).unwrap();
+
+        assert_eq!(expiration.max_entries, Some(50));
+        assert_eq!(expiration.max_age_seconds, Some(3600));
+        assert!(!expiration.is_empty());
+    }
+}
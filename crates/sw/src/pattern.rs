@@ -0,0 +1,112 @@
+/// Checks a URL against a route pattern.
+///
+/// Patterns starting with `http://` or `https://` are matched against the
+/// origin and pathname, anything else against the pathname alone. The query
+/// string and hash are never part of the match.
+///
+/// - `*` matches any run of characters, slashes included
+/// - `:name` matches exactly one non-empty path segment
+///
+/// ## Example
+///
+/// ```ignore
+/// assert!(matches_url("/build/*", "https://example.com/build/root-ABC.js"));
+/// assert!(matches_url("/posts/:id", "https://example.com/posts/42?ref=home"));
+/// ```
+pub fn matches_url(pattern: &str, url: &str) -> bool {
+    let (origin, path) = split_url(url);
+
+    if pattern.starts_with("http://") || pattern.starts_with("https://") {
+        matches(pattern.as_bytes(), format!("{}{}", origin, path).as_bytes())
+    } else {
+        matches(pattern.as_bytes(), path.as_bytes())
+    }
+}
+
+/// Splits an absolute URL into its origin and its pathname.
+pub fn split_url(url: &str) -> (&str, &str) {
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+    let authority = url.find("://").map(|i| i + 3).unwrap_or(0);
+
+    match url[authority..].find('/') {
+        Some(i) => url.split_at(authority + i),
+        None => (url, "/"),
+    }
+}
+
+fn matches(pattern: &[u8], input: &[u8]) -> bool {
+    match pattern.first() {
+        None => input.is_empty(),
+        Some(b'*') => (0..=input.len()).any(|i| matches(&pattern[1..], &input[i..])),
+        Some(b':') => {
+            let name = pattern[1..]
+                .iter()
+                .take_while(|c| c.is_ascii_alphanumeric() || **c == b'_')
+                .count();
+            let segment = input.iter().take_while(|c| **c != b'/').count();
+
+            segment > 0 && matches(&pattern[1 + name..], &input[segment..])
+        }
+        Some(c) => input.first() == Some(c) && matches(&pattern[1..], &input[1..]),
+    }
+}
+
+#[cfg(test)]
+mod pattern_tests {
+    use super::*;
+
+    #[test]
+    fn split_url_test() {
+        assert_eq!(
+            split_url("https://example.com/a/b?c=d"),
+            ("https://example.com", "/a/b")
+        );
+        assert_eq!(
+            split_url("https://example.com"),
+            ("https://example.com", "/")
+        );
+        assert_eq!(split_url("/relative#hash"), ("", "/relative"));
+    }
+
+    #[test]
+    fn wildcard_test() {
+        assert!(matches_url(
+            "/build/*",
+            "https://example.com/build/_shared/chunk-ABC.js"
+        ));
+        assert!(matches_url(
+            "*.css",
+            "https://example.com/build/root.css?v=1"
+        ));
+        assert!(!matches_url(
+            "/build/*",
+            "https://example.com/assets/logo.png"
+        ));
+    }
+
+    #[test]
+    fn param_test() {
+        assert!(matches_url("/posts/:id", "https://example.com/posts/42"));
+        assert!(matches_url(
+            "/posts/:id/edit",
+            "https://example.com/posts/42/edit"
+        ));
+        assert!(!matches_url(
+            "/posts/:id",
+            "https://example.com/posts/42/edit"
+        ));
+        assert!(!matches_url("/posts/:id", "https://example.com/posts/"));
+    }
+
+    #[test]
+    fn origin_test() {
+        assert!(matches_url(
+            "https://fonts.gstatic.com/*",
+            "https://fonts.gstatic.com/s/inter.woff2"
+        ));
+        assert!(!matches_url(
+            "https://fonts.gstatic.com/*",
+            "https://example.com/s/inter.woff2"
+        ));
+    }
+}
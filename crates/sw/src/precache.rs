@@ -0,0 +1,224 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Request, RequestCache, RequestInit, Response};
+
+use crate::{
+    pattern::split_url,
+    scope::{fetch, open_cache},
+};
+
+/// Default cache precached assets are stored in.
+pub const DEFAULT_PRECACHE_NAME: &str = "remix-pwa-precache";
+
+/// Query parameter the revision is stored under in cache keys, so that a new
+/// revision of the same URL gets a new cache entry.
+const REVISION_PARAM: &str = "__remix_pwa_revision";
+
+/// An entry of a precache manifest, as generated by `remix-pwa-precache`.
+/// `revision` can be left out for URLs that already carry a content hash.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PrecacheEntry {
+    pub url: String,
+    pub revision: Option<String>,
+}
+
+impl PrecacheEntry {
+    /// The URL this entry is stored under in the cache.
+    pub fn cache_key(&self) -> String {
+        match &self.revision {
+            Some(revision) => {
+                let separator = if self.url.contains('?') { '&' } else { '?' };
+                format!("{}{}{}={}", self.url, separator, REVISION_PARAM, revision)
+            }
+            None => self.url.clone(),
+        }
+    }
+}
+
+/// Precaches a manifest on install, cleans outdated entries on activate and
+/// answers requests for precached URLs from the cache.
+#[derive(Clone, Debug)]
+pub struct Precache {
+    pub cache_name: String,
+    pub entries: Vec<PrecacheEntry>,
+}
+
+impl Precache {
+    pub fn new(cache_name: &str, entries: Vec<PrecacheEntry>) -> Precache {
+        Precache {
+            cache_name: cache_name.to_string(),
+            entries,
+        }
+    }
+
+    /// Finds the entry for a request URL. Entries are matched by pathname
+    /// (and query) when relative, or by the full URL when absolute.
+    pub fn lookup(&self, url: &str) -> Option<&PrecacheEntry> {
+        let (origin, path) = split_url(url);
+        let query = url[origin.len() + path.len()..]
+            .split('#')
+            .next()
+            .unwrap_or_default();
+        let relative = format!("{}{}", path, query);
+
+        self.entries.iter().find(|entry| {
+            if entry.url.starts_with('/') {
+                entry.url == relative
+            } else {
+                entry.url == format!("{}{}", origin, relative)
+            }
+        })
+    }
+
+    /// Fetches and stores every entry that isn't cached yet. Fails if any
+    /// entry can't be fetched, which makes the service worker install fail
+    /// rather than activate with a partial cache.
+    pub async fn install(&self) -> Result<(), JsValue> {
+        let cache = open_cache(&self.cache_name).await?;
+
+        for entry in &self.entries {
+            let key = entry.cache_key();
+            if JsFuture::from(cache.match_with_str(&key))
+                .await?
+                .is_truthy()
+            {
+                continue;
+            }
+
+            // Skip the HTTP cache, it could hand us an outdated revision
+            let mut init = RequestInit::new();
+            init.cache(RequestCache::Reload);
+            let response = fetch(&Request::new_with_str_and_init(&entry.url, &init)?).await?;
+
+            if !response.ok() {
+                return Err(JsValue::from_str(&format!(
+                    "Failed to precache {} ({})",
+                    entry.url,
+                    response.status()
+                )));
+            }
+
+            JsFuture::from(cache.put_with_str(&key, &response)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes cached entries that are no longer part of the manifest.
+    pub async fn activate(&self) -> Result<(), JsValue> {
+        let cache = open_cache(&self.cache_name).await?;
+        let expected = self
+            .entries
+            .iter()
+            .map(PrecacheEntry::cache_key)
+            .collect::<HashSet<_>>();
+
+        let keys: js_sys::Array = JsFuture::from(cache.keys()).await?.unchecked_into();
+        for key in keys.iter().map(JsCast::unchecked_into::<Request>) {
+            let url = key.url();
+            let (origin, _) = split_url(&url);
+            let relative = &url[origin.len()..];
+
+            if !expected.contains(relative) && !expected.contains(&url) {
+                JsFuture::from(cache.delete_with_request(&key)).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Answers a request for `entry` from the cache, falling back to the
+    /// network if the entry went missing.
+    pub async fn respond(
+        &self,
+        entry: &PrecacheEntry,
+        request: &Request,
+    ) -> Result<Response, JsValue> {
+        let cache = open_cache(&self.cache_name).await?;
+
+        match JsFuture::from(cache.match_with_str(&entry.cache_key()))
+            .await?
+            .dyn_into::<Response>()
+        {
+            Ok(response) => Ok(response),
+            Err(_) => fetch(request).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod precache_tests {
+    use super::*;
+
+    fn entry(url: &str, revision: Option<&str>) -> PrecacheEntry {
+        PrecacheEntry {
+            url: url.to_string(),
+            revision: revision.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn cache_key_test() {
+        assert_eq!(
+            entry("/", Some("abc")).cache_key(),
+            "/?__remix_pwa_revision=abc"
+        );
+        assert_eq!(
+            entry("/a?b=c", Some("abc")).cache_key(),
+            "/a?b=c&__remix_pwa_revision=abc"
+        );
+        assert_eq!(
+            entry("/build/root-X1Y2.js", None).cache_key(),
+            "/build/root-X1Y2.js"
+        );
+    }
+
+    #[test]
+    fn lookup_test() {
+        let precache = Precache::new(
+            DEFAULT_PRECACHE_NAME,
+            vec![
+                entry("/build/root.js", Some("1")),
+                entry("/offline?lang=en", Some("2")),
+                entry("https://cdn.example.com/font.woff2", None),
+            ],
+        );
+
+        assert_eq!(
+            precache
+                .lookup("https://example.com/build/root.js")
+                .unwrap()
+                .url,
+            "/build/root.js"
+        );
+        assert!(precache
+            .lookup("https://example.com/build/root.js?v=2")
+            .is_none());
+        assert!(precache
+            .lookup("https://example.com/offline?lang=en")
+            .is_some());
+        assert!(precache
+            .lookup("https://cdn.example.com/font.woff2")
+            .is_some());
+        assert!(precache.lookup("https://example.com/font.woff2").is_none());
+    }
+
+    #[test]
+    fn deserialize_manifest_test() {
+        let entries: Vec<PrecacheEntry> = serde_json::from_str(
+            r#"[{ "url": "/build/a.js", "revision": "abc" }, { "url": "/build/b-HASH.js" }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                entry("/build/a.js", Some("abc")),
+                entry("/build/b-HASH.js", None)
+            ]
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use std::{cell::RefCell, rc::Rc};
+
+use serde::Deserialize;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::future_to_promise;
+use web_sys::{ExtendableEvent, FetchEvent};
+
+use crate::{
+    pattern::matches_url,
+    precache::{Precache, PrecacheEntry, DEFAULT_PRECACHE_NAME},
+    scope::global,
+    share_target::ShareTarget,
+    strategies::Strategy,
+};
+
+/// Which requests a route applies to. `method` and `destination` are
+/// optional; when left out any method or destination matches.
+///
+/// ## Example
+///
+/// ```js
+/// { pattern: "/build/*", method: "GET", destination: "script" }
+/// ```
+///
+/// [Request destinations](https://developer.mozilla.org/en-US/docs/Web/API/Request/destination)
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct RouteMatcher {
+    pub pattern: String,
+    pub method: Option<String>,
+    pub destination: Option<String>,
+}
+
+impl RouteMatcher {
+    pub fn matches(&self, url: &str, method: &str, destination: &str) -> bool {
+        let method_matches = match &self.method {
+            Some(expected) => expected.eq_ignore_ascii_case(method),
+            None => true,
+        };
+        let destination_matches = match &self.destination {
+            Some(expected) => expected == destination,
+            None => true,
+        };
+
+        method_matches && destination_matches && matches_url(&self.pattern, url)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Route {
+    pub matcher: RouteMatcher,
+    pub strategy: Rc<Strategy>,
+}
+
+#[derive(Default)]
+struct RouterState {
+    routes: Vec<Route>,
+    precache: Option<Rc<Precache>>,
+    share_target: Option<Rc<ShareTarget>>,
+}
+
+/// Routes fetch events to caching strategies and manages the precache.
+///
+/// Routes are matched in the order they were registered. Requests that match
+/// no route (and aren't precached) are left to the browser.
+///
+/// ## Example
+///
+/// ```js
+/// // entry.worker.js
+/// import init, { Router } from "@remix-pwa/sw";
+/// import manifest from "./precache-manifest";
+///
+/// await init();
+///
+/// const router = new Router();
+/// router.precache(manifest);
+/// router.registerRoute({ pattern: "/build/*" }, { strategy: "cache-first", cacheName: "assets", maxEntries: 100 });
+/// router.registerRoute({ pattern: "*", destination: "document" }, { strategy: "network-first", networkTimeoutSeconds: 3 });
+/// router.listen();
+/// ```
+///
+/// [Source](https://github.com/remix-pwa/remix-pwa-ecosystem/blob/main/crates/sw/src/router.rs)
+#[wasm_bindgen]
+#[derive(Clone, Default)]
+pub struct Router {
+    state: Rc<RefCell<RouterState>>,
+}
+
+impl Router {
+    pub fn add_route(&self, matcher: RouteMatcher, strategy: Strategy) {
+        self.state.borrow_mut().routes.push(Route {
+            matcher,
+            strategy: Rc::new(strategy),
+        });
+    }
+
+    pub fn find_route(&self, url: &str, method: &str, destination: &str) -> Option<Route> {
+        self.state
+            .borrow()
+            .routes
+            .iter()
+            .find(|route| route.matcher.matches(url, method, destination))
+            .cloned()
+    }
+
+    pub fn set_precache(&self, precache: Precache) {
+        self.state.borrow_mut().precache = Some(Rc::new(precache));
+    }
+
+    fn precache_ref(&self) -> Option<Rc<Precache>> {
+        self.state.borrow().precache.clone()
+    }
+
+    pub fn set_share_target(&self, share_target: ShareTarget) {
+        self.state.borrow_mut().share_target = Some(Rc::new(share_target));
+    }
+
+    fn share_target_ref(&self) -> Option<Rc<ShareTarget>> {
+        self.state.borrow().share_target.clone()
+    }
+}
+
+#[wasm_bindgen]
+impl Router {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Router {
+        Router::default()
+    }
+
+    /// Registers a route. Takes a matcher (`{ pattern, method?, destination? }`)
+    /// and a strategy (`{ strategy, cacheName?, networkTimeoutSeconds?,
+    /// maxEntries?, maxAgeSeconds? }`).
+    #[wasm_bindgen(js_name = "registerRoute")]
+    pub fn register_route(&self, matcher: JsValue, strategy: JsValue) -> Result<(), JsValue> {
+        let matcher: RouteMatcher = serde_wasm_bindgen::from_value(matcher)?;
+        let strategy: Strategy = serde_wasm_bindgen::from_value(strategy)?;
+
+        self.add_route(matcher, strategy);
+        Ok(())
+    }
+
+    /// Sets the precache manifest, an array of `{ url, revision? }` entries.
+    pub fn precache(&self, manifest: JsValue, cache_name: Option<String>) -> Result<(), JsValue> {
+        let entries: Vec<PrecacheEntry> = serde_wasm_bindgen::from_value(manifest)?;
+        let cache_name = cache_name.unwrap_or_else(|| DEFAULT_PRECACHE_NAME.to_string());
+
+        self.set_precache(Precache::new(&cache_name, entries));
+        Ok(())
+    }
+
+    /// Handles shares sent to the manifest's `share_target`. Takes
+    /// `{ action, params?, redirect? }`, where `params` is the manifest's
+    /// `share_target.params`.
+    #[wasm_bindgen(js_name = "shareTarget")]
+    pub fn share_target(&self, options: JsValue) -> Result<(), JsValue> {
+        let share_target: ShareTarget = serde_wasm_bindgen::from_value(options)?;
+
+        self.set_share_target(share_target);
+        Ok(())
+    }
+
+    /// Responds to a fetch event if it is a share, or matches the precache
+    /// or a route. Returns whether the event was handled.
+    #[wasm_bindgen(js_name = "handleFetch")]
+    pub fn handle_fetch(&self, event: FetchEvent) -> Result<bool, JsValue> {
+        let request = event.request();
+        let url = request.url();
+
+        if let Some(share_target) = self.share_target_ref() {
+            if share_target.matches(&url, &request.method()) {
+                event.respond_with(&future_to_promise(async move {
+                    share_target.handle(&request).await.map(JsValue::from)
+                }))?;
+                return Ok(true);
+            }
+        }
+
+        if let Some(precache) = self.precache_ref() {
+            if let Some(entry) = precache.lookup(&url).cloned() {
+                event.respond_with(&future_to_promise(async move {
+                    precache.respond(&entry, &request).await.map(JsValue::from)
+                }))?;
+                return Ok(true);
+            }
+        }
+
+        let destination = JsValue::from(request.destination())
+            .as_string()
+            .unwrap_or_default();
+        let route = match self.find_route(&url, &request.method(), &destination) {
+            Some(route) => route,
+            None => return Ok(false),
+        };
+
+        let fetch_event = event.clone();
+        event.respond_with(&future_to_promise(async move {
+            route
+                .strategy
+                .handle(request, &fetch_event)
+                .await
+                .map(JsValue::from)
+        }))?;
+
+        Ok(true)
+    }
+
+    /// Precaches the manifest during the `install` event.
+    #[wasm_bindgen(js_name = "handleInstall")]
+    pub fn handle_install(&self, event: ExtendableEvent) -> Result<(), JsValue> {
+        if let Some(precache) = self.precache_ref() {
+            event.wait_until(&future_to_promise(async move {
+                precache.install().await.map(|_| JsValue::UNDEFINED)
+            }))?;
+        }
+
+        Ok(())
+    }
+
+    /// Cleans outdated precache entries during the `activate` event.
+    #[wasm_bindgen(js_name = "handleActivate")]
+    pub fn handle_activate(&self, event: ExtendableEvent) -> Result<(), JsValue> {
+        if let Some(precache) = self.precache_ref() {
+            event.wait_until(&future_to_promise(async move {
+                precache.activate().await.map(|_| JsValue::UNDEFINED)
+            }))?;
+        }
+
+        Ok(())
+    }
+
+    /// Attaches `install`, `activate` and `fetch` listeners to the service
+    /// worker global scope.
+    pub fn listen(&self) -> Result<(), JsValue> {
+        let scope = global();
+
+        let router = self.clone();
+        let on_install =
+            Closure::<dyn FnMut(ExtendableEvent)>::new(move |event: ExtendableEvent| {
+                if let Err(e) = router.handle_install(event) {
+                    log::error!("Error handling install event: {:?}", e);
+                }
+            });
+
+        let router = self.clone();
+        let on_activate =
+            Closure::<dyn FnMut(ExtendableEvent)>::new(move |event: ExtendableEvent| {
+                if let Err(e) = router.handle_activate(event) {
+                    log::error!("Error handling activate event: {:?}", e);
+                }
+            });
+
+        let router = self.clone();
+        let on_fetch = Closure::<dyn FnMut(FetchEvent)>::new(move |event: FetchEvent| {
+            if let Err(e) = router.handle_fetch(event) {
+                log::error!("Error handling fetch event: {:?}", e);
+            }
+        });
+
+        scope.add_event_listener_with_callback("install", on_install.as_ref().unchecked_ref())?;
+        scope.add_event_listener_with_callback("activate", on_activate.as_ref().unchecked_ref())?;
+        scope.add_event_listener_with_callback("fetch", on_fetch.as_ref().unchecked_ref())?;
+
+        // The listeners live as long as the worker does
+        on_install.forget();
+        on_activate.forget();
+        on_fetch.forget();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod router_tests {
+    use super::*;
+    use crate::strategies::StrategyKind;
+
+    fn matcher(pattern: &str, method: Option<&str>, destination: Option<&str>) -> RouteMatcher {
+        RouteMatcher {
+            pattern: pattern.to_string(),
+            method: method.map(str::to_string),
+            destination: destination.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn route_matcher_test() {
+        let scripts = matcher("/build/*", Some("get"), Some("script"));
+
+        assert!(scripts.matches("https://example.com/build/root.js", "GET", "script"));
+        assert!(!scripts.matches("https://example.com/build/root.js", "POST", "script"));
+        assert!(!scripts.matches("https://example.com/build/root.css", "GET", "style"));
+        assert!(matcher("*", None, None).matches("https://example.com/", "DELETE", ""));
+    }
+
+    #[test]
+    fn find_route_order_test() {
+        let router = Router::new();
+        router.add_route(
+            matcher("/build/*", None, None),
+            Strategy::new(StrategyKind::CacheFirst),
+        );
+        router.add_route(
+            matcher("*", None, Some("document")),
+            Strategy::new(StrategyKind::NetworkFirst),
+        );
+        router.add_route(
+            matcher("*", None, None),
+            Strategy::new(StrategyKind::NetworkOnly),
+        );
+
+        let kind = |url: &str, destination: &str| {
+            router
+                .find_route(url, "GET", destination)
+                .unwrap()
+                .strategy
+                .kind
+        };
+
+        assert_eq!(
+            kind("https://example.com/build/a.js", "script"),
+            StrategyKind::CacheFirst
+        );
+        assert_eq!(
+            kind("https://example.com/about", "document"),
+            StrategyKind::NetworkFirst
+        );
+        assert_eq!(
+            kind("https://example.com/api/data", ""),
+            StrategyKind::NetworkOnly
+        );
+    }
+
+    #[test]
+    fn no_route_test() {
+        let router = Router::new();
+        router.add_route(
+            matcher("/build/*", None, None),
+            Strategy::new(StrategyKind::CacheFirst),
+        );
+
+        assert!(router
+            .find_route("https://example.com/about", "GET", "document")
+            .is_none());
+    }
+}
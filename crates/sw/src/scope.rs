@@ -0,0 +1,41 @@
+use js_sys::Promise;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Cache, Request, Response, ServiceWorkerGlobalScope};
+
+/// Returns the `ServiceWorkerGlobalScope` the module is running in.
+///
+/// Panics when called outside of a service worker.
+pub fn global() -> ServiceWorkerGlobalScope {
+    js_sys::global()
+        .dyn_into::<ServiceWorkerGlobalScope>()
+        .expect("`ServiceWorkerGlobalScope` doesn't exist. Make sure you are running this in a service worker")
+}
+
+/// Opens (and creates if needed) the cache named `name`.
+pub async fn open_cache(name: &str) -> Result<Cache, JsValue> {
+    let cache = JsFuture::from(global().caches()?.open(name)).await?;
+    Ok(cache.unchecked_into())
+}
+
+/// Fetches `request` from the network.
+pub async fn fetch(request: &Request) -> Result<Response, JsValue> {
+    let response = JsFuture::from(global().fetch_with_request(request)).await?;
+    Ok(response.unchecked_into())
+}
+
+/// Looks `request` up in `cache`, returning `None` on a miss.
+pub async fn match_request(cache: &Cache, request: &Request) -> Result<Option<Response>, JsValue> {
+    let response = JsFuture::from(cache.match_with_request(request)).await?;
+    Ok(response.dyn_into::<Response>().ok())
+}
+
+/// A promise that resolves with `undefined` after `ms` milliseconds.
+pub fn sleep(ms: i32) -> Promise {
+    Promise::new(&mut |resolve, _| {
+        if let Err(e) = global().set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms)
+        {
+            log::error!("Error setting timeout: {:?}", e);
+        }
+    })
+}
@@ -0,0 +1,276 @@
+use serde::Deserialize;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{File, FormData, Request, Response, Url};
+
+use crate::{pattern::matches_url, scope::open_cache};
+
+/// Cache shares are handed over to the page through. Must match
+/// `crates/client/src/client/share_target.rs`.
+pub const SHARE_TARGET_CACHE: &str = "remix-pwa-share-target";
+
+/// Query parameter the page is redirected with, holding the share's id.
+pub const SHARE_PARAM: &str = "share-target";
+
+/// Path prefix of the cache keys shares are stored under.
+pub const SHARE_KEY_PREFIX: &str = "/__remix-pwa-share-target/";
+
+/// A `files` entry of the manifest's `share_target.params`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ShareFileParam {
+    pub name: String,
+}
+
+/// The manifest's `share_target.params`: which form fields hold the
+/// shared title, text, URL and files. Defaults to `title`, `text`, `url`
+/// and `files`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ShareParams {
+    pub title: Option<String>,
+    pub text: Option<String>,
+    pub url: Option<String>,
+    pub files: Vec<ShareFileParam>,
+}
+
+impl Default for ShareParams {
+    fn default() -> Self {
+        ShareParams {
+            title: Some("title".to_string()),
+            text: Some("text".to_string()),
+            url: Some("url".to_string()),
+            files: vec![ShareFileParam {
+                name: "files".to_string(),
+            }],
+        }
+    }
+}
+
+/// Handles shares sent to the app through the manifest's `share_target`.
+///
+/// Shares are normalised to `{ title, text, url, files }`, stored in the
+/// [`SHARE_TARGET_CACHE`] and the page is redirected to `redirect` (the
+/// `action` by default) with a `?share-target=<id>` parameter, where
+/// `takeSharedData()` from `@remix-pwa/client` picks it up.
+///
+/// ## Example
+///
+/// ```js
+/// // manifest: "share_target": { "action": "/share", "method": "POST", "enctype": "multipart/form-data", "params": { ... } }
+/// router.shareTarget({ action: "/share", params: manifest.share_target.params, redirect: "/notes/new" });
+/// ```
+///
+/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/Manifest/share_target)
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ShareTarget {
+    /// Pattern matching the manifest's `action`, e.g. `/share`
+    pub action: String,
+    #[serde(default)]
+    pub params: ShareParams,
+    pub redirect: Option<String>,
+}
+
+impl ShareTarget {
+    /// Whether a request is a share. `POST`s to the action always are;
+    /// `GET`s only when they carry one of the share params, so the action
+    /// page can still be visited normally.
+    pub fn matches(&self, url: &str, method: &str) -> bool {
+        if !matches_url(&self.action, url) {
+            return false;
+        }
+
+        match method {
+            "POST" => true,
+            "GET" => {
+                let query = match Url::new(url) {
+                    Ok(url) => url.search_params(),
+                    Err(_) => return false,
+                };
+
+                [&self.params.title, &self.params.text, &self.params.url]
+                    .iter()
+                    .filter_map(|param| param.as_deref())
+                    .any(|name| query.has(name))
+            }
+            _ => false,
+        }
+    }
+
+    /// Where the page is sent after a share.
+    pub fn redirect_url(&self, id: &str) -> String {
+        let target = self.redirect.as_deref().unwrap_or(&self.action);
+        let separator = if target.contains('?') { '&' } else { '?' };
+
+        format!("{}{}{}={}", target, separator, SHARE_PARAM, id)
+    }
+
+    /// Stores a share and redirects to the page that handles it.
+    pub async fn handle(&self, request: &Request) -> Result<Response, JsValue> {
+        let share = if request.method() == "POST" {
+            let form = JsFuture::from(request.form_data()?)
+                .await?
+                .unchecked_into::<FormData>();
+            self.read_form(&form)
+        } else {
+            let query = Url::new(&request.url())?.search_params();
+            let field = |name: &Option<String>| name.as_deref().and_then(|name| query.get(name));
+
+            Share {
+                title: field(&self.params.title).unwrap_or_default(),
+                text: field(&self.params.text).unwrap_or_default(),
+                url: field(&self.params.url).unwrap_or_default(),
+                files: Vec::new(),
+            }
+        };
+
+        let id = format!(
+            "{:x}{:x}",
+            js_sys::Date::now() as u64,
+            (js_sys::Math::random() * u32::MAX as f64) as u32
+        );
+        let body = share.normalize().to_form_data()?;
+
+        let cache = open_cache(SHARE_TARGET_CACHE).await?;
+        let key = format!("{}{}", SHARE_KEY_PREFIX, id);
+        JsFuture::from(cache.put_with_str(&key, &Response::new_with_opt_form_data(Some(&body))?))
+            .await?;
+
+        // 303, so the browser follows up with a GET instead of re-posting
+        Response::redirect_with_status(&self.redirect_url(&id), 303)
+    }
+
+    fn read_form(&self, form: &FormData) -> Share {
+        let field = |name: &Option<String>| {
+            name.as_deref()
+                .and_then(|name| form.get(name).as_string())
+                .unwrap_or_default()
+        };
+
+        Share {
+            title: field(&self.params.title),
+            text: field(&self.params.text),
+            url: field(&self.params.url),
+            files: self
+                .params
+                .files
+                .iter()
+                .flat_map(|param| form.get_all(&param.name).to_vec())
+                .filter_map(|file| file.dyn_into::<File>().ok())
+                .collect(),
+        }
+    }
+}
+
+struct Share {
+    title: String,
+    text: String,
+    url: String,
+    files: Vec<File>,
+}
+
+impl Share {
+    fn normalize(mut self) -> Share {
+        if self.url.is_empty() {
+            if let Some(url) = extract_url(&self.text) {
+                self.url = url.to_string();
+            }
+        }
+
+        self
+    }
+
+    fn to_form_data(&self) -> Result<FormData, JsValue> {
+        let form = FormData::new()?;
+        form.append_with_str("title", &self.title)?;
+        form.append_with_str("text", &self.text)?;
+        form.append_with_str("url", &self.url)?;
+
+        for file in &self.files {
+            form.append_with_blob_and_filename("files", file, &file.name())?;
+        }
+
+        Ok(form)
+    }
+}
+
+/// Finds the first `http(s)` URL in shared text. Many Android apps share
+/// links as text ("Check this out https://...") rather than as `url`.
+pub fn extract_url(text: &str) -> Option<&str> {
+    text.split_whitespace()
+        .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+        .map(|url| url.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '"', '\'']))
+}
+
+#[cfg(test)]
+mod share_target_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn share_target() -> ShareTarget {
+        serde_json::from_str(
+            r#"{
+                "action": "/share",
+                "params": { "title": "name", "text": "description", "files": [{ "name": "media", "accept": ["image/*"] }] }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn params_test() {
+        let target = share_target();
+
+        assert_eq!(target.params.title.as_deref(), Some("name"));
+        // Params left out of the manifest keep their default name
+        assert_eq!(target.params.url.as_deref(), Some("url"));
+        assert_eq!(target.params.files[0].name, "media");
+
+        let defaults: ShareTarget = serde_json::from_str(r#"{ "action": "/share" }"#).unwrap();
+        assert_eq!(defaults.params, ShareParams::default());
+    }
+
+    #[test]
+    fn matches_test() {
+        let target = share_target();
+
+        assert!(target.matches("https://example.com/share", "POST"));
+        assert!(!target.matches("https://example.com/notes", "POST"));
+        assert!(!target.matches("https://example.com/share", "PUT"));
+    }
+
+    #[wasm_bindgen_test]
+    fn matches_query_test() {
+        let target = share_target();
+
+        assert!(target.matches("https://example.com/share?name=Hi&description=There", "GET"));
+        assert!(target.matches("https://example.com/share?na%6De=Hi", "GET"));
+        assert!(!target.matches("https://example.com/share", "GET"));
+        assert!(!target.matches("https://example.com/share?title=Hi", "GET"));
+    }
+
+    #[test]
+    fn redirect_url_test() {
+        let mut target = share_target();
+        assert_eq!(target.redirect_url("abc"), "/share?share-target=abc");
+
+        target.redirect = Some("/notes/new?draft=1".to_string());
+        assert_eq!(
+            target.redirect_url("abc"),
+            "/notes/new?draft=1&share-target=abc"
+        );
+    }
+
+    #[test]
+    fn extract_url_test() {
+        assert_eq!(
+            extract_url("Check this out: https://remix.run/docs."),
+            Some("https://remix.run/docs")
+        );
+        assert_eq!(
+            extract_url("(http://example.com/a?b=c)"),
+            None,
+            "URLs must start a word"
+        );
+        assert_eq!(extract_url("no links here"), None);
+    }
+}
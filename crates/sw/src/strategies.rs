@@ -0,0 +1,169 @@
+use js_sys::{Array, Promise};
+use serde::Deserialize;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+use web_sys::{FetchEvent, Request, Response};
+
+use crate::{
+    expiration::{stamp, Expiration},
+    scope::{fetch, global, match_request, open_cache, sleep},
+};
+
+/// Default cache for runtime strategies that don't name one.
+pub const DEFAULT_CACHE_NAME: &str = "remix-pwa-runtime";
+
+/// How a matched request should be answered.
+///
+/// [Strategies overview](https://developer.chrome.com/docs/workbox/caching-strategies-overview)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StrategyKind {
+    /// Serve from the cache, falling back to the network on a miss.
+    CacheFirst,
+    /// Try the network first, falling back to the cache when it fails or
+    /// takes longer than `networkTimeoutSeconds`.
+    NetworkFirst,
+    /// Serve from the cache straight away and refresh it in the background.
+    StaleWhileRevalidate,
+    NetworkOnly,
+    CacheOnly,
+}
+
+/// A caching strategy as passed to `Router.registerRoute`.
+///
+/// ## Example
+///
+/// ```js
+/// { strategy: "network-first", cacheName: "pages", networkTimeoutSeconds: 3, maxEntries: 50 }
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Strategy {
+    #[serde(rename = "strategy")]
+    pub kind: StrategyKind,
+    #[serde(default = "default_cache_name")]
+    pub cache_name: String,
+    pub network_timeout_seconds: Option<f64>,
+    #[serde(flatten)]
+    pub expiration: Expiration,
+}
+
+fn default_cache_name() -> String {
+    DEFAULT_CACHE_NAME.to_string()
+}
+
+impl Strategy {
+    pub fn new(kind: StrategyKind) -> Strategy {
+        Strategy {
+            kind,
+            cache_name: default_cache_name(),
+            network_timeout_seconds: None,
+            expiration: Expiration::default(),
+        }
+    }
+
+    /// Answers `request`. `event` is used to keep the worker alive while
+    /// stale-while-revalidate refreshes the cache in the background.
+    pub async fn handle(&self, request: Request, event: &FetchEvent) -> Result<Response, JsValue> {
+        match self.kind {
+            StrategyKind::CacheFirst => match self.cached(&request).await? {
+                Some(response) => Ok(response),
+                None => self.fetch_and_cache(&request).await,
+            },
+            StrategyKind::NetworkFirst => match self.fetch_and_cache(&request).await {
+                Ok(response) => Ok(response),
+                Err(e) => self.cached(&request).await?.ok_or(e),
+            },
+            StrategyKind::StaleWhileRevalidate => {
+                let cached = self.cached(&request).await?;
+                let strategy = self.clone();
+                let revalidate = future_to_promise(async move {
+                    strategy.fetch_and_cache(&request).await.map(JsValue::from)
+                });
+
+                match cached {
+                    Some(response) => {
+                        event.wait_until(&revalidate)?;
+                        Ok(response)
+                    }
+                    None => Ok(JsFuture::from(revalidate).await?.unchecked_into()),
+                }
+            }
+            StrategyKind::NetworkOnly => self.fetch_with_timeout(&request).await,
+            StrategyKind::CacheOnly => self.cached(&request).await?.ok_or_else(|| {
+                JsValue::from_str(&format!("No cached response for {}", request.url()))
+            }),
+        }
+    }
+
+    /// Looks `request` up in the strategy's cache, skipping expired entries.
+    async fn cached(&self, request: &Request) -> Result<Option<Response>, JsValue> {
+        let cache = open_cache(&self.cache_name).await?;
+
+        match match_request(&cache, request).await? {
+            Some(response) if !self.expiration.check(&cache, request, &response).await? => {
+                Ok(Some(response))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Fetches `request` and stores successful `GET` responses in the cache.
+    async fn fetch_and_cache(&self, request: &Request) -> Result<Response, JsValue> {
+        let response = self.fetch_with_timeout(request).await?;
+
+        if response.ok() && request.method() == "GET" {
+            let cache = open_cache(&self.cache_name).await?;
+            JsFuture::from(cache.put_with_request(request, &stamp(response.clone()?)?)).await?;
+            self.expiration.enforce(&cache).await?;
+        }
+
+        Ok(response)
+    }
+
+    async fn fetch_with_timeout(&self, request: &Request) -> Result<Response, JsValue> {
+        let timeout = match self.network_timeout_seconds {
+            Some(seconds) if self.kind == StrategyKind::NetworkFirst => seconds,
+            _ => return fetch(request).await,
+        };
+
+        let network = global().fetch_with_request(request);
+        let race = Promise::race(&Array::of2(&network, &sleep((timeout * 1000.0) as i32)));
+
+        JsFuture::from(race)
+            .await?
+            .dyn_into::<Response>()
+            .map_err(|_| JsValue::from_str(&format!("Network timed out after {}s", timeout)))
+    }
+}
+
+#[cfg(test)]
+mod strategies_tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_test() {
+        let strategy: Strategy = serde_json::from_str(
+            r#"{ "strategy": "network-first", "cacheName": "pages", "networkTimeoutSeconds": 3, "maxEntries": 20 }"#,
+        )
+        .unwrap();
+
+        assert_eq!(strategy.kind, StrategyKind::NetworkFirst);
+        assert_eq!(strategy.cache_name, "pages");
+        assert_eq!(strategy.network_timeout_seconds, Some(3.0));
+        assert_eq!(strategy.expiration.max_entries, Some(20));
+    }
+
+    #[test]
+    fn default_cache_name_test() {
+        let strategy: Strategy =
+            serde_json::from_str(r#"{ "strategy": "stale-while-revalidate" }"#).unwrap();
+
+        assert_eq!(strategy, Strategy::new(StrategyKind::StaleWhileRevalidate));
+    }
+
+    #[test]
+    fn unknown_strategy_test() {
+        assert!(serde_json::from_str::<Strategy>(r#"{ "strategy": "cache-maybe" }"#).is_err());
+    }
+}
@@ -7,6 +7,17 @@
 
 COMMAND="wasm-pack.exe build --target web --scope remix-pwa"
 
+# Crates with an `npm` feature (allocator, start function, ...) only get it
+# in the npm build, so it stays off for Rust crates depending on them
+pack() {
+  local crate=$(echo "$1" | tr -d '",')
+  if grep -q "^npm = " "$crate/Cargo.toml"; then
+    eval "$COMMAND $crate -- --features npm"
+  else
+    eval "$COMMAND $crate"
+  fi
+}
+
 # Install toml-cli & json for toml parsing
 npm install -g toml-cli
 npm install -g json
@@ -16,14 +27,18 @@ if [ "$1" == "--all" ] || [ "$1" == "" ]; then
   for value in "${VALUES[@]}"
   do
     if [ "$value" != "[" ] && [ "$value" != "]" ]; then
+        # Native crates (CLIs, etc.) aren't packed with wasm-pack
+        if ! grep -q "cdylib" "$(echo "$value" | tr -d '",')/Cargo.toml"; then
+          continue
+        fi
         echo "Packing $value and getting it ready..."
-        eval "$COMMAND $value"
+        pack "$value"
     fi
   done
 else
   for arg in "$@"
   do
     echo "Packing $arg. Hang on..."
-    eval "$COMMAND $arg"
+    pack "$arg"
   done
 fi